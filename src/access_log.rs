@@ -0,0 +1,64 @@
+use actix_web::{
+    body::MessageBody,
+    dev::{ServiceRequest, ServiceResponse},
+    http::header::{HeaderName, HeaderValue},
+    middleware::Next,
+};
+use secp256k1_zkp::rand::{self, RngCore};
+use serde::Serialize;
+use std::time::Instant;
+
+const REQUEST_ID: &str = "x-request-id";
+
+#[derive(Serialize)]
+struct AccessLogEntry<'a> {
+    method: &'a str,
+    path: &'a str,
+    status: u16,
+    latency_ms: f64,
+    client_ip: Option<&'a str>,
+    request_id: &'a str,
+}
+
+/// Logs every request as a JSON line to the `sibyls::access` target. Requests are tagged with
+/// the `X-Request-Id` sent by the client or else a random one, which is echoed in the response.
+pub async fn access_log(
+    req: ServiceRequest,
+    next: Next<impl MessageBody>,
+) -> Result<ServiceResponse<impl MessageBody>, actix_web::Error> {
+    let start = Instant::now();
+    let request_id = req
+        .headers()
+        .get(REQUEST_ID)
+        .and_then(|request_id| request_id.to_str().ok())
+        .map(ToString::to_string)
+        .unwrap_or_else(|| format!("{:016x}", rand::thread_rng().next_u64()));
+    let method = req.method().to_string();
+    let path = req.path().to_string();
+    let client_ip = req
+        .connection_info()
+        .realip_remote_addr()
+        .map(ToString::to_string);
+
+    let result = next.call(req).await;
+    let status = match &result {
+        Ok(res) => res.status(),
+        Err(err) => err.as_response_error().status_code(),
+    };
+    let entry = AccessLogEntry {
+        method: &method,
+        path: &path,
+        status: status.as_u16(),
+        latency_ms: start.elapsed().as_secs_f64() * 1000.0,
+        client_ip: client_ip.as_deref(),
+        request_id: &request_id,
+    };
+    info!(target: "sibyls::access", "{}", serde_json::to_string(&entry).unwrap());
+
+    let mut res = result?;
+    if let Ok(request_id) = HeaderValue::from_str(&request_id) {
+        res.headers_mut()
+            .insert(HeaderName::from_static(REQUEST_ID), request_id);
+    }
+    Ok(res)
+}
@@ -0,0 +1,364 @@
+use actix_web::{delete, get, post, web, HttpResponse};
+use serde::{Deserialize, Serialize};
+use sibyls::{
+    oracle::{oracle_scheduler, pricefeeds::PriceFeed, AuditAction, DbValue, Oracle},
+    AssetPair, SUPPORTED_BASES,
+};
+use std::{collections::HashMap, sync::Arc};
+use time::{format_description::well_known::Rfc3339, OffsetDateTime};
+
+use crate::{
+    error::SibylsError, get_announcement, maturation_key, resolve_maturation, ApiEventDescriptor,
+    Format,
+};
+
+/// Pricefeeds of each asset pair, used to aggregate the price of forced attestations
+pub struct AdminPriceFeeds(pub HashMap<AssetPair, Arc<Vec<Box<dyn PriceFeed + Send + Sync>>>>);
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct AdHocEventRequest {
+    /// asset pair whose event database stores the event, BTCUSD by default
+    asset_pair: Option<AssetPair>,
+    /// RFC3339-encoded time of maturation
+    maturation: String,
+    #[serde(default)]
+    event_id: String,
+    event_descriptor: ApiEventDescriptor,
+    /// reason recorded in the audit trail
+    reason: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+struct AdminFilters {
+    asset_pair: AssetPair,
+    /// outcome to attest instead of the price aggregated from the pricefeeds, the index of the
+    /// outcome for enum events
+    outcome: Option<i64>,
+    /// outcome of an enum event to attest, taking precedence over `outcome`
+    outcome_name: Option<String>,
+    /// reason recorded in the audit trail
+    reason: Option<String>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+struct SchedulerFilters {
+    /// asset pair to pause or resume, all of them if unset
+    asset_pair: Option<AssetPair>,
+    /// reason logged with the change
+    reason: Option<String>,
+}
+
+/// Whether the announcements of an asset pair are paused
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct SchedulerState {
+    asset_pair: AssetPair,
+    paused: bool,
+}
+
+impl Default for AdminFilters {
+    fn default() -> Self {
+        AdminFilters {
+            asset_pair: AssetPair::BTCUSD,
+            outcome: None,
+            outcome_name: None,
+            reason: None,
+        }
+    }
+}
+
+/// Records an operator action on the stored oracle event maturing at `maturation`
+fn audit(
+    oracle: &Oracle,
+    action: AuditAction,
+    maturation: &str,
+    reason: Option<String>,
+) -> Result<(), SibylsError> {
+    let event = oracle
+        .event_database
+        .get(maturation)?
+        .ok_or_else(|| SibylsError::OracleEventNotFoundError(maturation.to_string()))?;
+    let event: DbValue =
+        serde_json::from_slice(&event).map_err(|err| SibylsError::OracleError(err.into()))?;
+    Ok(oracle.record_audit(action, maturation, &event, reason)?)
+}
+
+/// Attests the oracle event with the given event id or RFC3339 maturation out of schedule with
+/// `outcome_name` or `outcome` if given, or else with the outcome determined like for scheduled
+/// attestations, and records it in the audit trail. Returns the maturation of the event.
+pub async fn force_attestation(
+    oracle: &Oracle,
+    pricefeeds: &[Box<dyn PriceFeed + Send + Sync>],
+    event_id: &str,
+    outcome: Option<i64>,
+    outcome_name: Option<&str>,
+    reason: Option<String>,
+) -> Result<String, SibylsError> {
+    let maturation = resolve_maturation(oracle, event_id)?;
+    let maturation_time = OffsetDateTime::parse(&maturation, &Rfc3339)?;
+    let outcome = match outcome_name {
+        Some(outcome_name) => Some(oracle_scheduler::enum_outcome_index(
+            oracle,
+            maturation_time,
+            outcome_name,
+        )?),
+        None => outcome,
+    };
+    let outcome =
+        oracle_scheduler::force_attestation(oracle, pricefeeds, maturation_time, outcome).await?;
+    warn!(
+        "forced attestation of {} oracle event with maturation {} to outcome {}",
+        oracle.asset_pair(),
+        maturation,
+        outcome
+    );
+    audit(oracle, AuditAction::ForceAttestation, &maturation, reason)?;
+    Ok(maturation)
+}
+
+#[post("/attest/{event_id}")]
+async fn attest(
+    oracles: web::Data<HashMap<AssetPair, Oracle>>,
+    pricefeeds: web::Data<AdminPriceFeeds>,
+    filters: web::Query<AdminFilters>,
+    path: web::Path<String>,
+) -> actix_web::Result<HttpResponse, actix_web::Error> {
+    info!("POST /admin/attest/{}: {:#?}", path, filters);
+    let (oracle, pricefeeds) = match (
+        oracles.get(&filters.asset_pair),
+        pricefeeds.0.get(&filters.asset_pair),
+    ) {
+        (Some(oracle), Some(pricefeeds)) => (oracle, pricefeeds),
+        _ => return Err(SibylsError::UnrecordedAssetPairError(filters.asset_pair).into()),
+    };
+
+    let maturation = force_attestation(
+        oracle,
+        pricefeeds,
+        &path,
+        filters.outcome,
+        filters.outcome_name.as_deref(),
+        filters.reason.clone(),
+    )
+    .await?;
+    Ok(HttpResponse::Ok().json(get_announcement(
+        &oracles,
+        filters.asset_pair,
+        &maturation,
+        Format::Hex,
+    )?))
+}
+
+#[post("/events")]
+async fn create_event(
+    oracles: web::Data<HashMap<AssetPair, Oracle>>,
+    request: web::Json<AdHocEventRequest>,
+) -> actix_web::Result<HttpResponse, actix_web::Error> {
+    info!("POST /admin/events: {:#?}", request);
+    let request = request.into_inner();
+    let asset_pair = request.asset_pair.unwrap_or(AssetPair::BTCUSD);
+    let oracle = match oracles.get(&asset_pair) {
+        None => return Err(SibylsError::UnrecordedAssetPairError(asset_pair).into()),
+        Some(val) => val,
+    };
+
+    match &request.event_descriptor {
+        ApiEventDescriptor::DigitDecomposition { base, .. } if !SUPPORTED_BASES.contains(base) => {
+            return Err(SibylsError::InvalidEventDescriptorError(format!(
+                "unsupported base {base}, supported bases are {SUPPORTED_BASES:?}"
+            ))
+            .into())
+        }
+        ApiEventDescriptor::DigitDecomposition { nb_digits: 0, .. } => {
+            return Err(SibylsError::InvalidEventDescriptorError(
+                "numeric events need at least one digit".to_string(),
+            )
+            .into())
+        }
+        ApiEventDescriptor::Enum { outcomes } if outcomes.is_empty() => {
+            return Err(SibylsError::InvalidEventDescriptorError(
+                "enum events need at least one outcome".to_string(),
+            )
+            .into())
+        }
+        _ => {}
+    }
+
+    let maturation = maturation_key(&request.maturation)?;
+    let maturation_time =
+        OffsetDateTime::parse(&maturation, &Rfc3339).map_err(SibylsError::from)?;
+    if maturation_time <= OffsetDateTime::now_utc() {
+        return Err(SibylsError::MaturationInPastError(maturation).into());
+    }
+
+    oracle_scheduler::create_adhoc_event(
+        oracle,
+        request.event_descriptor.into(),
+        request.event_id,
+        maturation_time,
+    )
+    .map_err(SibylsError::from)?;
+    warn!("created ad-hoc {asset_pair} oracle event with maturation {maturation}");
+    audit(
+        oracle,
+        AuditAction::CreateEvent,
+        &maturation,
+        request.reason,
+    )?;
+    Ok(HttpResponse::Created().json(get_announcement(
+        &oracles,
+        asset_pair,
+        &maturation,
+        Format::Hex,
+    )?))
+}
+
+#[delete("/events/{event_id}")]
+async fn delete_event(
+    oracles: web::Data<HashMap<AssetPair, Oracle>>,
+    filters: web::Query<AdminFilters>,
+    path: web::Path<String>,
+) -> actix_web::Result<HttpResponse, actix_web::Error> {
+    info!("DELETE /admin/events/{}: {:#?}", path, filters);
+    let oracle = match oracles.get(&filters.asset_pair) {
+        None => return Err(SibylsError::UnrecordedAssetPairError(filters.asset_pair).into()),
+        Some(val) => val,
+    };
+
+    let maturation = resolve_maturation(oracle, &path)?;
+    let event = oracle
+        .delete_event(&maturation)
+        .map_err(SibylsError::from)?;
+    warn!(
+        "deleted {} oracle event with maturation {}",
+        filters.asset_pair, maturation
+    );
+    oracle
+        .record_audit(
+            AuditAction::DeleteEvent,
+            &maturation,
+            &event,
+            filters.reason.clone(),
+        )
+        .map_err(SibylsError::from)?;
+    Ok(HttpResponse::NoContent().finish())
+}
+
+#[post("/events/{event_id}/cancel")]
+async fn cancel_event(
+    oracles: web::Data<HashMap<AssetPair, Oracle>>,
+    filters: web::Query<AdminFilters>,
+    path: web::Path<String>,
+) -> actix_web::Result<HttpResponse, actix_web::Error> {
+    info!("POST /admin/events/{}/cancel: {:#?}", path, filters);
+    let oracle = match oracles.get(&filters.asset_pair) {
+        None => return Err(SibylsError::UnrecordedAssetPairError(filters.asset_pair).into()),
+        Some(val) => val,
+    };
+
+    let maturation = resolve_maturation(oracle, &path)?;
+    let maturation_time =
+        OffsetDateTime::parse(&maturation, &Rfc3339).map_err(SibylsError::from)?;
+    match oracle_scheduler::cancel_event(oracle, maturation_time).map_err(SibylsError::from)? {
+        Some(outcome) => warn!(
+            "cancelled {} oracle event with maturation {}, attested cancellation outcome {}",
+            filters.asset_pair, maturation, outcome
+        ),
+        None => warn!(
+            "cancelled {} oracle event with maturation {}",
+            filters.asset_pair, maturation
+        ),
+    }
+    audit(
+        oracle,
+        AuditAction::CancelEvent,
+        &maturation,
+        filters.reason.clone(),
+    )?;
+    Ok(HttpResponse::Ok().json(get_announcement(
+        &oracles,
+        filters.asset_pair,
+        &maturation,
+        Format::Hex,
+    )?))
+}
+
+#[get("/audit")]
+async fn audit_trail(
+    oracles: web::Data<HashMap<AssetPair, Oracle>>,
+    filters: web::Query<AdminFilters>,
+) -> actix_web::Result<HttpResponse, actix_web::Error> {
+    info!("GET /admin/audit: {:#?}", filters);
+    let oracle = match oracles.get(&filters.asset_pair) {
+        None => return Err(SibylsError::UnrecordedAssetPairError(filters.asset_pair).into()),
+        Some(val) => val,
+    };
+    Ok(HttpResponse::Ok().json(oracle.audit_trail().map_err(SibylsError::from)?))
+}
+
+/// Returns whether the announcements of each asset pair are paused
+fn scheduler_states(oracles: &HashMap<AssetPair, Oracle>) -> Vec<SchedulerState> {
+    let mut states = oracles
+        .iter()
+        .map(|(asset_pair, oracle)| SchedulerState {
+            asset_pair: *asset_pair,
+            paused: oracle.is_paused(),
+        })
+        .collect::<Vec<_>>();
+    states.sort_by_key(|state| state.asset_pair.to_string());
+    states
+}
+
+/// Pauses or resumes the announcements of the asset pair selected by `filters`, or of all of
+/// them. Attestations of announced events go on regardless
+fn set_paused(
+    oracles: &HashMap<AssetPair, Oracle>,
+    filters: &SchedulerFilters,
+    paused: bool,
+) -> Result<Vec<SchedulerState>, SibylsError> {
+    let selected = match filters.asset_pair {
+        None => oracles.values().collect::<Vec<_>>(),
+        Some(asset_pair) => vec![oracles
+            .get(&asset_pair)
+            .ok_or(SibylsError::UnrecordedAssetPairError(asset_pair))?],
+    };
+    for oracle in selected {
+        oracle.set_paused(paused);
+        warn!(
+            "{} announcements of {} oracle events, reason: {}",
+            if paused { "paused" } else { "resumed" },
+            oracle.asset_pair(),
+            filters.reason.as_deref().unwrap_or("none given")
+        );
+    }
+    Ok(scheduler_states(oracles))
+}
+
+#[get("/scheduler")]
+async fn scheduler(
+    oracles: web::Data<HashMap<AssetPair, Oracle>>,
+) -> actix_web::Result<HttpResponse, actix_web::Error> {
+    info!("GET /admin/scheduler");
+    Ok(HttpResponse::Ok().json(scheduler_states(&oracles)))
+}
+
+#[post("/scheduler/pause")]
+async fn pause_scheduler(
+    oracles: web::Data<HashMap<AssetPair, Oracle>>,
+    filters: web::Query<SchedulerFilters>,
+) -> actix_web::Result<HttpResponse, actix_web::Error> {
+    info!("POST /admin/scheduler/pause: {:#?}", filters);
+    Ok(HttpResponse::Ok().json(set_paused(&oracles, &filters, true)?))
+}
+
+#[post("/scheduler/resume")]
+async fn resume_scheduler(
+    oracles: web::Data<HashMap<AssetPair, Oracle>>,
+    filters: web::Query<SchedulerFilters>,
+) -> actix_web::Result<HttpResponse, actix_web::Error> {
+    info!("POST /admin/scheduler/resume: {:#?}", filters);
+    Ok(HttpResponse::Ok().json(set_paused(&oracles, &filters, false)?))
+}
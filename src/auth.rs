@@ -0,0 +1,54 @@
+use actix_web::{
+    body::{EitherBody, MessageBody},
+    dev::{ServiceRequest, ServiceResponse},
+    http::header,
+    middleware::Next,
+    web, HttpRequest, HttpResponse,
+};
+use sibyls::ApiKey;
+
+/// API keys accepted on the `/admin` scope
+pub struct AdminApiKeys(pub Vec<ApiKey>);
+
+/// Rejects requests with `401 Unauthorized` unless they carry one of the [`AdminApiKeys`]
+/// registered as app data, either as `Authorization: Bearer <key>` or `X-API-Key: <key>`
+pub async fn require_api_key(
+    req: ServiceRequest,
+    next: Next<impl MessageBody>,
+) -> Result<ServiceResponse<EitherBody<impl MessageBody>>, actix_web::Error> {
+    let authorized = match (
+        req.app_data::<web::Data<AdminApiKeys>>(),
+        api_key(req.request()),
+    ) {
+        (Some(api_keys), Some(key)) => api_keys
+            .0
+            .iter()
+            .any(|api_key| constant_time_eq(api_key.0.as_bytes(), key.as_bytes())),
+        _ => false,
+    };
+    if !authorized {
+        warn!("unauthorized request to {}", req.path());
+        let response = HttpResponse::Unauthorized()
+            .insert_header((header::WWW_AUTHENTICATE, "Bearer"))
+            .finish();
+        return Ok(req.into_response(response).map_into_right_body());
+    }
+    next.call(req)
+        .await
+        .map(ServiceResponse::map_into_left_body)
+}
+
+fn api_key(req: &HttpRequest) -> Option<&str> {
+    if let Some(key) = req.headers().get("X-API-Key") {
+        return key.to_str().ok();
+    }
+    req.headers()
+        .get(header::AUTHORIZATION)?
+        .to_str()
+        .ok()?
+        .strip_prefix("Bearer ")
+}
+
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0, |acc, (x, y)| acc | (x ^ y)) == 0
+}
@@ -1,4 +1,6 @@
+use actix_web::http::StatusCode;
 use displaydoc::Display;
+use sibyls::oracle::{oracle_scheduler::OracleSchedulerError, storage::StorageError, OracleError};
 use thiserror::Error;
 
 #[allow(clippy::enum_variant_names)]
@@ -13,15 +15,87 @@ pub enum SibylsError {
     /// oracle event with maturation {0} not found
     OracleEventNotFoundError(String),
 
+    /// oracle event with event id {0} not found
+    OracleEventIdNotFoundError(String),
+
+    /// schedule {0} not recorded
+    UnrecordedScheduleError(String),
+
+    /// no upcoming oracle event for asset pair {0}
+    NoUpcomingOracleEventError(sibyls::AssetPair),
+
     /// database error: {0}
     DatabaseError(#[from] sled::Error),
+
+    /// storage error: {0}
+    StorageError(#[from] StorageError),
+
+    /// oracle error: {0}
+    OracleError(#[from] OracleError),
+
+    /// oracle scheduler error: {0}
+    OracleSchedulerError(#[from] OracleSchedulerError),
+
+    /// batch of {0} lookups exceeds maximum of {1}
+    BatchTooLargeError(usize, u32),
+
+    /// maturation {0} is not in the future
+    MaturationInPastError(String),
+
+    /// invalid event descriptor: {0}
+    InvalidEventDescriptorError(String),
+
+    /// no prices recorded for oracle event with maturation {0}
+    PriceSourcesNotFoundError(String),
+
+    /// no asset pairs recorded
+    NoAssetPairsError,
 }
 
 impl actix_web::error::ResponseError for SibylsError {
-    fn status_code(&self) -> actix_web::http::StatusCode {
-        if let SibylsError::DatetimeParseError(_) = self {
-            return actix_web::http::StatusCode::BAD_REQUEST;
+    fn status_code(&self) -> StatusCode {
+        match self {
+            SibylsError::DatetimeParseError(_)
+            | SibylsError::BatchTooLargeError(..)
+            | SibylsError::MaturationInPastError(_)
+            | SibylsError::InvalidEventDescriptorError(_)
+            | SibylsError::OracleSchedulerError(
+                OracleSchedulerError::InvalidOutcomeError(_)
+                | OracleSchedulerError::UnknownOutcomeError(_)
+                | OracleSchedulerError::OutcomeRequiredError(_),
+            ) => StatusCode::BAD_REQUEST,
+            SibylsError::UnrecordedAssetPairError(_)
+            | SibylsError::UnrecordedScheduleError(_)
+            | SibylsError::OracleEventNotFoundError(_)
+            | SibylsError::OracleEventIdNotFoundError(_)
+            | SibylsError::NoUpcomingOracleEventError(_)
+            | SibylsError::PriceSourcesNotFoundError(_)
+            | SibylsError::OracleSchedulerError(OracleSchedulerError::OracleEventNotFoundError(
+                _,
+            ))
+            | SibylsError::OracleError(OracleError::OracleEventNotFoundError(_)) => {
+                StatusCode::NOT_FOUND
+            }
+            SibylsError::OracleSchedulerError(
+                OracleSchedulerError::OracleEventAlreadyAttestedError(_)
+                | OracleSchedulerError::OracleEventCancelledError(_)
+                | OracleSchedulerError::OracleEventAlreadyExistsError(_)
+                | OracleSchedulerError::EventIdAlreadyExistsError(_),
+            )
+            | SibylsError::OracleError(
+                OracleError::OracleEventAlreadyAttestedError(_) | OracleError::NonceReuseError(_),
+            )
+            | SibylsError::OracleSchedulerError(OracleSchedulerError::OracleError(
+                OracleError::NonceReuseError(_),
+            )) => StatusCode::CONFLICT,
+            SibylsError::NoAssetPairsError
+            | SibylsError::OracleSchedulerError(
+                OracleSchedulerError::InsufficientPriceFeedsError(..),
+            ) => StatusCode::SERVICE_UNAVAILABLE,
+            SibylsError::DatabaseError(_)
+            | SibylsError::StorageError(_)
+            | SibylsError::OracleError(_)
+            | SibylsError::OracleSchedulerError(_) => StatusCode::INTERNAL_SERVER_ERROR,
         }
-        actix_web::http::StatusCode::INTERNAL_SERVER_ERROR
     }
 }
@@ -0,0 +1,94 @@
+use actix_web::{get, web, HttpResponse};
+use dlc_messages::oracle_msgs::OracleAttestation;
+use secp256k1_zkp::hashes::{sha256, Hash};
+use serde::Serialize;
+use sibyls::{
+    oracle::{tlv, Oracle},
+    AssetPair,
+};
+use std::collections::HashMap;
+
+use crate::{
+    error::SibylsError, get_announcement, list_announcements, resolve_maturation, ApiOracleEvent,
+    Filters, Format,
+};
+
+/// Oracle event in the schema expected by oracle explorers
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ExplorerEvent {
+    /// event id, or the RFC3339 maturation for events announced without one
+    event_name: String,
+    asset_pair: AssetPair,
+    /// hex-encoded sha256 hash of the announcement TLV
+    announcement_hash: String,
+    #[serde(rename = "announcementTLV")]
+    announcement_tlv: String,
+    #[serde(rename = "attestationTLV")]
+    attestation_tlv: Option<String>,
+    /// RFC3339-encoded time of maturation
+    maturation_time: String,
+    /// attested outcome strings, one per digit
+    outcomes: Option<Vec<String>>,
+}
+
+impl From<ApiOracleEvent> for ExplorerEvent {
+    fn from(event: ApiOracleEvent) -> Self {
+        let announcement_bytes = hex::decode(&event.announcement).unwrap();
+        let outcomes = event.attestation.as_ref().map(|attestation| {
+            let attestation: OracleAttestation = tlv::decode(&hex::decode(attestation).unwrap())
+                .expect("stored attestations to be valid TLV");
+            attestation.outcomes
+        });
+        let event_id = event
+            .decoded
+            .map(|decoded| decoded.event_id)
+            .unwrap_or_default();
+        ExplorerEvent {
+            event_name: if event_id.is_empty() {
+                event.maturation.clone()
+            } else {
+                event_id
+            },
+            asset_pair: event.asset_pair,
+            announcement_hash: sha256::Hash::hash(&announcement_bytes).to_string(),
+            announcement_tlv: event.announcement,
+            attestation_tlv: event.attestation,
+            maturation_time: event.maturation,
+            outcomes,
+        }
+    }
+}
+
+#[get("/announcements")]
+async fn announcements(
+    oracles: web::Data<HashMap<AssetPair, Oracle>>,
+    filters: web::Query<Filters>,
+) -> actix_web::Result<HttpResponse, actix_web::Error> {
+    info!("GET /v2/announcements: {:#?}", filters);
+    let mut filters = filters.into_inner();
+    // event ids are only part of decoded announcements
+    filters.format = Format::Decoded;
+    let events = list_announcements(&oracles, &filters)?
+        .events
+        .into_iter()
+        .map(ExplorerEvent::from)
+        .collect::<Vec<_>>();
+    Ok(HttpResponse::Ok().json(events))
+}
+
+#[get("/announcements/{event_name}")]
+async fn announcement(
+    oracles: web::Data<HashMap<AssetPair, Oracle>>,
+    filters: web::Query<Filters>,
+    path: web::Path<String>,
+) -> actix_web::Result<HttpResponse, actix_web::Error> {
+    info!("GET /v2/announcements/{}: {:#?}", path, filters);
+    let oracle = match oracles.get(&filters.asset_pair) {
+        None => return Err(SibylsError::UnrecordedAssetPairError(filters.asset_pair).into()),
+        Some(val) => val,
+    };
+    let maturation = resolve_maturation(oracle, &path)?;
+    let event = get_announcement(&oracles, filters.asset_pair, &maturation, Format::Decoded)?;
+    Ok(HttpResponse::Ok().json(ExplorerEvent::from(event)))
+}
@@ -0,0 +1,163 @@
+use sibyls::{
+    oracle::{storage, DbValue, Oracle},
+    AssetPair,
+};
+use std::collections::HashMap;
+use time::{format_description::well_known::Rfc3339, OffsetDateTime};
+use tonic::{Request, Response, Status};
+
+use proto::sibyls_server::{Sibyls, SibylsServer};
+use proto::{
+    GetAnnouncementRequest, GetAttestationRequest, GetAttestationResponse, GetPublicKeyRequest,
+    GetPublicKeyResponse, ListAnnouncementsRequest, ListAnnouncementsResponse, OracleEvent,
+};
+
+pub mod proto {
+    tonic::include_proto!("sibyls.v1");
+}
+
+const LIST_LIMIT: u32 = 100;
+
+pub fn server(oracles: HashMap<AssetPair, Oracle>) -> SibylsServer<SibylsService> {
+    SibylsServer::new(SibylsService { oracles })
+}
+
+pub struct SibylsService {
+    oracles: HashMap<AssetPair, Oracle>,
+}
+
+impl SibylsService {
+    fn oracle(&self, asset_pair: &str) -> Result<(AssetPair, &Oracle), Status> {
+        let asset_pair = if asset_pair.is_empty() {
+            AssetPair::BTCUSD
+        } else {
+            AssetPair::from_name(asset_pair).ok_or_else(|| {
+                Status::invalid_argument(format!("unknown asset pair {asset_pair}"))
+            })?
+        };
+        self.oracles
+            .get(&asset_pair)
+            .map(|oracle| (asset_pair, oracle))
+            .ok_or_else(|| Status::not_found(format!("asset pair {asset_pair} not recorded")))
+    }
+
+    fn db_value(&self, asset_pair: &str, maturation: &str) -> Result<(AssetPair, DbValue), Status> {
+        OffsetDateTime::parse(maturation, &Rfc3339).map_err(|err| {
+            Status::invalid_argument(format!("datetime RFC3339 parsing error: {err}"))
+        })?;
+        let (asset_pair, oracle) = self.oracle(asset_pair)?;
+        let event = oracle
+            .event_database
+            .get(maturation)
+            .map_err(|err| Status::internal(format!("database error: {err}")))?
+            .ok_or_else(|| {
+                Status::not_found(format!(
+                    "oracle event with maturation {maturation} not found"
+                ))
+            })?;
+        let event = oracle.published(maturation.as_bytes(), parse_db_value(&event)?);
+        Ok((asset_pair, event))
+    }
+}
+
+fn parse_db_value(event: &[u8]) -> Result<DbValue, Status> {
+    serde_json::from_slice(event)
+        .map_err(|err| Status::internal(format!("json deserialization error: {err}")))
+}
+
+fn oracle_event(asset_pair: AssetPair, maturation: String, event: DbValue) -> OracleEvent {
+    OracleEvent {
+        asset_pair: asset_pair.to_string(),
+        announcement: event.1,
+        attestation: event.2,
+        maturation,
+        outcome: event.3,
+        cancelled: event.5,
+    }
+}
+
+#[tonic::async_trait]
+impl Sibyls for SibylsService {
+    async fn get_announcement(
+        &self,
+        request: Request<GetAnnouncementRequest>,
+    ) -> Result<Response<OracleEvent>, Status> {
+        let request = request.into_inner();
+        info!("gRPC GetAnnouncement: {:#?}", request);
+        let (asset_pair, event) = self.db_value(&request.asset_pair, &request.maturation)?;
+        Ok(Response::new(oracle_event(
+            asset_pair,
+            request.maturation,
+            event,
+        )))
+    }
+
+    async fn list_announcements(
+        &self,
+        request: Request<ListAnnouncementsRequest>,
+    ) -> Result<Response<ListAnnouncementsResponse>, Status> {
+        let request = request.into_inner();
+        info!("gRPC ListAnnouncements: {:#?}", request);
+        let (asset_pair, oracle) = self.oracle(&request.asset_pair)?;
+        let limit = match request.limit {
+            0 => LIST_LIMIT,
+            limit => limit.min(LIST_LIMIT),
+        };
+
+        let entries = oracle
+            .event_database
+            .iter()
+            .map_err(|err| Status::internal(format!("database error: {err}")))?;
+        let entries: Box<dyn Iterator<Item = storage::Result<(String, Vec<u8>)>>> =
+            if request.reverse {
+                Box::new(entries.rev())
+            } else {
+                Box::new(entries)
+            };
+        let events = entries
+            .skip(request.offset as usize)
+            .take(limit as usize)
+            .map(|entry| {
+                let (maturation, event) =
+                    entry.map_err(|err| Status::internal(format!("database error: {err}")))?;
+                let event = oracle.published(maturation.as_bytes(), parse_db_value(&event)?);
+                Ok(oracle_event(asset_pair, maturation, event))
+            })
+            .collect::<Result<Vec<_>, Status>>()?;
+        Ok(Response::new(ListAnnouncementsResponse { events }))
+    }
+
+    async fn get_attestation(
+        &self,
+        request: Request<GetAttestationRequest>,
+    ) -> Result<Response<GetAttestationResponse>, Status> {
+        let request = request.into_inner();
+        info!("gRPC GetAttestation: {:#?}", request);
+        let (_, event) = self.db_value(&request.asset_pair, &request.maturation)?;
+        match (event.2, event.3) {
+            (Some(attestation), Some(outcome)) => Ok(Response::new(GetAttestationResponse {
+                attestation,
+                outcome,
+            })),
+            _ => Err(Status::not_found(format!(
+                "oracle event with maturation {} not attested yet",
+                request.maturation
+            ))),
+        }
+    }
+
+    async fn get_public_key(
+        &self,
+        _: Request<GetPublicKeyRequest>,
+    ) -> Result<Response<GetPublicKeyResponse>, Status> {
+        info!("gRPC GetPublicKey");
+        let oracle = self
+            .oracles
+            .values()
+            .next()
+            .ok_or_else(|| Status::internal("no asset pairs recorded"))?;
+        Ok(Response::new(GetPublicKeyResponse {
+            public_key: oracle.public_key().serialize().to_vec(),
+        }))
+    }
+}
@@ -2,6 +2,7 @@ pub mod common;
 
 pub use common::*;
 
+pub mod metrics;
 pub mod oracle;
 
 pub use oracle::oracle_scheduler::{build_announcement, build_attestation};
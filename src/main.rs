@@ -1,49 +1,122 @@
 #[macro_use]
 extern crate log;
 
-use actix_web::{get, web, App, HttpResponse, HttpServer};
-use clap::Parser;
+use actix_cors::Cors;
+use actix_web::{
+    get,
+    http::{
+        header::{self, CacheDirective, EntityTag},
+        Method,
+    },
+    middleware::{from_fn, Compress, Condition},
+    post, web, App, HttpMessage, HttpRequest, HttpResponse, HttpServer,
+};
+use actix_web_prom::PrometheusMetricsBuilder;
+use clap::{Parser, Subcommand};
+use dlc_messages::oracle_msgs::{
+    DigitDecompositionEventDescriptor, EnumEventDescriptor, EventDescriptor, OracleAnnouncement,
+};
+use futures::StreamExt;
 use hex::ToHex;
-use secp256k1_zkp::{rand, KeyPair, Secp256k1, SecretKey};
+use secp256k1_zkp::{
+    hashes::{sha256, Hash},
+    rand, KeyPair, Secp256k1, SecretKey,
+};
 use serde::{Deserialize, Serialize};
-use sibyls::oracle::pricefeeds::create_price_feeds;
-use sled::IVec;
+use sibyls::oracle::pricefeeds::{
+    create_price_feeds, cross_rate_feeds, stream_prices, PriceFeed, PriceFeedStatus,
+};
 use std::process::exit;
 use std::{
     collections::HashMap,
     env,
     fs::{self, File},
-    io::Read,
+    io::{BufReader, Read},
+    net::{IpAddr, Ipv4Addr, SocketAddr},
+    ops::Bound,
     str::FromStr,
+    sync::Arc,
+    time::SystemTime,
 };
-use time::{format_description::well_known::Rfc3339, Duration, OffsetDateTime};
+use time::{format_description::well_known::Rfc3339, OffsetDateTime, UtcOffset};
+use tokio::sync::broadcast::{self, error::RecvError};
+use utoipa::{IntoParams, OpenApi, ToSchema};
+use utoipa_swagger_ui::SwaggerUi;
 
 use sibyls::{
-    oracle::{oracle_scheduler, DbValue, Oracle},
-    AssetPair, AssetPairInfo, OracleConfig,
+    oracle::{
+        oracle_scheduler, storage, DbValue, EventType, FailedAttestation, Oracle,
+        OracleEventUpdate, PriceSources,
+    },
+    AssetPair, AssetPairInfo, DatabaseConfig, OracleConfig, TlsConfig,
 };
 
 #[cfg(not(feature = "test-feed"))]
-use sibyls::oracle::pricefeeds::ALL_PRICE_FEEDS;
+use sibyls::oracle::pricefeeds::{FeedId, ALL_PRICE_FEEDS};
 
+mod access_log;
+mod admin;
+mod auth;
 mod error;
 use error::SibylsError;
 
+mod explorer;
+mod grpc;
+mod rate_limit;
+mod rpc;
+mod tor;
+
 const PAGE_SIZE: u32 = 100;
+const MAX_PAGE_SIZE: u32 = 1000;
+const DEFAULT_BIND: IpAddr = IpAddr::V4(Ipv4Addr::LOCALHOST);
+const DEFAULT_PORT: u16 = 8080;
+const EVENT_UPDATES_CAPACITY: usize = 64;
+/// `max-age` of attested oracle events, which never change anymore
+const IMMUTABLE_MAX_AGE: u32 = 365 * 24 * 60 * 60;
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, ToSchema)]
 #[serde(rename_all = "camelCase")]
 enum SortOrder {
     Insertion,
     ReverseInsertion,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Clone, Copy, Debug, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+enum Format {
+    Hex,
+    Decoded,
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
 #[serde(default, rename_all = "camelCase")]
+#[into_params(parameter_in = Query)]
 struct Filters {
+    /// sort order, `reverseInsertion` is most recent first
     sort_by: SortOrder,
+    /// page to start retrieval from, in units of `limit`
     page: u32,
+    /// maximum number of oracle events to return, capped at 1000
+    limit: u32,
+    /// number of oracle events to skip in addition to `page`
+    offset: u32,
+    /// only return oracle events maturing at or after this RFC3339 time
+    from: Option<String>,
+    /// only return oracle events maturing at or before this RFC3339 time
+    to: Option<String>,
+    /// only return attested (`true`) or pending (`false`) oracle events
+    attested: Option<bool>,
+    /// only return numeric or enum oracle events
+    event_type: Option<EventType>,
+    /// asset pair
     asset_pair: AssetPair,
+    /// additional schedule of the asset pair, its main schedule by default
+    schedule: Option<String>,
+    /// `decoded` additionally returns the parsed announcement
+    format: Format,
+    /// also count the oracle events matching the filters across all pages, which walks all of
+    /// them
+    total: bool,
 }
 
 impl Default for Filters {
@@ -51,175 +124,1332 @@ impl Default for Filters {
         Filters {
             sort_by: SortOrder::ReverseInsertion,
             page: 0,
+            limit: PAGE_SIZE,
+            offset: 0,
+            from: None,
+            to: None,
+            attested: None,
+            event_type: None,
             asset_pair: AssetPair::BTCUSD,
+            schedule: None,
+            format: Format::Hex,
+            total: false,
         }
     }
 }
 
-#[derive(Serialize)]
+#[derive(Debug, Deserialize, ToSchema)]
+#[serde(default, rename_all = "camelCase")]
+struct BatchRequest {
+    /// asset pair
+    asset_pair: AssetPair,
+    /// additional schedule of the asset pair, its main schedule by default
+    schedule: Option<String>,
+    /// RFC3339-encoded maturations of the oracle events to look up
+    maturations: Vec<String>,
+    /// event ids of the oracle events to look up
+    event_ids: Vec<String>,
+    /// `decoded` additionally returns the parsed announcements
+    format: Format,
+}
+
+impl Default for BatchRequest {
+    fn default() -> Self {
+        BatchRequest {
+            asset_pair: AssetPair::BTCUSD,
+            schedule: None,
+            maturations: Vec::new(),
+            event_ids: Vec::new(),
+            format: Format::Hex,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+#[serde(default, rename_all = "camelCase")]
+#[into_params(parameter_in = Query)]
+struct LatestFilters {
+    /// only return the oracle event of this asset pair, all asset pairs by default
+    asset_pair: Option<AssetPair>,
+    /// `decoded` additionally returns the parsed announcements
+    format: Format,
+}
+
+impl Default for LatestFilters {
+    fn default() -> Self {
+        LatestFilters {
+            asset_pair: None,
+            format: Format::Hex,
+        }
+    }
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+struct StreamFilters {
+    #[serde(alias = "asset_pair")]
+    asset_pair: Option<AssetPair>,
+}
+
+#[derive(Serialize, ToSchema)]
 struct ApiOracleEvent {
     asset_pair: AssetPair,
+    /// hex-encoded TLV of `oracle_announcement`
     announcement: String,
+    /// hex-encoded TLV of `oracle_attestation`, if exists
     attestation: Option<String>,
+    /// RFC3339-encoded time of maturation (attestation)
+    #[schema(example = "2022-05-31T08:00:00Z")]
     maturation: String,
-    outcome: Option<u64>,
+    /// numeric events attest a price, enum events one of their outcomes
+    event_type: EventType,
+    /// outcome value, if exists, the index of the outcome for enum events
+    outcome: Option<i64>,
+    /// attested outcome of enum events
+    #[serde(skip_serializing_if = "Option::is_none")]
+    outcome_name: Option<String>,
+    /// whether the oracle event was cancelled by the operator, `outcome` being its cancellation
+    /// outcome if attested
+    cancelled: bool,
+    /// parsed announcement, if requested with `format=decoded`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    decoded: Option<ApiDecodedAnnouncement>,
+}
+
+#[derive(Serialize, ToSchema)]
+struct ApiAnnouncementsPage {
+    events: Vec<ApiOracleEvent>,
+    /// number of oracle events matching the filters, across all pages, if requested with `total`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    total: Option<usize>,
+    /// requested page
+    page: u32,
+    /// maximum number of oracle events per page, after capping `limit`
+    page_size: u32,
+    /// RFC3339 maturation of the first oracle event of the next page, if any;
+    /// pass it as `from` (`insertion`) or `to` (`reverseInsertion`) to resume from there
+    #[schema(example = "2022-05-30T08:00:00Z")]
+    next: Option<String>,
+}
+
+#[derive(Serialize, ToSchema)]
+struct ApiDecodedAnnouncement {
+    event_id: String,
+    /// hex-encoded x-only public key of the oracle
+    oracle_public_key: String,
+    /// hex-encoded x-only nonces, one per digit
+    nonces: Vec<String>,
+    /// maturation as UNIX timestamp
+    maturity_epoch: u32,
+    event_descriptor: ApiEventDescriptor,
+}
+
+#[derive(Debug, Deserialize, Serialize, ToSchema)]
+#[serde(tag = "type", rename_all = "snake_case")]
+enum ApiEventDescriptor {
+    DigitDecomposition {
+        base: u16,
+        is_signed: bool,
+        unit: String,
+        precision: i32,
+        nb_digits: u16,
+    },
+    Enum {
+        outcomes: Vec<String>,
+    },
+}
+
+impl From<&OracleAnnouncement> for ApiDecodedAnnouncement {
+    fn from(oracle_announcement: &OracleAnnouncement) -> Self {
+        let oracle_event = &oracle_announcement.oracle_event;
+        ApiDecodedAnnouncement {
+            event_id: oracle_event.event_id.clone(),
+            oracle_public_key: oracle_announcement.oracle_public_key.to_string(),
+            nonces: oracle_event
+                .oracle_nonces
+                .iter()
+                .map(ToString::to_string)
+                .collect(),
+            maturity_epoch: oracle_event.event_maturity_epoch,
+            event_descriptor: (&oracle_event.event_descriptor).into(),
+        }
+    }
+}
+
+impl From<&EventDescriptor> for ApiEventDescriptor {
+    fn from(event_descriptor: &EventDescriptor) -> Self {
+        match event_descriptor {
+            EventDescriptor::DigitDecompositionEvent(descriptor) => {
+                ApiEventDescriptor::DigitDecomposition {
+                    base: descriptor.base,
+                    is_signed: descriptor.is_signed,
+                    unit: descriptor.unit.clone(),
+                    precision: descriptor.precision,
+                    nb_digits: descriptor.nb_digits,
+                }
+            }
+            EventDescriptor::EnumEvent(descriptor) => ApiEventDescriptor::Enum {
+                outcomes: descriptor.outcomes.clone(),
+            },
+        }
+    }
 }
 
+impl From<ApiEventDescriptor> for EventDescriptor {
+    fn from(descriptor: ApiEventDescriptor) -> Self {
+        match descriptor {
+            ApiEventDescriptor::DigitDecomposition {
+                base,
+                is_signed,
+                unit,
+                precision,
+                nb_digits,
+            } => EventDescriptor::DigitDecompositionEvent(DigitDecompositionEventDescriptor {
+                base,
+                is_signed,
+                unit,
+                precision,
+                nb_digits,
+            }),
+            ApiEventDescriptor::Enum { outcomes } => {
+                EventDescriptor::EnumEvent(EnumEventDescriptor { outcomes })
+            }
+        }
+    }
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+struct ApiUpcomingOracleEvent {
+    #[serde(flatten)]
+    event: ApiOracleEvent,
+    /// seconds until maturation (attestation)
+    seconds_remaining: i64,
+}
+
+/// Returns the oracle event of a database entry of `oracle` as published
 fn parse_database_entry(
+    oracle: &Oracle,
+    format: Format,
+    (maturation, event): (String, Vec<u8>),
+) -> ApiOracleEvent {
+    let event: DbValue = serde_json::from_slice(&event).unwrap();
+    let event = oracle.published(maturation.as_bytes(), event);
+    api_oracle_event(oracle.asset_pair(), format, &maturation, event)
+}
+
+fn api_oracle_event(
     asset_pair: AssetPair,
-    (maturation, event): (IVec, IVec),
+    format: Format,
+    maturation: &str,
+    event: DbValue,
 ) -> ApiOracleEvent {
-    let maturation = String::from_utf8_lossy(&maturation).to_string();
-    let event: DbValue = serde_json::from_str(&String::from_utf8_lossy(&event)).unwrap();
+    let maturation = maturation.to_string();
+    let event_type = event.event_type();
+    let outcome_name = event.outcome_name();
+    let decoded = match format {
+        Format::Hex => None,
+        Format::Decoded => Some((&event.announcement()).into()),
+    };
     ApiOracleEvent {
         asset_pair,
         announcement: event.1.encode_hex::<String>(),
         attestation: event.2.map(|att| att.encode_hex::<String>()),
         maturation,
+        event_type,
         outcome: event.3,
+        outcome_name,
+        cancelled: event.5,
+        decoded,
     }
 }
 
+/// Returns the database key of the oracle event maturing at the given RFC3339 time
+fn maturation_key(rfc3339_time: &str) -> Result<String, SibylsError> {
+    let maturation = OffsetDateTime::parse(rfc3339_time, &Rfc3339)?;
+    // keys are stored in UTC without fractional seconds, so they sort chronologically
+    let maturation = maturation
+        .to_offset(UtcOffset::UTC)
+        .replace_nanosecond(0)
+        .unwrap();
+    Ok(maturation.format(&Rfc3339).unwrap())
+}
+
+/// Responds with a single oracle event, or just its binary announcement TLV if the client
+/// prefers `application/octet-stream`. Responses are tagged with an `ETag` of their body so
+/// clients can revalidate them with `If-None-Match`. Announcements and attested events are
+/// immutable and cached for good, pending events have to be revalidated since their
+/// attestation is still to come.
+fn event_response(req: &HttpRequest, event: &ApiOracleEvent) -> HttpResponse {
+    let binary = req.get_header::<header::Accept>().is_some_and(|accept| {
+        accept
+            .ranking()
+            .first()
+            .is_some_and(|mime| mime.essence_str() == "application/octet-stream")
+    });
+    let (body, content_type) = if binary {
+        (
+            hex::decode(&event.announcement).unwrap(),
+            header::ContentType::octet_stream(),
+        )
+    } else {
+        (
+            serde_json::to_vec(event).unwrap(),
+            header::ContentType::json(),
+        )
+    };
+    let etag = EntityTag::new_strong(sha256::Hash::hash(&body).to_string());
+    let not_modified = match req.get_header::<header::IfNoneMatch>() {
+        Some(header::IfNoneMatch::Any) => true,
+        Some(header::IfNoneMatch::Items(etags)) => etags.iter().any(|tag| tag.weak_eq(&etag)),
+        None => false,
+    };
+
+    let mut response = if not_modified {
+        HttpResponse::NotModified()
+    } else {
+        HttpResponse::Ok()
+    };
+    response
+        .insert_header(header::ETag(etag))
+        .insert_header((header::VARY, "Accept"));
+    if !binary && event.attestation.is_some() {
+        // attestations are signed at maturation
+        if let Ok(maturation) = OffsetDateTime::parse(&event.maturation, &Rfc3339) {
+            response.insert_header(header::LastModified(SystemTime::from(maturation).into()));
+        }
+    }
+    if binary || event.attestation.is_some() {
+        response.insert_header(header::CacheControl(vec![
+            CacheDirective::Public,
+            CacheDirective::MaxAge(IMMUTABLE_MAX_AGE),
+            CacheDirective::Extension("immutable".to_string(), None),
+        ]));
+    } else {
+        response.insert_header(header::CacheControl(vec![CacheDirective::NoCache]));
+    }
+
+    if not_modified {
+        response.finish()
+    } else {
+        response.content_type(content_type).body(body)
+    }
+}
+
+/// Returns the oracle of the asset pair, or of its additional schedule if one is given
+fn schedule_oracle<'a>(
+    oracles: &'a HashMap<AssetPair, Oracle>,
+    asset_pair: AssetPair,
+    schedule: Option<&str>,
+) -> Result<&'a Oracle, SibylsError> {
+    let oracle = match oracles.get(&asset_pair) {
+        None => return Err(SibylsError::UnrecordedAssetPairError(asset_pair)),
+        Some(val) => val,
+    };
+    match schedule {
+        None => Ok(oracle),
+        Some(schedule) => oracle
+            .schedule(schedule)
+            .ok_or_else(|| SibylsError::UnrecordedScheduleError(schedule.to_string())),
+    }
+}
+
+fn list_announcements(
+    oracles: &HashMap<AssetPair, Oracle>,
+    filters: &Filters,
+) -> Result<ApiAnnouncementsPage, SibylsError> {
+    let oracle = schedule_oracle(oracles, filters.asset_pair, filters.schedule.as_deref())?;
+
+    let page_size = filters.limit.min(MAX_PAGE_SIZE);
+    let limit = page_size as usize;
+    let skip = filters.page as usize * limit + filters.offset as usize;
+    info!("retrieving {} oracle events skipping {}", limit, skip);
+
+    let from = match &filters.from {
+        None => Bound::Unbounded,
+        Some(from) => Bound::Included(maturation_key(from)?),
+    };
+    let to = match &filters.to {
+        None => Bound::Unbounded,
+        Some(to) => Bound::Included(maturation_key(to)?),
+    };
+
+    let entries = oracle.event_database.range((from, to))?;
+    let entries: Box<dyn Iterator<Item = storage::Result<(String, Vec<u8>)>>> =
+        match filters.sort_by {
+            SortOrder::Insertion => Box::new(entries),
+            SortOrder::ReverseInsertion => Box::new(entries.rev()),
+        };
+    let matching = entries
+        .map(|entry| {
+            let (maturation, event) = entry?;
+            let event: DbValue = serde_json::from_slice(&event)
+                .map_err(|err| SibylsError::OracleError(err.into()))?;
+            let event = oracle.published(maturation.as_bytes(), event);
+            Ok::<_, SibylsError>((maturation, event))
+        })
+        .filter(|entry| match (filters.attested, entry) {
+            (Some(attested), Ok((_, event))) => event.2.is_some() == attested,
+            _ => true,
+        })
+        .filter(|entry| match (filters.event_type, entry) {
+            (Some(event_type), Ok((_, event))) => event.event_type() == event_type,
+            _ => true,
+        });
+
+    // the walk stops at the first oracle event of the next page, unless the matching oracle events
+    // are counted, and only those of the page are decoded
+    let mut events = Vec::with_capacity(limit);
+    let mut next = None;
+    let mut total = 0;
+    for entry in matching {
+        let (maturation, event) = entry?;
+        if total >= skip && events.len() < limit {
+            events.push(api_oracle_event(
+                filters.asset_pair,
+                filters.format,
+                &maturation,
+                event,
+            ));
+        } else if total == skip + limit {
+            next = Some(maturation);
+            if !filters.total {
+                break;
+            }
+        }
+        total += 1;
+    }
+    Ok(ApiAnnouncementsPage {
+        events,
+        total: filters.total.then_some(total),
+        page: filters.page,
+        page_size,
+        next,
+    })
+}
+
+#[utoipa::path(
+    get,
+    context_path = "/v1",
+    path = "/announcements",
+    params(Filters),
+    responses(
+        (status = 200, description = "page of oracle events", body = ApiAnnouncementsPage),
+        (status = 400, description = "invalid RFC3339 time"),
+        (status = 404, description = "asset pair not recorded"),
+        (status = 500, description = "database error"),
+    )
+)]
 #[get("/announcements")]
 async fn announcements(
     oracles: web::Data<HashMap<AssetPair, Oracle>>,
     filters: web::Query<Filters>,
 ) -> actix_web::Result<HttpResponse, actix_web::Error> {
     info!("GET /announcements: {:#?}", filters);
-    let oracle = match oracles.get(&filters.asset_pair) {
-        None => return Err(SibylsError::UnrecordedAssetPairError(filters.asset_pair).into()),
+    Ok(HttpResponse::Ok().json(list_announcements(&oracles, &filters)?))
+}
+
+/// Returns the most recently attested oracle event of the given oracle, if any
+fn latest_attested_event(
+    oracle: &Oracle,
+    format: Format,
+) -> Result<Option<ApiOracleEvent>, SibylsError> {
+    // announcements are created ahead of their maturation, so skip the pending ones
+    for entry in oracle.event_database.iter()?.rev() {
+        let event = parse_database_entry(oracle, format, entry?);
+        if event.attestation.is_some() {
+            return Ok(Some(event));
+        }
+    }
+    Ok(None)
+}
+
+#[utoipa::path(
+    get,
+    context_path = "/v1",
+    path = "/announcements/latest",
+    params(LatestFilters),
+    responses(
+        (status = 200, description = "most recently attested oracle event of each asset pair", body = [ApiOracleEvent]),
+        (status = 404, description = "asset pair not recorded"),
+        (status = 500, description = "database error"),
+    )
+)]
+#[get("/announcements/latest")]
+async fn latest_announcements(
+    oracles: web::Data<HashMap<AssetPair, Oracle>>,
+    filters: web::Query<LatestFilters>,
+) -> actix_web::Result<HttpResponse, actix_web::Error> {
+    info!("GET /announcements/latest: {:#?}", filters);
+    if let Some(asset_pair) = filters.asset_pair {
+        if !oracles.contains_key(&asset_pair) {
+            return Err(SibylsError::UnrecordedAssetPairError(asset_pair).into());
+        }
+    }
+
+    let mut events = Vec::with_capacity(oracles.len());
+    for (asset_pair, oracle) in oracles.iter() {
+        if filters
+            .asset_pair
+            .is_none_or(|filter| filter == *asset_pair)
+        {
+            events.extend(latest_attested_event(oracle, filters.format)?);
+        }
+    }
+    events.sort_by_key(|event| event.asset_pair.to_string());
+    Ok(HttpResponse::Ok().json(events))
+}
+
+/// Returns the next oracle event of the given asset pair that is still to be attested
+fn get_next_announcement(
+    oracles: &HashMap<AssetPair, Oracle>,
+    asset_pair: AssetPair,
+    format: Format,
+) -> Result<ApiUpcomingOracleEvent, SibylsError> {
+    let oracle = match oracles.get(&asset_pair) {
+        None => return Err(SibylsError::UnrecordedAssetPairError(asset_pair)),
         Some(val) => val,
     };
 
-    if oracle.event_database.is_empty() {
+    let now = OffsetDateTime::now_utc();
+    let now_key = maturation_key(&now.format(&Rfc3339).unwrap())?;
+    for entry in oracle
+        .event_database
+        .range((Bound::Included(now_key), Bound::Unbounded))?
+    {
+        let event = parse_database_entry(oracle, format, entry?);
+        if event.attestation.is_some() {
+            continue;
+        }
+        let maturation = OffsetDateTime::parse(&event.maturation, &Rfc3339)?;
+        return Ok(ApiUpcomingOracleEvent {
+            seconds_remaining: (maturation - now).whole_seconds().max(0),
+            event,
+        });
+    }
+    Err(SibylsError::NoUpcomingOracleEventError(asset_pair))
+}
+
+#[utoipa::path(
+    get,
+    context_path = "/v1",
+    path = "/announcements/next",
+    params(Filters),
+    responses(
+        (status = 200, description = "next oracle event still to be attested", body = ApiUpcomingOracleEvent),
+        (status = 404, description = "no upcoming oracle event or asset pair not recorded"),
+        (status = 500, description = "database error"),
+    )
+)]
+#[get("/announcements/next")]
+async fn next_announcement(
+    oracles: web::Data<HashMap<AssetPair, Oracle>>,
+    filters: web::Query<Filters>,
+) -> actix_web::Result<HttpResponse, actix_web::Error> {
+    info!("GET /announcements/next: {:#?}", filters);
+    Ok(HttpResponse::Ok().json(get_next_announcement(
+        &oracles,
+        filters.asset_pair,
+        filters.format,
+    )?))
+}
+
+fn get_announcement(
+    oracles: &HashMap<AssetPair, Oracle>,
+    asset_pair: AssetPair,
+    maturation: &str,
+    format: Format,
+) -> Result<ApiOracleEvent, SibylsError> {
+    let oracle = match oracles.get(&asset_pair) {
+        None => return Err(SibylsError::UnrecordedAssetPairError(asset_pair)),
+        Some(val) => val,
+    };
+    get_oracle_announcement(oracle, maturation, format)
+}
+
+/// Returns the oracle event with the given maturation of a single schedule
+fn get_oracle_announcement(
+    oracle: &Oracle,
+    maturation: &str,
+    format: Format,
+) -> Result<ApiOracleEvent, SibylsError> {
+    let _ = OffsetDateTime::parse(maturation, &Rfc3339)?;
+
+    if oracle.event_database.is_empty()? {
         info!("no oracle events found");
-        return Ok(HttpResponse::Ok().json(Vec::<ApiOracleEvent>::new()));
-    }
-
-    let start = filters.page * PAGE_SIZE;
-
-    match filters.sort_by {
-        SortOrder::Insertion => loop {
-            let init_key = oracle
-                .event_database
-                .first()
-                .map_err(SibylsError::DatabaseError)?
-                .unwrap()
-                .0;
-            let start_key = OffsetDateTime::parse(&String::from_utf8_lossy(&init_key), &Rfc3339)
-                .unwrap()
-                + Duration::days(start.into());
-            let end_key = start_key + Duration::days(PAGE_SIZE.into());
-            let start_key = start_key.format(&Rfc3339).unwrap().into_bytes();
-            let end_key = end_key.format(&Rfc3339).unwrap().into_bytes();
-            if init_key
-                == oracle
-                    .event_database
-                    .first()
-                    .map_err(SibylsError::DatabaseError)?
-                    .unwrap()
-                    .0
-            {
-                // don't know if range can change while iterating due to another thread modifying
-                info!(
-                    "retrieving oracle events from {} to {}",
-                    String::from_utf8_lossy(&start_key),
-                    String::from_utf8_lossy(&end_key),
-                );
-                return Ok(HttpResponse::Ok().json(
-                    oracle
-                        .event_database
-                        .range(start_key..end_key)
-                        .map(|result| parse_database_entry(filters.asset_pair, result.unwrap()))
-                        .collect::<Vec<_>>(),
-                ));
-            }
-        },
-        SortOrder::ReverseInsertion => loop {
-            let init_key = oracle
-                .event_database
-                .last()
-                .map_err(SibylsError::DatabaseError)?
-                .unwrap()
-                .0;
-            let end_key = OffsetDateTime::parse(&String::from_utf8_lossy(&init_key), &Rfc3339)
-                .unwrap()
-                - Duration::days(start.into());
-            let start_key = end_key - Duration::days(PAGE_SIZE.into());
-            let start_key = start_key.format(&Rfc3339).unwrap().into_bytes();
-            let end_key = end_key.format(&Rfc3339).unwrap().into_bytes();
-            if init_key
-                == oracle
-                    .event_database
-                    .last()
-                    .map_err(SibylsError::DatabaseError)?
-                    .unwrap()
-                    .0
-            {
-                // don't know if range can change while iterating due to another thread modifying
-                info!(
-                    "retrieving oracle events from {} to {}",
-                    String::from_utf8_lossy(&start_key),
-                    String::from_utf8_lossy(&end_key),
-                );
-                return Ok(HttpResponse::Ok().json(
-                    oracle
-                        .event_database
-                        .range(start_key..end_key)
-                        .map(|result| parse_database_entry(filters.asset_pair, result.unwrap()))
-                        .collect::<Vec<_>>(),
-                ));
-            }
-        },
+        return Err(SibylsError::OracleEventNotFoundError(
+            maturation.to_string(),
+        ));
     }
+
+    info!("retrieving oracle event with maturation {}", maturation);
+    let event = match oracle.event_database.get(maturation)? {
+        Some(val) => val,
+        None => {
+            return Err(SibylsError::OracleEventNotFoundError(
+                maturation.to_string(),
+            ))
+        }
+    };
+    Ok(parse_database_entry(
+        oracle,
+        format,
+        (maturation.into(), event),
+    ))
 }
 
+#[utoipa::path(
+    get,
+    context_path = "/v1",
+    path = "/announcement/{rfc3339_time}",
+    params(
+        ("rfc3339_time" = String, Path, description = "RFC3339-encoded time of maturation", example = "2022-05-31T08:00:00Z"),
+        Filters,
+    ),
+    responses(
+        (status = 200, description = "oracle event with the given maturation, or its binary announcement TLV with `Accept: application/octet-stream`", body = ApiOracleEvent),
+        (status = 304, description = "oracle event matches the `If-None-Match` ETag"),
+        (status = 400, description = "invalid RFC3339 time"),
+        (status = 404, description = "oracle event not found or asset pair not recorded"),
+        (status = 500, description = "database error"),
+    )
+)]
 #[get("/announcement/{rfc3339_time}")]
 async fn announcement(
+    req: HttpRequest,
     oracles: web::Data<HashMap<AssetPair, Oracle>>,
     filters: web::Query<Filters>,
     path: web::Path<String>,
 ) -> actix_web::Result<HttpResponse, actix_web::Error> {
     info!("GET /announcement/{}: {:#?}", path, filters);
-    let _ = OffsetDateTime::parse(&path, &Rfc3339).map_err(SibylsError::DatetimeParseError)?;
+    let oracle = schedule_oracle(&oracles, filters.asset_pair, filters.schedule.as_deref())?;
+    let event = get_oracle_announcement(oracle, &path, filters.format)?;
+    Ok(event_response(&req, &event))
+}
 
-    let oracle = match oracles.get(&filters.asset_pair) {
-        None => return Err(SibylsError::UnrecordedAssetPairError(filters.asset_pair).into()),
-        Some(val) => val,
-    };
+#[utoipa::path(
+    get,
+    context_path = "/v1",
+    path = "/announcement/{rfc3339_time}/prices",
+    params(
+        ("rfc3339_time" = String, Path, description = "RFC3339-encoded time of maturation", example = "2022-05-31T08:00:00Z"),
+        Filters,
+    ),
+    responses(
+        (status = 200, description = "prices the attested outcome was aggregated from", body = PriceSources),
+        (status = 400, description = "invalid RFC3339 time"),
+        (status = 404, description = "oracle event not attested with an aggregated price or asset pair not recorded"),
+        (status = 500, description = "database error"),
+    )
+)]
+#[get("/announcement/{rfc3339_time}/prices")]
+async fn announcement_prices(
+    oracles: web::Data<HashMap<AssetPair, Oracle>>,
+    filters: web::Query<Filters>,
+    path: web::Path<String>,
+) -> actix_web::Result<HttpResponse, actix_web::Error> {
+    info!("GET /announcement/{}/prices: {:#?}", path, filters);
+    let oracle = schedule_oracle(&oracles, filters.asset_pair, filters.schedule.as_deref())?;
+    let maturation = maturation_key(&path)?;
+    let price_sources = oracle
+        .price_sources(&maturation)
+        .map_err(SibylsError::from)?
+        .ok_or(SibylsError::PriceSourcesNotFoundError(maturation))?;
+    Ok(HttpResponse::Ok().json(price_sources))
+}
 
-    if oracle.event_database.is_empty() {
-        info!("no oracle events found");
-        return Err(SibylsError::OracleEventNotFoundError(path.to_string()).into());
+#[utoipa::path(
+    get,
+    context_path = "/v1",
+    path = "/attestations/failed",
+    params(Filters),
+    responses(
+        (status = 200, description = "scheduled attestations given up after all retries failed, oldest first", body = [FailedAttestation]),
+        (status = 404, description = "asset pair not recorded"),
+        (status = 500, description = "database error"),
+    )
+)]
+#[get("/attestations/failed")]
+async fn failed_attestations(
+    oracles: web::Data<HashMap<AssetPair, Oracle>>,
+    filters: web::Query<Filters>,
+) -> actix_web::Result<HttpResponse, actix_web::Error> {
+    info!("GET /attestations/failed: {:#?}", filters);
+    let oracle = schedule_oracle(&oracles, filters.asset_pair, filters.schedule.as_deref())?;
+    Ok(HttpResponse::Ok().json(oracle.failed_attestations().map_err(SibylsError::from)?))
+}
+
+/// Returns the maturation of the oracle event with the given event id, which may also be the
+/// RFC3339 maturation itself for events announced without event id
+fn resolve_maturation(oracle: &Oracle, event_id: &str) -> Result<String, SibylsError> {
+    if let Some(maturation) = oracle.event_maturation(event_id)? {
+        return Ok(maturation);
+    }
+    if let Ok(key) = maturation_key(event_id) {
+        if oracle.event_database.contains(&key)? {
+            return Ok(key);
+        }
     }
+    Err(SibylsError::OracleEventIdNotFoundError(
+        event_id.to_string(),
+    ))
+}
 
-    info!("retrieving oracle event with maturation {}", path);
-    let event = match oracle
-        .event_database
-        .get(path.as_bytes())
-        .map_err(SibylsError::DatabaseError)?
-    {
-        Some(val) => val,
-        None => return Err(SibylsError::OracleEventNotFoundError(path.to_string()).into()),
+fn get_announcement_by_event_id(
+    oracle: &Oracle,
+    event_id: &str,
+    format: Format,
+) -> Result<ApiOracleEvent, SibylsError> {
+    info!("retrieving oracle event with event id {}", event_id);
+    match oracle.event_maturation(event_id)? {
+        Some(maturation) => get_oracle_announcement(oracle, &maturation, format),
+        None => Err(SibylsError::OracleEventIdNotFoundError(
+            event_id.to_string(),
+        )),
+    }
+}
+
+#[utoipa::path(
+    get,
+    context_path = "/v1",
+    path = "/event/{event_id}",
+    params(
+        ("event_id" = String, Path, description = "event id of the announced oracle event"),
+        Filters,
+    ),
+    responses(
+        (status = 200, description = "oracle event with the given event id", body = ApiOracleEvent),
+        (status = 304, description = "oracle event matches the `If-None-Match` ETag"),
+        (status = 404, description = "oracle event not found or asset pair not recorded"),
+        (status = 500, description = "database error"),
+    )
+)]
+#[get("/event/{event_id}")]
+async fn event_by_id(
+    req: HttpRequest,
+    oracles: web::Data<HashMap<AssetPair, Oracle>>,
+    filters: web::Query<Filters>,
+    path: web::Path<String>,
+) -> actix_web::Result<HttpResponse, actix_web::Error> {
+    info!("GET /event/{}: {:#?}", path, filters);
+    let oracle = schedule_oracle(&oracles, filters.asset_pair, filters.schedule.as_deref())?;
+    let event = get_announcement_by_event_id(oracle, &path, filters.format)?;
+    Ok(event_response(&req, &event))
+}
+
+#[utoipa::path(
+    post,
+    context_path = "/v1",
+    path = "/announcements/batch",
+    request_body = BatchRequest,
+    responses(
+        (status = 200, description = "oracle events matching any of the maturations or event ids, missing ones are omitted", body = [ApiOracleEvent]),
+        (status = 400, description = "invalid RFC3339 time or too many lookups"),
+        (status = 404, description = "asset pair not recorded"),
+        (status = 500, description = "database error"),
+    )
+)]
+#[post("/announcements/batch")]
+async fn announcements_batch(
+    oracles: web::Data<HashMap<AssetPair, Oracle>>,
+    request: web::Json<BatchRequest>,
+) -> actix_web::Result<HttpResponse, actix_web::Error> {
+    info!("POST /announcements/batch: {:#?}", request);
+    let lookups = request.maturations.len() + request.event_ids.len();
+    if lookups > MAX_PAGE_SIZE as usize {
+        return Err(SibylsError::BatchTooLargeError(lookups, MAX_PAGE_SIZE).into());
+    }
+
+    let oracle = schedule_oracle(&oracles, request.asset_pair, request.schedule.as_deref())?;
+    let mut events = Vec::with_capacity(lookups);
+    for maturation in &request.maturations {
+        match get_oracle_announcement(oracle, maturation, request.format) {
+            Ok(event) => events.push(event),
+            Err(SibylsError::OracleEventNotFoundError(_)) => {}
+            Err(err) => return Err(err.into()),
+        }
+    }
+    for event_id in &request.event_ids {
+        match get_announcement_by_event_id(oracle, event_id, request.format) {
+            Ok(event) => events.push(event),
+            Err(SibylsError::OracleEventIdNotFoundError(_)) => {}
+            Err(err) => return Err(err.into()),
+        }
+    }
+    Ok(HttpResponse::Ok().json(events))
+}
+
+#[derive(Serialize, ToSchema)]
+struct ApiOracleStats {
+    asset_pair: AssetPair,
+    /// number of oracle events announced
+    total_announcements: usize,
+    /// number of oracle events attested
+    total_attestations: usize,
+    /// oracle events past their maturation that were neither attested nor cancelled
+    missed_attestations: usize,
+    /// RFC3339 maturation of the earliest oracle event, if any
+    #[schema(example = "2022-05-01T08:00:00Z")]
+    first_maturation: Option<String>,
+    /// RFC3339 maturation of the latest oracle event, if any
+    #[schema(example = "2022-06-07T08:00:00Z")]
+    last_maturation: Option<String>,
+    /// attested oracle events pruned by the retention policy, not counted above
+    pruned_events: u64,
+}
+
+impl ApiOracleStats {
+    /// Counts the oracle event maturing at `maturation`, which was missed if unattested before
+    /// `now_key`, unless cancelled by the operator
+    fn record(&mut self, maturation: String, event: &DbValue, now_key: &str) {
+        self.total_announcements += 1;
+        if event.2.is_some() {
+            self.total_attestations += 1;
+        } else if !event.5 && maturation.as_str() < now_key {
+            self.missed_attestations += 1;
+        }
+        if self.first_maturation.is_none() {
+            self.first_maturation = Some(maturation.clone());
+        }
+        self.last_maturation = Some(maturation);
+    }
+}
+
+/// Summarizes the event database of the given oracle
+fn oracle_stats(asset_pair: AssetPair, oracle: &Oracle) -> Result<ApiOracleStats, SibylsError> {
+    let now_key = maturation_key(&OffsetDateTime::now_utc().format(&Rfc3339).unwrap())?;
+    let mut summary = ApiOracleStats {
+        asset_pair,
+        total_announcements: 0,
+        total_attestations: 0,
+        missed_attestations: 0,
+        first_maturation: None,
+        last_maturation: None,
+        pruned_events: oracle.pruned_events()?,
     };
-    Ok(HttpResponse::Ok().json(parse_database_entry(
-        filters.asset_pair,
-        ((&**path).into(), event),
-    )))
+    for entry in oracle.event_database.iter()? {
+        let (maturation, event) = entry?;
+        let event: DbValue =
+            serde_json::from_slice(&event).map_err(|err| SibylsError::OracleError(err.into()))?;
+        summary.record(maturation, &event, &now_key);
+    }
+    Ok(summary)
+}
+
+#[utoipa::path(
+    get,
+    context_path = "/v1",
+    path = "/stats",
+    responses(
+        (status = 200, description = "oracle activity of each asset pair", body = [ApiOracleStats]),
+        (status = 500, description = "database error"),
+    )
+)]
+#[get("/stats")]
+async fn stats(
+    oracles: web::Data<HashMap<AssetPair, Oracle>>,
+) -> actix_web::Result<HttpResponse, actix_web::Error> {
+    info!("GET /stats");
+    let mut summaries = oracles
+        .iter()
+        .map(|(asset_pair, oracle)| oracle_stats(*asset_pair, oracle))
+        .collect::<Result<Vec<_>, _>>()?;
+    summaries.sort_by_key(|summary| summary.asset_pair.to_string());
+    Ok(HttpResponse::Ok().json(summaries))
+}
+
+#[derive(Serialize, ToSchema)]
+struct ApiPriceFeedsHealth {
+    asset_pair: AssetPair,
+    pricefeeds: Vec<PriceFeedStatus>,
 }
 
+#[utoipa::path(
+    get,
+    context_path = "/v1",
+    path = "/pricefeeds",
+    responses(
+        (status = 200, description = "health of the pricefeeds of each asset pair", body = [ApiPriceFeedsHealth]),
+    )
+)]
+#[get("/pricefeeds")]
+async fn pricefeeds_health(
+    oracles: web::Data<HashMap<AssetPair, Oracle>>,
+) -> actix_web::Result<HttpResponse, actix_web::Error> {
+    info!("GET /pricefeeds");
+    let mut health = oracles
+        .iter()
+        .map(|(asset_pair, oracle)| ApiPriceFeedsHealth {
+            asset_pair: *asset_pair,
+            pricefeeds: oracle.health.pricefeeds.statuses(),
+        })
+        .collect::<Vec<_>>();
+    health.sort_by_key(|health| health.asset_pair.to_string());
+    Ok(HttpResponse::Ok().json(health))
+}
+
+#[utoipa::path(
+    get,
+    context_path = "/v1",
+    path = "/config",
+    responses(
+        (status = 200, description = "oracle config", body = OracleConfig),
+    )
+)]
 #[get("/config")]
 async fn config(
-    oracles: web::Data<HashMap<AssetPair, Oracle>>,
+    oracle_config: web::Data<OracleConfig>,
 ) -> actix_web::Result<HttpResponse, actix_web::Error> {
     info!("GET /config");
-    Ok(HttpResponse::Ok().json(
-        oracles
-            .values()
-            .next()
-            .expect("no asset pairs recorded")
-            .oracle_config,
+    Ok(HttpResponse::Ok().json(oracle_config.get_ref()))
+}
+
+#[derive(Serialize, ToSchema)]
+struct ApiOracleInfo {
+    metadata: ApiOracleMetadata,
+    /// hex-encoded Schnorr signature with the oracle key over the SHA256 hash of the compact
+    /// JSON serialization of `metadata`
+    signature: String,
+}
+
+#[derive(Serialize, ToSchema)]
+struct ApiOracleMetadata {
+    name: Option<String>,
+    operator_contact: Option<String>,
+    /// sibyls version
+    version: &'static str,
+    /// hex-encoded x-only public key of the oracle
+    public_key: String,
+    asset_pairs: Vec<ApiAssetPairMetadata>,
+}
+
+#[derive(Serialize, ToSchema)]
+struct ApiAssetPairMetadata {
+    asset_pair: AssetPair,
+    event_descriptor: ApiEventDescriptor,
+    /// time of attestation, in 24-hour format
+    #[schema(example = "08:00")]
+    attestation_time: String,
+    /// frequency of attestation
+    #[schema(example = "1day")]
+    frequency: String,
+    /// offset from attestation for announcement
+    #[schema(example = "7days 8h")]
+    announcement_offset: String,
+    /// times of day of the maturations of daily events, taking precedence over the time and
+    /// frequency of attestation
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    maturation_times: Vec<String>,
+    /// cron expression of the maturations, taking precedence over the times of maturation and
+    /// the time and frequency of attestation
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[schema(example = "0 */4 * * *")]
+    cron: Option<String>,
+    /// IANA timezone of the time of attestation and cron expression, UTC if unset
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[schema(example = "America/New_York")]
+    timezone: Option<String>,
+}
+
+fn humantime_duration(duration: time::Duration) -> String {
+    humantime::format_duration(duration.try_into().unwrap_or_default()).to_string()
+}
+
+fn asset_pair_metadata(oracles: &HashMap<AssetPair, Oracle>) -> Vec<ApiAssetPairMetadata> {
+    let mut served_pairs = oracles
+        .iter()
+        .map(|(asset_pair, oracle)| {
+            let oracle_config = &oracle.oracle_config;
+            ApiAssetPairMetadata {
+                asset_pair: *asset_pair,
+                event_descriptor: (&oracle.event_descriptor()).into(),
+                attestation_time: format!(
+                    "{:02}:{:02}",
+                    oracle_config.attestation_time.hour(),
+                    oracle_config.attestation_time.minute()
+                ),
+                frequency: humantime_duration(oracle_config.frequency),
+                announcement_offset: humantime_duration(oracle_config.announcement_offset),
+                maturation_times: oracle
+                    .maturation_times()
+                    .iter()
+                    .map(|time| format!("{:02}:{:02}", time.hour(), time.minute()))
+                    .collect(),
+                cron: oracle.cron().map(ToString::to_string),
+                timezone: oracle
+                    .timezone()
+                    .map(|timezone| timezone.name().to_string()),
+            }
+        })
+        .collect::<Vec<_>>();
+    served_pairs.sort_by_key(|metadata| metadata.asset_pair.to_string());
+    served_pairs
+}
+
+#[utoipa::path(
+    get,
+    context_path = "/v1",
+    path = "/asset_pairs",
+    responses(
+        (status = 200, description = "served asset pairs with their event descriptor and schedule", body = [ApiAssetPairMetadata]),
+    )
+)]
+#[get("/asset_pairs")]
+async fn asset_pairs(
+    oracles: web::Data<HashMap<AssetPair, Oracle>>,
+) -> actix_web::Result<HttpResponse, actix_web::Error> {
+    info!("GET /asset_pairs");
+    Ok(HttpResponse::Ok().json(asset_pair_metadata(&oracles)))
+}
+
+#[utoipa::path(
+    get,
+    context_path = "/v1",
+    path = "/oracle/info",
+    responses(
+        (status = 200, description = "oracle metadata signed with the oracle key", body = ApiOracleInfo),
+        (status = 503, description = "no asset pairs recorded"),
+    )
+)]
+#[get("/oracle/info")]
+async fn oracle_info(
+    oracles: web::Data<HashMap<AssetPair, Oracle>>,
+) -> actix_web::Result<HttpResponse, actix_web::Error> {
+    info!("GET /oracle/info");
+    let oracle = oracles
+        .values()
+        .next()
+        .ok_or(SibylsError::NoAssetPairsError)?;
+    let metadata = ApiOracleMetadata {
+        name: oracle.oracle_config.name.clone(),
+        operator_contact: oracle.oracle_config.operator_contact.clone(),
+        version: env!("CARGO_PKG_VERSION"),
+        public_key: oracle.public_key().to_string(),
+        asset_pairs: asset_pair_metadata(&oracles),
+    };
+    let signature = oracle
+        .sign(&serde_json::to_vec(&metadata).unwrap())
+        .to_string();
+    Ok(HttpResponse::Ok().json(ApiOracleInfo {
+        metadata,
+        signature,
+    }))
+}
+
+#[get("/ws")]
+async fn ws(
+    req: HttpRequest,
+    body: web::Payload,
+    event_updates: web::Data<broadcast::Sender<OracleEventUpdate>>,
+) -> actix_web::Result<HttpResponse, actix_web::Error> {
+    info!("GET /ws");
+    let (response, mut session, mut msg_stream) = actix_ws::handle(&req, body)?;
+    let mut event_updates = event_updates.subscribe();
+
+    actix_web::rt::spawn(async move {
+        loop {
+            tokio::select! {
+                update = event_updates.recv() => match update {
+                    Ok(update) => {
+                        let update = serde_json::to_string(&update).unwrap();
+                        if session.text(update).await.is_err() {
+                            return;
+                        }
+                    }
+                    Err(RecvError::Lagged(skipped)) => {
+                        warn!("websocket client lagging behind, skipped {skipped} oracle event updates");
+                    }
+                    Err(RecvError::Closed) => break,
+                },
+                msg = msg_stream.next() => match msg {
+                    Some(Ok(actix_ws::Message::Ping(bytes))) => {
+                        if session.pong(&bytes).await.is_err() {
+                            return;
+                        }
+                    }
+                    Some(Ok(actix_ws::Message::Close(reason))) => {
+                        let _ = session.close(reason).await;
+                        return;
+                    }
+                    Some(Ok(_)) => {}
+                    Some(Err(_)) | None => break,
+                },
+            }
+        }
+        let _ = session.close(None).await;
+    });
+
+    Ok(response)
+}
+
+#[get("/events/stream")]
+async fn events_stream(
+    filters: web::Query<StreamFilters>,
+    event_updates: web::Data<broadcast::Sender<OracleEventUpdate>>,
+) -> actix_web::Result<HttpResponse, actix_web::Error> {
+    info!("GET /events/stream: {:#?}", filters);
+    let asset_pair = filters.asset_pair;
+    let stream = futures::stream::unfold(
+        event_updates.subscribe(),
+        move |mut event_updates| async move {
+            loop {
+                match event_updates.recv().await {
+                    Ok(update)
+                        if asset_pair.is_none_or(|asset_pair| asset_pair == update.asset_pair) =>
+                    {
+                        let event =
+                            format!("data: {}\n\n", serde_json::to_string(&update).unwrap());
+                        return Some((
+                            Ok::<_, actix_web::Error>(web::Bytes::from(event)),
+                            event_updates,
+                        ));
+                    }
+                    Ok(_) => continue,
+                    Err(RecvError::Lagged(skipped)) => {
+                        warn!("event stream client lagging behind, skipped {skipped} oracle event updates");
+                    }
+                    Err(RecvError::Closed) => return None,
+                }
+            }
+        },
+    );
+
+    Ok(HttpResponse::Ok()
+        .content_type("text/event-stream")
+        .insert_header(("Cache-Control", "no-cache"))
+        .streaming(stream))
+}
+
+#[derive(Debug, Serialize)]
+struct ApiHealth {
+    database: bool,
+    scheduler: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pricefeeds: Option<bool>,
+    /// scheduler tasks restarted after panicking, including those of additional schedules
+    scheduler_restarts: u64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    last_scheduler_panic: Option<String>,
+}
+
+fn health_response(oracles: &HashMap<AssetPair, Oracle>, check_pricefeeds: bool) -> HttpResponse {
+    let health = oracles
+        .iter()
+        .map(|(asset_pair, oracle)| {
+            let schedules = || std::iter::once(oracle).chain(oracle.schedules());
+            let health = ApiHealth {
+                database: oracle.event_database.is_empty().is_ok(),
+                scheduler: schedules().all(|oracle| oracle.health.is_scheduler_alive()),
+                pricefeeds: check_pricefeeds.then(|| oracle.health.are_price_feeds_responsive()),
+                scheduler_restarts: schedules()
+                    .map(|oracle| oracle.health.scheduler_restarts())
+                    .sum(),
+                last_scheduler_panic: schedules()
+                    .find_map(|oracle| oracle.health.last_scheduler_panic()),
+            };
+            (*asset_pair, health)
+        })
+        .collect::<HashMap<_, _>>();
+    let healthy = health
+        .values()
+        .all(|health| health.database && health.scheduler && health.pricefeeds.unwrap_or(true));
+    if healthy {
+        HttpResponse::Ok().json(health)
+    } else {
+        warn!("oracle unhealthy: {health:?}");
+        HttpResponse::ServiceUnavailable().json(health)
+    }
+}
+
+#[get("/healthz")]
+async fn healthz(
+    oracles: web::Data<HashMap<AssetPair, Oracle>>,
+) -> actix_web::Result<HttpResponse, actix_web::Error> {
+    Ok(health_response(&oracles, false))
+}
+
+#[get("/readyz")]
+async fn readyz(
+    oracles: web::Data<HashMap<AssetPair, Oracle>>,
+) -> actix_web::Result<HttpResponse, actix_web::Error> {
+    Ok(health_response(&oracles, true))
+}
+
+#[derive(OpenApi)]
+#[openapi(
+    paths(
+        announcements,
+        latest_announcements,
+        next_announcement,
+        announcement,
+        announcement_prices,
+        failed_attestations,
+        announcements_batch,
+        event_by_id,
+        config,
+        oracle_info,
+        asset_pairs,
+        stats,
+        pricefeeds_health
+    ),
+    components(schemas(
+        ApiOracleEvent,
+        ApiAnnouncementsPage,
+        ApiUpcomingOracleEvent,
+        ApiOracleInfo,
+        ApiOracleMetadata,
+        ApiAssetPairMetadata,
+        ApiOracleStats,
+        ApiPriceFeedsHealth,
+        PriceFeedStatus,
+        PriceSources,
+        sibyls::oracle::PriceSource,
+        FailedAttestation,
+        EventType,
+        BatchRequest,
+        ApiDecodedAnnouncement,
+        ApiEventDescriptor,
+        Format,
+        AssetPair,
+        SortOrder,
+        OracleConfig,
+        sibyls::SigningVersion,
+        sibyls::AggregationType,
+        sibyls::RoundingMode,
+        sibyls::TwapConfig
     ))
+)]
+struct ApiDoc;
+
+fn cors(allowed_origins: &[String]) -> Cors {
+    let cors = Cors::default()
+        .allowed_methods([Method::GET, Method::POST])
+        .allowed_header(header::CONTENT_TYPE)
+        .max_age(3600);
+    if allowed_origins.iter().any(|origin| origin == "*") {
+        return cors.allow_any_origin();
+    }
+    allowed_origins
+        .iter()
+        .fold(cors, |cors, origin| cors.allowed_origin(origin))
+}
+
+fn load_rustls_config(tls_config: &TlsConfig) -> anyhow::Result<rustls::ServerConfig> {
+    let cert_chain = rustls_pemfile::certs(&mut BufReader::new(File::open(&tls_config.cert)?))?
+        .into_iter()
+        .map(rustls::Certificate)
+        .collect::<Vec<_>>();
+    if cert_chain.is_empty() {
+        return Err(anyhow::anyhow!(
+            "no certificates found in {}",
+            tls_config.cert.display()
+        ));
+    }
+
+    let mut key_reader = BufReader::new(File::open(&tls_config.key)?);
+    let key = loop {
+        match rustls_pemfile::read_one(&mut key_reader)? {
+            Some(rustls_pemfile::Item::PKCS8Key(key) | rustls_pemfile::Item::RSAKey(key)) => {
+                break rustls::PrivateKey(key)
+            }
+            Some(_) => continue,
+            None => {
+                return Err(anyhow::anyhow!(
+                    "no private key found in {}",
+                    tls_config.key.display()
+                ))
+            }
+        }
+    };
+
+    Ok(rustls::ServerConfig::builder()
+        .with_safe_defaults()
+        .with_no_client_auth()
+        .with_single_cert(cert_chain, key)?)
+}
+
+/// Creates the pricefeeds configured for an asset pair, leaving out those not listing it
+fn asset_pair_price_feeds(
+    asset_pair_info: &AssetPairInfo,
+    oracle_config: &OracleConfig,
+) -> anyhow::Result<Vec<Box<dyn PriceFeed + Send + Sync>>> {
+    let asset_pair = asset_pair_info.asset_pair;
+    let mut feed_ids = if asset_pair_info.include_price_feeds.is_empty() {
+        #[cfg(not(feature = "test-feed"))]
+        let ret = ALL_PRICE_FEEDS
+            .iter()
+            .cloned()
+            .chain(
+                oracle_config
+                    .pricefeeds
+                    .custom
+                    .iter()
+                    .map(|custom| FeedId::Custom(custom.name.clone())),
+            )
+            .chain(
+                oracle_config
+                    .pricefeeds
+                    .exec
+                    .iter()
+                    .map(|exec| FeedId::Exec(exec.name.clone())),
+            )
+            .chain(
+                oracle_config
+                    .pricefeeds
+                    .alpha_vantage
+                    .is_some()
+                    .then_some(FeedId::AlphaVantage),
+            )
+            .chain(
+                oracle_config
+                    .pricefeeds
+                    .polygon
+                    .is_some()
+                    .then_some(FeedId::Polygon),
+            )
+            .chain(
+                oracle_config
+                    .pricefeeds
+                    .bitcoind
+                    .is_some()
+                    .then_some(FeedId::Bitcoind),
+            )
+            .chain(
+                oracle_config
+                    .pricefeeds
+                    .esplora
+                    .is_some()
+                    .then_some(FeedId::Esplora),
+            )
+            .collect::<Vec<_>>();
+        #[cfg(feature = "test-feed")]
+        let ret = vec![sibyls::oracle::pricefeeds::FeedId::Test];
+        ret
+    } else {
+        asset_pair_info.include_price_feeds.clone()
+    };
+
+    feed_ids.retain(|x| !asset_pair_info.exclude_price_feeds.contains(x));
+
+    let mut pricefeeds_config = oracle_config.pricefeeds.clone();
+    pricefeeds_config
+        .weights
+        .extend(asset_pair_info.price_feed_weights.clone());
+
+    // not every exchange lists every asset pair
+    let mut pricefeeds = create_price_feeds(&feed_ids, &pricefeeds_config)?;
+    if asset_pair.cross_rate().is_some() {
+        pricefeeds = cross_rate_feeds(pricefeeds, &pricefeeds_config)?;
+    }
+    pricefeeds.retain(
+        |pricefeed| match pricefeed.translate_asset_pair(asset_pair) {
+            Ok(_) => true,
+            Err(err) => {
+                warn!("skipping pricefeed {}: {}", pricefeed.id(), err);
+                false
+            }
+        },
+    );
+    Ok(pricefeeds)
 }
 
 #[derive(Parser)]
@@ -236,6 +1466,199 @@ struct Args {
     /// Optional oracle config file; if not provided, it is assumed to exist at "config/oracle.json"
     #[clap(short, long, parse(from_os_str), value_name = "FILE", value_hint = clap::ValueHint::FilePath)]
     oracle_config_file: Option<std::path::PathBuf>,
+
+    /// Optional address for the HTTP server to listen on; overrides `server.bind` in the oracle config
+    #[clap(short, long, value_name = "ADDRESS")]
+    bind: Option<IpAddr>,
+
+    /// Optional port for the HTTP server to listen on; overrides `server.port` in the oracle config
+    #[clap(short, long, value_name = "PORT")]
+    port: Option<u16>,
+
+    /// Retrieves and aggregates prices and builds the next oracle event of every asset pair and
+    /// schedule without signing or storing anything, prints the results and exits
+    #[clap(long)]
+    dry_run: bool,
+
+    /// Keeps the oracle events and all other records in memory instead of the configured
+    /// database, so nothing is left behind on exit, e.g. for demos and tests; overrides
+    /// `database` in the oracle config
+    #[clap(long)]
+    ephemeral: bool,
+
+    /// Optional directory of the event databases; overrides `database_path` in the oracle config
+    #[clap(long, parse(from_os_str), value_name = "DIR", value_hint = clap::ValueHint::DirPath)]
+    database_path: Option<std::path::PathBuf>,
+
+    #[clap(subcommand)]
+    command: Option<Command>,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Rebuilds the announcements missing from the event databases, e.g. after losing them, from
+    /// the config and the secret key, then exits
+    Recover {
+        /// RFC3339 maturation of the earliest event to recover
+        #[clap(long, value_name = "RFC3339", parse(try_from_str = parse_rfc3339))]
+        since: OffsetDateTime,
+    },
+    /// Signs and stores the attestation of an announced oracle event without serving the oracle,
+    /// e.g. for recovery or on an air-gapped machine, and prints the attested oracle event
+    Attest {
+        /// event id of the oracle event, or its RFC3339 maturation if announced without one
+        event_id: String,
+        /// asset pair whose event database stores the event, built in or defined in the asset
+        /// pair config
+        #[clap(long, default_value = "BTCUSD")]
+        asset_pair: String,
+        /// additional schedule of the asset pair storing the event
+        #[clap(long)]
+        schedule: Option<String>,
+        /// outcome to attest, the index of the outcome for enum events
+        #[clap(long, required_unless_present_any = &["outcome_name", "from_feeds"])]
+        outcome: Option<i64>,
+        /// outcome of an enum event to attest
+        #[clap(long, conflicts_with = "outcome")]
+        outcome_name: Option<String>,
+        /// attest the price aggregated from the pricefeeds at maturation, or for enum events the
+        /// outcome picked by the outcome resolver
+        #[clap(long, conflicts_with_all = &["outcome", "outcome_name"])]
+        from_feeds: bool,
+        /// reason recorded in the audit trail
+        #[clap(long)]
+        reason: Option<String>,
+    },
+}
+
+fn parse_rfc3339(datetime: &str) -> Result<OffsetDateTime, time::error::Parse> {
+    OffsetDateTime::parse(datetime, &Rfc3339)
+}
+
+/// Returns the asset pair infos of every schedule of `asset_pair_infos`, the main schedule of
+/// each asset pair followed by its additional ones
+fn schedule_infos(asset_pair_infos: &[AssetPairInfo]) -> impl Iterator<Item = AssetPairInfo> + '_ {
+    asset_pair_infos.iter().flat_map(|asset_pair_info| {
+        std::iter::once(asset_pair_info.clone()).chain(
+            asset_pair_info
+                .schedules
+                .iter()
+                .map(|schedule| asset_pair_info.with_schedule(schedule)),
+        )
+    })
+}
+
+/// Runs the scheduled pipeline of every asset pair and schedule once against temporary event
+/// databases, leaving the event databases of the oracle untouched, and prints the results
+async fn dry_run(
+    asset_pair_infos: Vec<AssetPairInfo>,
+    oracle_config: OracleConfig,
+    keypair: KeyPair,
+) -> anyhow::Result<()> {
+    let (event_updates, _) = broadcast::channel(EVENT_UPDATES_CAPACITY);
+    let now = OffsetDateTime::now_utc();
+    let mut failed = 0;
+    for asset_pair_info in schedule_infos(&asset_pair_infos) {
+        let asset_pair = asset_pair_info.asset_pair;
+        let schedule = asset_pair_info.schedule.clone().unwrap_or_default();
+        let pricefeeds = asset_pair_price_feeds(&asset_pair_info, &oracle_config)?;
+        let oracle = Oracle::new_temporary(
+            oracle_config.clone(),
+            asset_pair_info,
+            keypair,
+            event_updates.clone(),
+        )?;
+        match oracle_scheduler::dry_run(&oracle, &pricefeeds, now).await {
+            Ok(dry_run) => println!("{}", serde_json::to_string_pretty(&dry_run)?),
+            Err(err) => {
+                error!("dry run of {asset_pair} {schedule} failed: {err}");
+                failed += 1;
+            }
+        }
+    }
+    if failed > 0 {
+        anyhow::bail!("dry run failed for {failed} schedules");
+    }
+    Ok(())
+}
+
+/// Runs a subcommand against the event databases instead of serving the oracle
+async fn run_command(
+    command: Command,
+    asset_pair_infos: Vec<AssetPairInfo>,
+    oracle_config: OracleConfig,
+    keypair: KeyPair,
+    secp: Secp256k1<secp256k1_zkp::All>,
+) -> anyhow::Result<()> {
+    let (event_updates, _) = broadcast::channel(EVENT_UPDATES_CAPACITY);
+    match command {
+        Command::Recover { since } => {
+            for asset_pair_info in schedule_infos(&asset_pair_infos) {
+                let asset_pair = asset_pair_info.asset_pair;
+                let schedule = asset_pair_info.schedule.clone().unwrap_or_default();
+                let mut oracle = Oracle::new(
+                    oracle_config.clone(),
+                    asset_pair_info,
+                    keypair,
+                    event_updates.clone(),
+                )?;
+                let recovered = oracle_scheduler::recover_events(
+                    &mut oracle,
+                    &secp,
+                    since,
+                    oracle_config.signing_version,
+                )?;
+                info!("recovered {recovered} announcements of {asset_pair} {schedule}");
+            }
+        }
+        Command::Attest {
+            event_id,
+            asset_pair,
+            schedule,
+            outcome,
+            outcome_name,
+            from_feeds,
+            reason,
+        } => {
+            let asset_pair_info = asset_pair_infos
+                .into_iter()
+                .find(|asset_pair_info| asset_pair_info.asset_pair.as_str() == asset_pair)
+                .ok_or_else(|| anyhow::anyhow!("asset pair {asset_pair} not recorded"))?;
+            let asset_pair_info = match &schedule {
+                None => asset_pair_info,
+                Some(schedule) => asset_pair_info
+                    .schedules
+                    .iter()
+                    .find(|event_schedule| &event_schedule.name == schedule)
+                    .map(|event_schedule| asset_pair_info.with_schedule(event_schedule))
+                    .ok_or_else(|| SibylsError::UnrecordedScheduleError(schedule.clone()))?,
+            };
+            let oracle = Oracle::new(
+                oracle_config.clone(),
+                asset_pair_info.clone(),
+                keypair,
+                event_updates,
+            )?;
+            let pricefeeds = if from_feeds {
+                asset_pair_price_feeds(&asset_pair_info, &oracle_config)?
+            } else {
+                Vec::new()
+            };
+            let maturation = admin::force_attestation(
+                &oracle,
+                &pricefeeds,
+                &event_id,
+                outcome,
+                outcome_name.as_deref(),
+                reason,
+            )
+            .await?;
+            oracle.flush().await?;
+            let event = get_oracle_announcement(&oracle, &maturation, Format::Hex)?;
+            println!("{}", serde_json::to_string_pretty(&event)?);
+        }
+    }
+    Ok(())
 }
 
 #[actix_web::main]
@@ -247,7 +1670,7 @@ async fn main() -> anyhow::Result<()> {
     let mut secret_key = String::new();
     let secp = Secp256k1::new();
 
-    let secret_key = match args.secret_key_file {
+    let secret_key = match &args.secret_key_file {
         None => {
             info!("no secret key file was found, generating secret key");
             secp.generate_keypair(&mut rand::thread_rng()).0
@@ -287,8 +1710,14 @@ async fn main() -> anyhow::Result<()> {
         "asset pair config successfully read: {:#?}",
         asset_pair_infos
     );
+    for asset_pair_info in &asset_pair_infos {
+        AssetPair::define(
+            asset_pair_info.asset_pair.as_str(),
+            &asset_pair_info.symbols,
+        );
+    }
 
-    let oracle_config: OracleConfig = match args.oracle_config_file {
+    let mut oracle_config: OracleConfig = match args.oracle_config_file {
         None => {
             info!("reading oracle config from config/oracle.json");
             serde_json::from_str(&fs::read_to_string("config/oracle.json")?)?
@@ -304,74 +1733,348 @@ async fn main() -> anyhow::Result<()> {
         }
     };
     info!("oracle config successfully read: {:#?}", oracle_config);
+    if let Some(database_path) = args.database_path {
+        oracle_config.database_path = database_path;
+    }
+    if args.ephemeral {
+        info!("keeping oracle events in memory, they are lost on exit");
+        oracle_config.database = DatabaseConfig::Memory;
+    }
+
+    if let Some(command) = args.command {
+        if args.secret_key_file.is_none() {
+            anyhow::bail!("subcommands need the secret key file of the oracle");
+        }
+        return run_command(command, asset_pair_infos, oracle_config, keypair, secp).await;
+    }
+    if args.dry_run {
+        return dry_run(asset_pair_infos, oracle_config, keypair).await;
+    }
 
     // setup event databases
+    let (event_updates, _) = broadcast::channel(EVENT_UPDATES_CAPACITY);
+    let mut admin_pricefeeds = HashMap::new();
+    let mut oracle_schedulers = Vec::new();
     let oracles = asset_pair_infos
         .iter()
         .map(|asset_pair_info| asset_pair_info.asset_pair)
         .zip(asset_pair_infos.iter().cloned().map(|asset_pair_info| {
             let asset_pair = asset_pair_info.asset_pair;
-            let include_price_feeds = asset_pair_info.include_price_feeds.clone();
-            let exclude_price_feeds = asset_pair_info.exclude_price_feeds.clone();
+            let schedules = asset_pair_info
+                .schedules
+                .iter()
+                .map(|schedule| asset_pair_info.with_schedule(schedule))
+                .collect::<Vec<_>>();
 
             // create oracle
             info!("creating oracle for {}", asset_pair);
-            let oracle = Oracle::new(oracle_config, asset_pair_info, keypair)?;
+            let mut oracle = Oracle::new(
+                oracle_config.clone(),
+                asset_pair_info.clone(),
+                keypair,
+                event_updates.clone(),
+            )?;
 
             // pricefeed retrieval
             info!("creating pricefeeds for {asset_pair}");
-            let mut feed_ids = if include_price_feeds.is_empty() {
-                #[cfg(not(feature = "test-feed"))]
-                let ret = ALL_PRICE_FEEDS.to_vec();
-                #[cfg(feature = "test-feed")]
-                let ret = vec![sibyls::oracle::pricefeeds::FeedId::Test];
-                ret
-            } else {
-                include_price_feeds
-            };
-
-            feed_ids.retain(|x| !exclude_price_feeds.contains(x));
+            let mut pricefeeds = asset_pair_price_feeds(&asset_pair_info, &oracle_config)?;
 
-            if feed_ids.is_empty() {
+            if pricefeeds.is_empty() {
                 error!("all pricefeeds for {asset_pair} are disabled");
                 exit(-2);
             }
 
-            info!("Using following price feeds: {feed_ids:?}");
+            info!(
+                "Using following price feeds: {:?}",
+                pricefeeds
+                    .iter()
+                    .map(|pricefeed| pricefeed.id())
+                    .collect::<Vec<_>>()
+            );
 
-            let pricefeeds = create_price_feeds(&feed_ids);
+            if oracle_config.pricefeeds.streaming {
+                pricefeeds = stream_prices(pricefeeds, asset_pair);
+            }
+            let pricefeeds = Arc::new(pricefeeds);
+            admin_pricefeeds.insert(asset_pair, pricefeeds.clone());
 
             info!("scheduling oracle events for {asset_pair}");
             // schedule oracle events (announcements/attestations)
-            oracle_scheduler::init(
+            let oracle_scheduler = oracle_scheduler::init(
                 oracle.clone(),
                 secp.clone(),
-                pricefeeds,
+                pricefeeds.clone(),
                 oracle_config.signing_version,
                 oracle_config.price_aggregation_type,
             )?;
+            oracle_schedulers.push(oracle_scheduler);
+
+            for asset_pair_info in schedules {
+                let schedule = asset_pair_info.schedule.clone().unwrap_or_default();
+                info!("scheduling {schedule} oracle events for {asset_pair}");
+                let schedule_oracle = Oracle::new(
+                    oracle_config.clone(),
+                    asset_pair_info,
+                    keypair,
+                    event_updates.clone(),
+                )?;
+                let oracle_scheduler = oracle_scheduler::init(
+                    schedule_oracle.clone(),
+                    secp.clone(),
+                    pricefeeds.clone(),
+                    oracle_config.signing_version,
+                    oracle_config.price_aggregation_type,
+                )?;
+                oracle_schedulers.push(oracle_scheduler);
+                oracle.add_schedule(schedule, schedule_oracle);
+            }
 
             Ok(oracle)
         }))
         .map(|(asset_pair, oracle)| oracle.map(|ok| (asset_pair, ok)))
         .collect::<anyhow::Result<HashMap<_, _>>>()?;
 
+    // setup and run gRPC server
+    if let Some(grpc_port) = oracle_config.server.grpc_port {
+        let grpc_bind = SocketAddr::new(
+            args.bind
+                .or(oracle_config.server.bind)
+                .unwrap_or(DEFAULT_BIND),
+            grpc_port,
+        );
+        info!("starting gRPC server at {grpc_bind}");
+        let grpc_server = tonic::transport::Server::builder()
+            .add_service(grpc::server(oracles.clone()))
+            .serve(grpc_bind);
+        tokio::spawn(async move {
+            if let Err(err) = grpc_server.await {
+                error!("gRPC server error: {err}");
+            }
+        });
+    }
+
     // setup and run server
-    let rpc_bind = env::var("SIBYLS_RPC_BIND").unwrap_or("127.0.0.1:8080".to_string());
-    info!("starting server at {rpc_bind}");
-    HttpServer::new(move || {
+    let rpc_bind = match (
+        args.bind.or(oracle_config.server.bind),
+        args.port.or(oracle_config.server.port),
+    ) {
+        (None, None) => env::var("SIBYLS_RPC_BIND")
+            .unwrap_or_else(|_| SocketAddr::new(DEFAULT_BIND, DEFAULT_PORT).to_string()),
+        (bind, port) => {
+            SocketAddr::new(bind.unwrap_or(DEFAULT_BIND), port.unwrap_or(DEFAULT_PORT)).to_string()
+        }
+    };
+    let cors_allowed_origins = oracle_config.server.cors_allowed_origins.clone();
+    if !cors_allowed_origins.is_empty() {
+        info!("allowing cross-origin requests from {cors_allowed_origins:?}");
+    }
+    let rate_limiter = oracle_config
+        .server
+        .rate_limit_per_minute
+        .map(|requests_per_minute| {
+            info!("rate limiting clients to {requests_per_minute} requests per minute");
+            let rate_limiter = web::Data::new(rate_limit::rate_limiter(requests_per_minute));
+            tokio::spawn(rate_limit::clean_up(rate_limiter.clone()));
+            rate_limiter
+        });
+    let admin_pricefeeds = web::Data::new(admin::AdminPriceFeeds(admin_pricefeeds));
+    let admin_api_keys = web::Data::new(auth::AdminApiKeys(
+        oracle_config.server.admin_api_keys.clone(),
+    ));
+    if admin_api_keys.0.is_empty() {
+        info!("no admin API keys configured, admin routes are disabled");
+    }
+    let compression = oracle_config.server.compression;
+    let access_log = oracle_config.server.access_log;
+    let json_rpc = oracle_config.server.json_rpc;
+    if json_rpc {
+        info!("serving JSON-RPC 2.0 interface at /rpc");
+    }
+    let prometheus = PrometheusMetricsBuilder::new("sibyls")
+        .registry(prometheus::default_registry().clone())
+        .endpoint("/metrics")
+        .build()
+        .map_err(|err| anyhow::anyhow!("cannot set up prometheus metrics: {err}"))?;
+    let openapi = ApiDoc::openapi();
+    let global_config = web::Data::new(oracle_config.clone());
+    let mut server = HttpServer::new(move || {
         App::new()
+            .wrap(Condition::new(compression, Compress::default()))
+            .wrap(from_fn(rate_limit::rate_limit))
+            .wrap(prometheus.clone())
+            .wrap(Condition::new(
+                !cors_allowed_origins.is_empty(),
+                cors(&cors_allowed_origins),
+            ))
+            .wrap(Condition::new(access_log, from_fn(access_log::access_log)))
             .app_data(web::Data::new(oracles.clone()))
+            .app_data(web::Data::new(event_updates.clone()))
+            .app_data(global_config.clone())
             .service(
                 web::scope("/v1")
                     .service(announcements)
+                    .service(announcements_batch)
+                    .service(latest_announcements)
+                    .service(next_announcement)
                     .service(announcement)
-                    .service(config),
+                    .service(announcement_prices)
+                    .service(failed_attestations)
+                    .service(event_by_id)
+                    .service(config)
+                    .service(oracle_info)
+                    .service(asset_pairs)
+                    .service(stats)
+                    .service(pricefeeds_health)
+                    .service(ws)
+                    .service(events_stream),
             )
-    })
-    .bind(rpc_bind)?
-    .run()
-    .await?;
+            .service(
+                web::scope("/v2")
+                    .service(explorer::announcements)
+                    .service(explorer::announcement),
+            )
+            .service(healthz)
+            .service(readyz)
+            .configure(|cfg| {
+                if let Some(rate_limiter) = &rate_limiter {
+                    cfg.app_data(rate_limiter.clone());
+                }
+                if json_rpc {
+                    cfg.service(rpc::rpc);
+                }
+                if !admin_api_keys.0.is_empty() {
+                    cfg.app_data(admin_api_keys.clone())
+                        .app_data(admin_pricefeeds.clone())
+                        .service(
+                            web::scope("/admin")
+                                .wrap(from_fn(auth::require_api_key))
+                                .service(admin::attest)
+                                .service(admin::create_event)
+                                .service(admin::delete_event)
+                                .service(admin::cancel_event)
+                                .service(admin::audit_trail)
+                                .service(admin::scheduler)
+                                .service(admin::pause_scheduler)
+                                .service(admin::resume_scheduler),
+                        );
+                }
+            })
+            .service(SwaggerUi::new("/docs/{_:.*}").url("/api-docs/openapi.json", openapi.clone()))
+    });
+    if let Some(workers) = oracle_config.server.workers {
+        server = server.workers(workers.get());
+    }
+    if let Some(max_connections) = oracle_config.server.max_connections {
+        server = server.max_connections(max_connections);
+    }
+    if let Some(keep_alive) = oracle_config.server.keep_alive {
+        server = server.keep_alive(keep_alive);
+    }
+    if let Some(client_request_timeout) = oracle_config.server.client_request_timeout {
+        server = server.client_request_timeout(client_request_timeout);
+    }
+    let server = match (&oracle_config.server.unix_socket, &oracle_config.server.tls) {
+        (Some(_), Some(_)) => anyhow::bail!("TLS is not supported on unix sockets"),
+        #[cfg(unix)]
+        (Some(unix_socket), None) => {
+            use std::os::unix::fs::FileTypeExt;
+            // a socket left behind by a previous run would make binding fail
+            if fs::metadata(unix_socket).is_ok_and(|metadata| metadata.file_type().is_socket()) {
+                fs::remove_file(unix_socket)?;
+            }
+            info!("starting server at unix:{}", unix_socket.display());
+            server.bind_uds(unix_socket)?
+        }
+        #[cfg(not(unix))]
+        (Some(_), None) => anyhow::bail!("unix sockets are only supported on unix"),
+        (None, None) => {
+            info!("starting server at http://{rpc_bind}");
+            server.bind(rpc_bind)?
+        }
+        (None, Some(tls_config)) => {
+            info!(
+                "loading TLS certificate from {} and key from {}",
+                tls_config.cert.display(),
+                tls_config.key.display()
+            );
+            let rustls_config = load_rustls_config(tls_config)?;
+            info!("starting server at https://{rpc_bind}");
+            server.bind_rustls(rpc_bind, rustls_config)?
+        }
+    };
+    if let Some(tor_config) = &oracle_config.server.tor {
+        let target = match &oracle_config.server.unix_socket {
+            Some(unix_socket) => format!("unix:{}", unix_socket.display()),
+            None => {
+                let mut target: SocketAddr = rpc_bind.parse()?;
+                if target.ip().is_unspecified() {
+                    target.set_ip(IpAddr::V4(Ipv4Addr::LOCALHOST));
+                }
+                target.to_string()
+            }
+        };
+        info!(
+            "publishing onion service via Tor control port at {}",
+            tor_config.control_address
+        );
+        let onion_address = tor::publish_onion_service(tor_config, &target).await?;
+        info!("serving onion service at http://{onion_address}");
+    }
+
+    // the server drains its connections on SIGINT/SIGTERM before returning
+    server.run().await?;
+
+    info!("server stopped, shutting down oracle schedulers");
+    for oracle_scheduler in oracle_schedulers {
+        oracle_scheduler.shutdown().await?;
+    }
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn stats_skip_cancelled_events_as_missed() {
+        let mut summary = ApiOracleStats {
+            asset_pair: AssetPair::BTCUSD,
+            total_announcements: 0,
+            total_attestations: 0,
+            missed_attestations: 0,
+            first_maturation: None,
+            last_maturation: None,
+            pruned_events: 0,
+        };
+        let now_key = "2022-06-04T08:00:00Z";
+        for (maturation, attested, cancelled) in [
+            ("2022-06-01T08:00:00Z", true, false),
+            ("2022-06-02T08:00:00Z", false, false),
+            ("2022-06-03T08:00:00Z", false, true),
+            ("2022-06-05T08:00:00Z", false, false),
+        ] {
+            let event = DbValue(
+                None,
+                vec![],
+                attested.then(Vec::new),
+                None,
+                false,
+                cancelled,
+            );
+            summary.record(maturation.to_string(), &event, now_key);
+        }
+
+        assert_eq!(4, summary.total_announcements);
+        assert_eq!(1, summary.total_attestations);
+        assert_eq!(1, summary.missed_attestations);
+        assert_eq!(
+            Some("2022-06-01T08:00:00Z"),
+            summary.first_maturation.as_deref()
+        );
+        assert_eq!(
+            Some("2022-06-05T08:00:00Z"),
+            summary.last_maturation.as_deref()
+        );
+    }
+}
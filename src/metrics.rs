@@ -0,0 +1,105 @@
+use prometheus::{
+    register_histogram_vec, register_int_counter_vec, register_int_gauge_vec, HistogramVec,
+    IntCounterVec, IntGaugeVec,
+};
+use std::sync::LazyLock;
+
+use crate::oracle::Oracle;
+
+pub static ANNOUNCEMENTS_CREATED: LazyLock<IntCounterVec> = LazyLock::new(|| {
+    register_int_counter_vec!(
+        "sibyls_announcements_created_total",
+        "Number of oracle announcements created",
+        &["asset_pair"]
+    )
+    .unwrap()
+});
+
+pub static ATTESTATIONS_SIGNED: LazyLock<IntCounterVec> = LazyLock::new(|| {
+    register_int_counter_vec!(
+        "sibyls_attestations_signed_total",
+        "Number of oracle attestations signed",
+        &["asset_pair"]
+    )
+    .unwrap()
+});
+
+pub static LATE_ATTESTATIONS: LazyLock<IntCounterVec> = LazyLock::new(|| {
+    register_int_counter_vec!(
+        "sibyls_late_attestations_total",
+        "Number of oracle attestations caught up after their scheduled attestation was missed",
+        &["asset_pair"]
+    )
+    .unwrap()
+});
+
+pub static ATTESTATIONS_FAILED: LazyLock<IntCounterVec> = LazyLock::new(|| {
+    register_int_counter_vec!(
+        "sibyls_attestations_failed_total",
+        "Number of scheduled oracle attestations given up after all retries failed",
+        &["asset_pair"]
+    )
+    .unwrap()
+});
+
+pub static SCHEDULER_RESTARTS: LazyLock<IntCounterVec> = LazyLock::new(|| {
+    register_int_counter_vec!(
+        "sibyls_scheduler_restarts_total",
+        "Number of scheduler tasks restarted after panicking",
+        &["asset_pair", "task"]
+    )
+    .unwrap()
+});
+
+pub static EVENTS_PRUNED: LazyLock<IntCounterVec> = LazyLock::new(|| {
+    register_int_counter_vec!(
+        "sibyls_events_pruned_total",
+        "Number of attested oracle events pruned by the retention policy",
+        &["asset_pair"]
+    )
+    .unwrap()
+});
+
+pub static OUTCOMES_CLAMPED: LazyLock<IntCounterVec> = LazyLock::new(|| {
+    register_int_counter_vec!(
+        "sibyls_outcomes_clamped_total",
+        "Number of outcomes clamped to the range representable by the event descriptor",
+        &["asset_pair"]
+    )
+    .unwrap()
+});
+
+pub static PRICEFEED_REQUEST_DURATION: LazyLock<HistogramVec> = LazyLock::new(|| {
+    register_histogram_vec!(
+        "sibyls_pricefeed_request_duration_seconds",
+        "Duration of pricefeed requests",
+        &["pricefeed"]
+    )
+    .unwrap()
+});
+
+pub static PRICEFEED_REQUEST_FAILURES: LazyLock<IntCounterVec> = LazyLock::new(|| {
+    register_int_counter_vec!(
+        "sibyls_pricefeed_request_failures_total",
+        "Number of failed pricefeed requests",
+        &["pricefeed"]
+    )
+    .unwrap()
+});
+
+pub static DATABASE_SIZE: LazyLock<IntGaugeVec> = LazyLock::new(|| {
+    register_int_gauge_vec!(
+        "sibyls_database_size_bytes",
+        "Size of the event database on disk",
+        &["asset_pair"]
+    )
+    .unwrap()
+});
+
+pub fn record_database_size(oracle: &Oracle) {
+    if let Ok(size) = oracle.event_database.size_on_disk() {
+        DATABASE_SIZE
+            .with_label_values(&[&oracle.asset_pair().to_string()])
+            .set(size as i64);
+    }
+}
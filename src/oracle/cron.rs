@@ -0,0 +1,278 @@
+//! Cron expressions scheduling oracle events at precise wall-clock times, e.g. `0 8 * * *` for
+//! every day at 08:00 or `0 */4 * * *` for every four hours
+
+use displaydoc::Display;
+use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
+use std::{fmt, str::FromStr};
+use thiserror::Error;
+use time::{Date, Duration, Month, OffsetDateTime, UtcOffset};
+
+/// Span searched for the next occurrence, long enough to find the 29th of February
+const MAX_SEARCH_SPAN: Duration = Duration::days(5 * 366);
+
+#[derive(Debug, Display, Error)]
+pub enum CronError {
+    /// cron expression {0} needs 5 fields: minute, hour, day of month, month and day of week
+    FieldCountError(String),
+
+    /// invalid cron field {0}
+    InvalidFieldError(String),
+
+    /// cron expression {0} never matches
+    NeverMatchesError(String),
+}
+
+/// Parsed cron expression of minute, hour, day of month, month and day of week, each field
+/// being `*`, a value, a range `a-b` or a list of them separated by commas, optionally with a
+/// step, e.g. `*/15` or `8-18/2`. Days of the week count from Sunday, which is both 0 and 7.
+#[derive(Clone, PartialEq)]
+pub struct CronSchedule {
+    expression: String,
+    minutes: u64,
+    hours: u64,
+    days_of_month: u64,
+    months: u64,
+    days_of_week: u64,
+    /// whether the day of month respectively week is restricted, in which case days matching
+    /// either of them match if both are
+    restricted_days: (bool, bool),
+}
+
+impl CronSchedule {
+    /// Returns the first time matching the expression strictly after `instant`, if any within
+    /// the next five years
+    pub fn next_after(&self, instant: OffsetDateTime) -> Option<OffsetDateTime> {
+        let instant = instant.to_offset(UtcOffset::UTC);
+        let mut next =
+            instant.replace_second(0).ok()?.replace_nanosecond(0).ok()? + Duration::MINUTE;
+        while next <= instant + MAX_SEARCH_SPAN {
+            if !matches(self.months, next.month() as u8) {
+                let (year, month) = match next.month() {
+                    Month::December => (next.year() + 1, Month::January),
+                    month => (next.year(), month.next()),
+                };
+                next = Date::from_calendar_date(year, month, 1)
+                    .ok()?
+                    .midnight()
+                    .assume_utc();
+            } else if !self.day_matches(next.date()) {
+                next = next.date().next_day()?.midnight().assume_utc();
+            } else if !matches(self.hours, next.hour()) {
+                next = next.replace_minute(0).ok()? + Duration::HOUR;
+            } else if !matches(self.minutes, next.minute()) {
+                next += Duration::MINUTE;
+            } else {
+                return Some(next);
+            }
+        }
+        None
+    }
+
+    fn day_matches(&self, date: Date) -> bool {
+        let day_of_month = matches(self.days_of_month, date.day());
+        let day_of_week = matches(self.days_of_week, date.weekday().number_days_from_sunday());
+        match self.restricted_days {
+            (true, true) => day_of_month || day_of_week,
+            _ => day_of_month && day_of_week,
+        }
+    }
+}
+
+fn matches(field: u64, value: u8) -> bool {
+    field & (1 << value) != 0
+}
+
+/// Parses a cron `field` of values between `min` and `max` into a bit set of them
+fn parse_field(field: &str, min: u8, max: u8) -> Result<u64, CronError> {
+    let invalid = || CronError::InvalidFieldError(field.to_string());
+    let value = |value: &str| {
+        value
+            .parse::<u8>()
+            .ok()
+            .filter(|value| (min..=max).contains(value))
+            .ok_or_else(invalid)
+    };
+    let mut values = 0;
+    for part in field.split(',') {
+        let (range, step) = match part.split_once('/') {
+            None => (part, None),
+            Some((range, step)) => (
+                range,
+                Some(
+                    step.parse::<usize>()
+                        .ok()
+                        .filter(|step| *step > 0)
+                        .ok_or_else(invalid)?,
+                ),
+            ),
+        };
+        let (start, end) = match range.split_once('-') {
+            _ if range == "*" => (min, max),
+            Some((start, end)) => (value(start)?, value(end)?),
+            // a single value with step, e.g. `5/15`, runs to the maximum
+            None if step.is_some() => (value(range)?, max),
+            None => (value(range)?, value(range)?),
+        };
+        if start > end {
+            return Err(invalid());
+        }
+        for value in (start..=end).step_by(step.unwrap_or(1)) {
+            values |= 1 << value;
+        }
+    }
+    Ok(values)
+}
+
+impl FromStr for CronSchedule {
+    type Err = CronError;
+
+    fn from_str(expression: &str) -> Result<Self, Self::Err> {
+        let fields = expression.split_whitespace().collect::<Vec<_>>();
+        let [minutes, hours, days_of_month, months, days_of_week] = fields[..] else {
+            return Err(CronError::FieldCountError(expression.to_string()));
+        };
+        let mut schedule = CronSchedule {
+            expression: fields.join(" "),
+            minutes: parse_field(minutes, 0, 59)?,
+            hours: parse_field(hours, 0, 23)?,
+            days_of_month: parse_field(days_of_month, 1, 31)?,
+            months: parse_field(months, 1, 12)?,
+            days_of_week: parse_field(days_of_week, 0, 7)?,
+            restricted_days: (
+                !days_of_month.starts_with('*'),
+                !days_of_week.starts_with('*'),
+            ),
+        };
+        // Sunday is 7 as well
+        if matches(schedule.days_of_week, 7) {
+            schedule.days_of_week |= 1;
+        }
+        if schedule.next_after(OffsetDateTime::UNIX_EPOCH).is_none() {
+            return Err(CronError::NeverMatchesError(expression.to_string()));
+        }
+        Ok(schedule)
+    }
+}
+
+impl fmt::Display for CronSchedule {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(&self.expression)
+    }
+}
+
+impl fmt::Debug for CronSchedule {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "CronSchedule({})", self.expression)
+    }
+}
+
+impl Serialize for CronSchedule {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&self.expression)
+    }
+}
+
+impl<'de> Deserialize<'de> for CronSchedule {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        String::deserialize(deserializer)?
+            .parse()
+            .map_err(de::Error::custom)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use time::macros::datetime;
+
+    fn next_after(expression: &str, instant: OffsetDateTime) -> OffsetDateTime {
+        expression
+            .parse::<CronSchedule>()
+            .unwrap()
+            .next_after(instant)
+            .unwrap()
+    }
+
+    #[test]
+    fn next_occurrences() {
+        let now = datetime!(2022-06-07 08:00 UTC);
+        assert_eq!(
+            datetime!(2022-06-08 08:00 UTC),
+            next_after("0 8 * * *", now)
+        );
+        assert_eq!(
+            datetime!(2022-06-07 08:00 UTC),
+            next_after("0 8 * * *", now - Duration::SECOND)
+        );
+        assert_eq!(
+            datetime!(2022-06-07 12:00 UTC),
+            next_after("0 */4 * * *", now)
+        );
+        assert_eq!(
+            datetime!(2022-06-07 08:15 UTC),
+            next_after("*/15 * * * *", now)
+        );
+        assert_eq!(
+            datetime!(2022-06-07 08:05 UTC),
+            next_after("5/15 8-18 * * *", now)
+        );
+        // 2022-06-07 is a Tuesday
+        assert_eq!(
+            datetime!(2022-06-10 16:30 UTC),
+            next_after("30 16 * * 5", now)
+        );
+        assert_eq!(
+            datetime!(2022-06-12 00:00 UTC),
+            next_after("0 0 * * 7", now)
+        );
+        assert_eq!(
+            datetime!(2022-07-01 00:00 UTC),
+            next_after("0 0 1 * *", now)
+        );
+        assert_eq!(
+            datetime!(2022-12-31 23:59 UTC),
+            next_after("59 23 31 12 *", now)
+        );
+        assert_eq!(
+            datetime!(2024-02-29 08:00 UTC),
+            next_after("0 8 29 2 *", now)
+        );
+        // either day matches if both are restricted
+        assert_eq!(
+            datetime!(2022-06-10 00:00 UTC),
+            next_after("0 0 15 * 5", now)
+        );
+        assert_eq!(
+            datetime!(2022-06-07 09:00 UTC),
+            next_after("0 9,17 * * 1-5", now.to_offset(time::macros::offset!(+2)))
+        );
+    }
+
+    #[test]
+    fn invalid_expressions_are_refused() {
+        assert!(matches!(
+            "0 8 * *".parse::<CronSchedule>(),
+            Err(CronError::FieldCountError(_))
+        ));
+        for expression in [
+            "60 8 * * *",
+            "0 8 0 * *",
+            "0 8 * 13 *",
+            "*/0 * * * *",
+            "0 9-8 * * *",
+        ] {
+            assert!(matches!(
+                expression.parse::<CronSchedule>(),
+                Err(CronError::InvalidFieldError(_))
+            ));
+        }
+        assert!(matches!(
+            "0 8 30 2 *".parse::<CronSchedule>(),
+            Err(CronError::NeverMatchesError(_))
+        ));
+        assert_eq!(
+            "0 8 * * *",
+            "0  8 * * *".parse::<CronSchedule>().unwrap().to_string()
+        );
+    }
+}
@@ -8,6 +8,33 @@ pub enum OracleError {
     /// nonpositive announcement time offset: {0}; announcement must happen before attestation
     InvalidAnnouncementTimeError(time::Duration),
 
+    /// invalid TWAP configuration: {0}
+    InvalidTwapError(String),
+
+    /// invalid event descriptor of {0}: {1}
+    InvalidEventDescriptorError(crate::AssetPair, String),
+
+    /// invalid event id format of {0}: {1}
+    InvalidEventIdFormatError(crate::AssetPair, String),
+
+    /// invalid schedules of {0}: {1}
+    InvalidScheduleError(crate::AssetPair, String),
+
     /// database error: {0}
     DatabaseError(#[from] sled::Error),
+
+    /// event storage error: {0}
+    StorageError(#[from] super::storage::StorageError),
+
+    /// json serialization/deserialization error: {0}
+    SerdeJsonError(#[from] serde_json::Error),
+
+    /// oracle event with maturation {0} not found
+    OracleEventNotFoundError(String),
+
+    /// oracle event with maturation {0} already attested
+    OracleEventAlreadyAttestedError(String),
+
+    /// nonces of oracle event with maturation {0} already signed another outcome, refusing to sign
+    NonceReuseError(String),
 }
@@ -0,0 +1,97 @@
+use super::pricefeeds::PriceFeedHealth;
+use std::sync::{
+    atomic::{AtomicI64, AtomicU64, Ordering},
+    Mutex,
+};
+use time::OffsetDateTime;
+
+/// maximum time between two scheduler heartbeats for the scheduler to be considered alive
+const SCHEDULER_HEARTBEAT_TIMEOUT: i64 = 60;
+/// maximum age of the last pricefeed response for the pricefeeds to be considered responsive
+const PRICE_RESPONSE_MAX_AGE: i64 = 15 * 60;
+
+/// Liveness information of an oracle, updated by its scheduler and read by the HTTP server
+#[derive(Debug, Default)]
+pub struct Health {
+    scheduler_heartbeat: AtomicI64,
+    last_price_response: AtomicI64,
+    scheduler_restarts: AtomicU64,
+    last_scheduler_panic: Mutex<Option<String>>,
+    /// health of the individual pricefeeds
+    pub pricefeeds: PriceFeedHealth,
+}
+
+impl Health {
+    pub fn record_scheduler_heartbeat(&self) {
+        self.scheduler_heartbeat.store(
+            OffsetDateTime::now_utc().unix_timestamp(),
+            Ordering::Relaxed,
+        );
+    }
+
+    /// Records that a task of the scheduler panicked with `panic` and was restarted
+    pub fn record_scheduler_restart(&self, panic: String) {
+        self.scheduler_restarts.fetch_add(1, Ordering::Relaxed);
+        *self.last_scheduler_panic.lock().unwrap() = Some(panic);
+    }
+
+    pub fn record_price_response(&self) {
+        self.last_price_response.store(
+            OffsetDateTime::now_utc().unix_timestamp(),
+            Ordering::Relaxed,
+        );
+    }
+
+    pub fn is_scheduler_alive(&self) -> bool {
+        OffsetDateTime::now_utc().unix_timestamp()
+            - self.scheduler_heartbeat.load(Ordering::Relaxed)
+            <= SCHEDULER_HEARTBEAT_TIMEOUT
+    }
+
+    /// Returns how often tasks of the scheduler were restarted after panicking
+    pub fn scheduler_restarts(&self) -> u64 {
+        self.scheduler_restarts.load(Ordering::Relaxed)
+    }
+
+    /// Returns the message of the last panic of a task of the scheduler, if any
+    pub fn last_scheduler_panic(&self) -> Option<String> {
+        self.last_scheduler_panic.lock().unwrap().clone()
+    }
+
+    pub fn are_price_feeds_responsive(&self) -> bool {
+        OffsetDateTime::now_utc().unix_timestamp()
+            - self.last_price_response.load(Ordering::Relaxed)
+            <= PRICE_RESPONSE_MAX_AGE
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn nothing_recorded_is_unhealthy() {
+        let health = Health::default();
+        assert!(!health.is_scheduler_alive());
+        assert!(!health.are_price_feeds_responsive());
+
+        health.record_scheduler_heartbeat();
+        assert!(health.is_scheduler_alive());
+        assert!(!health.are_price_feeds_responsive());
+
+        health.record_price_response();
+        assert!(health.are_price_feeds_responsive());
+    }
+
+    #[test]
+    fn scheduler_restarts_are_counted() {
+        let health = Health::default();
+        assert_eq!(0, health.scheduler_restarts());
+        assert_eq!(None, health.last_scheduler_panic());
+
+        health.record_scheduler_restart("first".to_string());
+        health.record_scheduler_restart("second".to_string());
+        assert_eq!(2, health.scheduler_restarts());
+        assert_eq!(Some("second".to_string()), health.last_scheduler_panic());
+    }
+}
@@ -1,28 +1,287 @@
-use crate::{AssetPairInfo, OracleConfig};
+use crate::{
+    AggregationType, AssetPair, AssetPairInfo, DatabaseConfig, OracleConfig, OutcomeResolver,
+    SUPPORTED_BASES,
+};
+use chrono_tz::Tz;
+use dlc_messages::oracle_msgs::{EventDescriptor, OracleAnnouncement, OracleAttestation};
+use hex::ToHex;
 use log::info;
-use secp256k1_zkp::KeyPair;
+use secp256k1_zkp::{hashes::sha256, schnorr, KeyPair, Message, Secp256k1, XOnlyPublicKey};
 use serde::{Deserialize, Serialize};
-use sled::Db;
+use sled::{Db, Tree};
+use std::{
+    collections::{HashMap, HashSet},
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+};
+use time::{
+    format_description::well_known::Rfc3339, Duration, OffsetDateTime, PrimitiveDateTime, Time,
+};
+use tokio::sync::broadcast;
+use utoipa::ToSchema;
 
 mod error;
 pub use error::OracleError;
 pub use error::Result;
 
+mod health;
+pub use health::Health;
+
+pub mod storage;
+use storage::EventStorage;
+
+/// Number of consecutive scheduled events whose event ids are checked to differ
+const EVENT_ID_CHECKS: usize = 1000;
+
+/// Key of the number of pruned oracle events in the `retention` tree
+const PRUNED_EVENTS_KEY: &str = "pruned_events";
+
 #[derive(Clone, Deserialize, Serialize)]
-// outstanding_sk_nonces?, announcement, attetstation?, outcome?
+// outstanding_sk_nonces?, announcement, attetstation?, outcome?, recovered, cancelled
 pub struct DbValue(
     pub Option<Vec<[u8; 32]>>,
     pub Vec<u8>,
     pub Option<Vec<u8>>,
-    pub Option<u64>,
+    pub Option<i64>,
+    #[serde(default)] pub bool,
+    #[serde(default)] pub bool,
 );
 
+impl DbValue {
+    pub fn announcement(&self) -> OracleAnnouncement {
+        tlv::decode(&self.1).expect("stored announcements to be valid TLV")
+    }
+
+    pub fn event_id(&self) -> String {
+        self.announcement().oracle_event.event_id
+    }
+
+    pub fn event_type(&self) -> EventType {
+        match self.announcement().oracle_event.event_descriptor {
+            EventDescriptor::DigitDecompositionEvent(_) => EventType::Numeric,
+            EventDescriptor::EnumEvent(_) => EventType::Enum,
+        }
+    }
+
+    /// Name of the attested outcome of enum events, whose outcomes are stored by index
+    pub fn outcome_name(&self) -> Option<String> {
+        match self.announcement().oracle_event.event_descriptor {
+            EventDescriptor::EnumEvent(descriptor) => usize::try_from(self.3?)
+                .ok()
+                .and_then(|index| descriptor.outcomes.into_iter().nth(index)),
+            EventDescriptor::DigitDecompositionEvent(_) => None,
+        }
+    }
+}
+
+/// Type of an oracle event, numeric events attesting a price and enum events one of a fixed set
+/// of outcomes. Both can be announced by the same oracle, e.g. ad-hoc enum events next to the
+/// scheduled price events of an asset pair.
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum EventType {
+    Numeric,
+    Enum,
+}
+
+#[derive(Clone, Copy, Debug, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum OracleEventKind {
+    Announcement,
+    Attestation,
+}
+
+#[derive(Clone, Copy, Debug, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AuditAction {
+    CreateEvent,
+    ForceAttestation,
+    DeleteEvent,
+    CancelEvent,
+}
+
+/// Record of an operator action on the event database
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct AuditEntry {
+    /// RFC3339 time of the action
+    pub time: String,
+    pub action: AuditAction,
+    pub maturation: String,
+    pub event_id: String,
+    /// hex-encoded TLV of the announcement of the affected oracle event
+    pub announcement: String,
+    pub reason: Option<String>,
+}
+
+/// Price of a single pricefeed an attested outcome was aggregated from
+#[derive(Clone, Debug, Deserialize, Serialize, ToSchema)]
+pub struct PriceSource {
+    pub pricefeed: String,
+    pub price: f64,
+    /// weight of the pricefeed in the aggregation
+    pub weight: f64,
+    /// volume traded around the price, if the pricefeed reports it
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub volume: Option<f64>,
+    /// RFC3339 time the price was retrieved
+    #[schema(example = "2022-06-07T08:00:02Z")]
+    pub retrieved_at: String,
+    /// RFC3339 time the price was sampled at, if a TWAP was attested
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[schema(example = "2022-06-07T07:55:00Z")]
+    pub sampled_at: Option<String>,
+}
+
+impl From<&pricefeeds::FeedPrice> for PriceSource {
+    fn from(feed_price: &pricefeeds::FeedPrice) -> Self {
+        PriceSource {
+            pricefeed: feed_price.pricefeed.to_string(),
+            price: feed_price.price,
+            weight: feed_price.weight,
+            volume: feed_price.volume,
+            retrieved_at: feed_price.retrieved.format(&Rfc3339).unwrap(),
+            sampled_at: None,
+        }
+    }
+}
+
+/// Prices an attested outcome was aggregated from, so third parties can verify it
+#[derive(Clone, Debug, Deserialize, Serialize, ToSchema)]
+pub struct PriceSources {
+    pub aggregation_type: AggregationType,
+    /// prices left after discarding outliers
+    pub prices: Vec<PriceSource>,
+}
+
+/// Scheduled attestation given up after all its retries failed, kept until the oracle event is
+/// attested otherwise, e.g. caught up or forced by the operator
+#[derive(Clone, Debug, Deserialize, Serialize, ToSchema)]
+pub struct FailedAttestation {
+    #[schema(example = "2022-06-07T08:00:00Z")]
+    pub maturation: String,
+    pub event_id: String,
+    /// error of the last attempt
+    pub error: String,
+    pub attempts: u32,
+    /// RFC3339 time the attestation was given up
+    #[schema(example = "2022-06-07T08:30:00Z")]
+    pub failed_at: String,
+}
+
+/// Notification sent whenever the scheduler stores a new announcement or attestation
+#[derive(Clone, Debug, Serialize)]
+pub struct OracleEventUpdate {
+    pub kind: OracleEventKind,
+    pub asset_pair: AssetPair,
+    pub event_id: String,
+    pub maturation: String,
+    /// hex-encoded TLV of the announcement or attestation
+    pub payload: String,
+}
+
 #[derive(Clone)]
 pub struct Oracle {
     pub oracle_config: OracleConfig,
     asset_pair_info: AssetPairInfo,
-    pub event_database: Db,
+    pub event_database: Arc<dyn EventStorage>,
+    /// local sled database of the oracle, storing the trees below and, unless another backend
+    /// is configured, the oracle events
+    database: Db,
+    /// [`AuditEntry`]s of operator actions in chronological order
+    audit_trail: Tree,
+    /// [`PriceSources`] of attested oracle events by maturation key
+    price_sources: Tree,
+    /// pending announcements and attestations of the scheduler, resumed after restarts
+    jobs: Tree,
+    /// [`FailedAttestation`]s by maturation key
+    failed_attestations: Tree,
+    /// number of oracle events pruned by the retention policy, under [`PRUNED_EVENTS_KEY`]
+    retention: Tree,
     keypair: KeyPair,
+    pub event_updates: broadcast::Sender<OracleEventUpdate>,
+    pub health: Arc<Health>,
+    /// set by the operator to halt new announcements, e.g. during maintenance, while attesting
+    /// the announced events goes on
+    paused: Arc<AtomicBool>,
+    /// oracles of the additional schedules of the asset pair by name
+    schedules: HashMap<String, Oracle>,
+}
+
+/// Checks that the configured event descriptor describes a valid event and that its outcome
+/// resolver fits it
+fn validate_event_descriptor(asset_pair_info: &AssetPairInfo) -> Result<()> {
+    let invalid = |reason: &str| {
+        Err(OracleError::InvalidEventDescriptorError(
+            asset_pair_info.asset_pair,
+            reason.to_string(),
+        ))
+    };
+    let descriptor = &asset_pair_info.event_descriptor;
+    if let Some(outcome) = &asset_pair_info.cancellation_outcome {
+        if !descriptor.outcomes.contains(outcome) {
+            return invalid(&format!(
+                "cancellation outcome {outcome} is not an outcome of the enum events"
+            ));
+        }
+    }
+    match (&asset_pair_info.outcome_resolver, descriptor.is_enum()) {
+        (_, false) if !SUPPORTED_BASES.contains(&descriptor.base) => invalid(&format!(
+            "unsupported base {}, supported bases are {:?}",
+            descriptor.base, SUPPORTED_BASES
+        )),
+        (_, false) if descriptor.num_digits == 0 => {
+            invalid("numeric events need at least one digit")
+        }
+        (_, false) if descriptor.max_outcome().is_none() => invalid(&format!(
+            "{} digits in base {} exceed the outcome range",
+            descriptor.num_digits, descriptor.base
+        )),
+        (Some(_), false) => invalid("outcome resolvers only apply to enum events"),
+        (Some(OutcomeResolver::PriceThresholds(thresholds)), true)
+            if thresholds.len() + 1 != descriptor.outcomes.len() =>
+        {
+            invalid("price thresholds need to separate the outcomes, one fewer than outcomes")
+        }
+        (Some(OutcomeResolver::PriceThresholds(thresholds)), true)
+            if thresholds.windows(2).any(|pair| pair[0] >= pair[1]) =>
+        {
+            invalid("price thresholds need to be ascending")
+        }
+        _ => Ok(()),
+    }
+}
+
+/// Checks that the additional schedules of an asset pair can be told apart from each other and
+/// from the main schedule, by name in the API and by the ids of their events
+fn validate_schedules(asset_pair_info: &AssetPairInfo) -> Result<()> {
+    let invalid = |reason: String| {
+        Err(OracleError::InvalidScheduleError(
+            asset_pair_info.asset_pair,
+            reason,
+        ))
+    };
+    let maturation = OffsetDateTime::UNIX_EPOCH;
+    let mut event_ids = vec![asset_pair_info.event_id(maturation)];
+    let mut names = HashSet::new();
+    for schedule in &asset_pair_info.schedules {
+        if schedule.name.is_empty() || !names.insert(&schedule.name) {
+            return invalid(format!(
+                "schedule names must be unique and not empty, got {:?}",
+                schedule.name
+            ));
+        }
+        let event_id = asset_pair_info.with_schedule(schedule).event_id(maturation);
+        if event_ids.contains(&event_id) {
+            return invalid(format!(
+                "event id format {} of schedule {} repeats event ids of another schedule",
+                schedule.event_id_format, schedule.name
+            ));
+        }
+        event_ids.push(event_id);
+    }
+    Ok(())
 }
 
 impl Oracle {
@@ -30,28 +289,503 @@ impl Oracle {
         oracle_config: OracleConfig,
         asset_pair_info: AssetPairInfo,
         keypair: KeyPair,
+        event_updates: broadcast::Sender<OracleEventUpdate>,
+    ) -> Result<Oracle> {
+        let storage = oracle_config.database.clone();
+        let database = match storage {
+            // the records kept along with the oracle events must not outlive them either
+            DatabaseConfig::Memory => {
+                info!(
+                    "keeping oracle events of {} in memory",
+                    database_name(&asset_pair_info)
+                );
+                sled::Config::new().temporary(true)
+            }
+            _ => {
+                let path = oracle_config
+                    .database_path
+                    .join(database_name(&asset_pair_info));
+                info!("creating sled at {}", path.display());
+                sled::Config::new().path(path)
+            }
+        };
+        Oracle::with_database(
+            oracle_config,
+            asset_pair_info,
+            keypair,
+            event_updates,
+            database,
+            &storage,
+        )
+    }
+
+    /// Creates an oracle whose oracle events are kept in memory and whose other records are
+    /// removed once dropped, leaving the event database of the asset pair untouched
+    pub fn new_temporary(
+        oracle_config: OracleConfig,
+        asset_pair_info: AssetPairInfo,
+        keypair: KeyPair,
+        event_updates: broadcast::Sender<OracleEventUpdate>,
     ) -> Result<Oracle> {
+        Oracle::with_database(
+            oracle_config,
+            asset_pair_info,
+            keypair,
+            event_updates,
+            sled::Config::new().temporary(true),
+            &DatabaseConfig::Memory,
+        )
+    }
+
+    fn with_database(
+        mut oracle_config: OracleConfig,
+        asset_pair_info: AssetPairInfo,
+        keypair: KeyPair,
+        event_updates: broadcast::Sender<OracleEventUpdate>,
+        database: sled::Config,
+        storage: &DatabaseConfig,
+    ) -> Result<Oracle> {
+        // the schedule of the asset pair takes precedence over the global one
+        if let Some(frequency) = asset_pair_info.frequency {
+            oracle_config.frequency = frequency;
+        }
+        if let Some(announcement_offset) = asset_pair_info.announcement_offset {
+            oracle_config.announcement_offset = announcement_offset;
+        }
+        if let Some(attestation_time) = asset_pair_info.attestation_time {
+            oracle_config.attestation_time = attestation_time;
+        }
+        if let Some(pregenerated_events) = asset_pair_info.pregenerated_events {
+            oracle_config.pregenerated_events = pregenerated_events;
+        }
         if !oracle_config.announcement_offset.is_positive() {
             return Err(OracleError::InvalidAnnouncementTimeError(
                 oracle_config.announcement_offset,
             ));
         }
+        validate_event_descriptor(&asset_pair_info)?;
+        validate_schedules(&asset_pair_info)?;
+        if let Some(event_id_format) = &asset_pair_info.event_id_format {
+            // ids are looked up by the API, so consecutive events must not share them. Cron
+            // schedules space events unevenly, so a number of them are checked
+            let mut maturation =
+                following_maturation(&oracle_config, &asset_pair_info, OffsetDateTime::UNIX_EPOCH);
+            for _ in 0..EVENT_ID_CHECKS {
+                let following = following_maturation(&oracle_config, &asset_pair_info, maturation);
+                if asset_pair_info.event_id(maturation) == asset_pair_info.event_id(following) {
+                    return Err(OracleError::InvalidEventIdFormatError(
+                        asset_pair_info.asset_pair,
+                        format!(
+                            "{event_id_format} repeats event ids of events {} apart",
+                            following - maturation
+                        ),
+                    ));
+                }
+                maturation = following;
+            }
+        }
+        if let Some(twap) = &oracle_config.twap {
+            // samples are historical prices, which the pricefeeds resolve to 1-minute candles
+            if twap.interval < Duration::MINUTE {
+                return Err(OracleError::InvalidTwapError(format!(
+                    "sampling interval {} is shorter than the minute candles of the pricefeeds",
+                    twap.interval
+                )));
+            }
+            if twap.window.is_negative() {
+                return Err(OracleError::InvalidTwapError(format!(
+                    "negative window {}",
+                    twap.window
+                )));
+            }
+        }
 
         // setup event database
-        let path = format!("events/{}", asset_pair_info.asset_pair);
-        info!("creating sled at {}", path);
-        let event_database = sled::open(path)?;
+        let database = database.open()?;
+        let event_database = storage::open(storage, &database_name(&asset_pair_info), &database)?;
+        let audit_trail = database.open_tree("audit")?;
+        let price_sources = database.open_tree("price_sources")?;
+        let jobs = database.open_tree("jobs")?;
+        let failed_attestations = database.open_tree("failed_attestations")?;
+        let retention = database.open_tree("retention")?;
+        if !event_database.has_signed_nonces()? && !event_database.is_empty()? {
+            info!("recording nonces of existing attestations");
+            for entry in event_database.iter()? {
+                let (_, event) = entry?;
+                let event: DbValue = serde_json::from_slice(&event)?;
+                if let Some(attestation) = &event.2 {
+                    let attestation: OracleAttestation =
+                        tlv::decode(attestation).expect("stored attestations to be valid TLV");
+                    let nonces = event
+                        .announcement()
+                        .oracle_event
+                        .oracle_nonces
+                        .iter()
+                        .map(XOnlyPublicKey::serialize)
+                        .collect::<Vec<_>>();
+                    event_database.record_signed_nonces(&nonces, &attestation.outcomes)?;
+                }
+            }
+        }
 
         Ok(Oracle {
             oracle_config,
             asset_pair_info,
             event_database,
+            database,
+            audit_trail,
+            price_sources,
+            jobs,
+            failed_attestations,
+            retention,
             keypair,
+            event_updates,
+            health: Arc::new(Health::default()),
+            paused: Arc::new(AtomicBool::new(false)),
+            schedules: HashMap::new(),
         })
     }
+
+    /// Waits for the oracle events and the records kept along with them to be durable
+    pub async fn flush(&self) -> Result<()> {
+        self.event_database.flush()?;
+        self.database.flush_async().await?;
+        Ok(())
+    }
+
+    /// Registers the oracle of an additional schedule of the asset pair
+    pub fn add_schedule(&mut self, name: String, oracle: Oracle) {
+        self.schedules.insert(name, oracle);
+    }
+
+    /// Returns the oracle of the additional schedule with the given name, if any
+    pub fn schedule(&self, name: &str) -> Option<&Oracle> {
+        self.schedules.get(name)
+    }
+
+    /// Returns the oracles of the additional schedules of the asset pair
+    pub fn schedules(&self) -> impl Iterator<Item = &Oracle> {
+        self.schedules.values()
+    }
+
+    pub fn asset_pair(&self) -> AssetPair {
+        self.asset_pair_info.asset_pair
+    }
+
+    /// Pauses or resumes the announcements of the scheduled events of the asset pair, including
+    /// those of its additional schedules
+    pub fn set_paused(&self, paused: bool) {
+        self.paused.store(paused, Ordering::Relaxed);
+        for oracle in self.schedules() {
+            oracle.set_paused(paused);
+        }
+    }
+
+    /// Whether announcements of scheduled events are paused by the operator
+    pub fn is_paused(&self) -> bool {
+        self.paused.load(Ordering::Relaxed)
+    }
+
+    /// Returns the maturation of the first scheduled event after `now`, or at the attestation
+    /// time of the current day for schedules by frequency
+    pub fn next_maturation(&self, now: OffsetDateTime) -> OffsetDateTime {
+        if self.is_calendar_schedule() {
+            return self.following_maturation(now);
+        }
+        let maturation = self.daily_maturation(now);
+        if maturation <= now {
+            self.following_maturation(maturation)
+        } else {
+            maturation
+        }
+    }
+
+    /// Whether the maturations of the scheduled events are given by a cron expression or times
+    /// of day rather than a frequency from the time of attestation
+    pub fn is_calendar_schedule(&self) -> bool {
+        self.asset_pair_info.cron.is_some() || !self.asset_pair_info.maturation_times.is_empty()
+    }
+
+    /// Returns the maturation at the attestation time of the day of `instant`, both in the
+    /// timezone of the asset pair
+    pub fn daily_maturation(&self, instant: OffsetDateTime) -> OffsetDateTime {
+        let timezone = self.asset_pair_info.timezone.unwrap_or(Tz::UTC);
+        let local =
+            timezone::to_local(timezone, instant).replace_time(self.oracle_config.attestation_time);
+        timezone::from_local(timezone, local)[0]
+    }
+
+    /// Returns the maturation of the scheduled event following the one maturing at `maturation`
+    pub fn following_maturation(&self, maturation: OffsetDateTime) -> OffsetDateTime {
+        following_maturation(&self.oracle_config, &self.asset_pair_info, maturation)
+    }
+
+    /// Returns the time the attestation of the oracle event maturing at `maturation` is
+    /// published, i.e. once the `attestation_delay` of the asset pair has passed
+    pub fn attestation_release(&self, maturation: OffsetDateTime) -> OffsetDateTime {
+        maturation + self.asset_pair_info.attestation_delay.unwrap_or_default()
+    }
+
+    /// Returns `event` as published, i.e. without its attestation and outcome until their
+    /// release, so they are signed at maturation but not disclosed before
+    pub fn published(&self, maturation: &[u8], mut event: DbValue) -> DbValue {
+        let embargoed = std::str::from_utf8(maturation)
+            .ok()
+            .and_then(|maturation| OffsetDateTime::parse(maturation, &Rfc3339).ok())
+            .is_some_and(|maturation| {
+                self.attestation_release(maturation) > OffsetDateTime::now_utc()
+            });
+        if embargoed {
+            event.2 = None;
+            event.3 = None;
+        }
+        event
+    }
+
+    /// Returns the RFC3339 maturation of the oracle event with the given event id, if any
+    pub fn event_maturation(&self, event_id: &str) -> Result<Option<String>> {
+        Ok(self.event_database.event_maturation(event_id)?)
+    }
+
+    /// Deletes the unattested oracle event maturing at `maturation`, returning it. Attested
+    /// events are never deleted since contracts may already have been settled with them.
+    pub fn delete_event(&self, maturation: &str) -> Result<DbValue> {
+        let stored = self
+            .event_database
+            .get(maturation)?
+            .ok_or_else(|| OracleError::OracleEventNotFoundError(maturation.to_string()))?;
+        let event: DbValue = serde_json::from_slice(&stored)?;
+        if event.2.is_some()
+            || !self
+                .event_database
+                .remove(maturation, &event.event_id(), &stored)?
+        {
+            return Err(OracleError::OracleEventAlreadyAttestedError(
+                maturation.to_string(),
+            ));
+        }
+        crate::metrics::record_database_size(self);
+        Ok(event)
+    }
+
+    /// Prunes the attested oracle event maturing at `maturation` along with its price sources,
+    /// unless it changed since read as `stored`. The nonces it was attested with stay recorded,
+    /// so they still never sign again. Returns whether it was pruned
+    pub fn prune_event(&self, maturation: &str, stored: &[u8]) -> Result<bool> {
+        let event: DbValue = serde_json::from_slice(stored)?;
+        if event.2.is_none()
+            || !self
+                .event_database
+                .remove(maturation, &event.event_id(), stored)?
+        {
+            return Ok(false);
+        }
+        self.price_sources.remove(maturation)?;
+        self.retention
+            .update_and_fetch(PRUNED_EVENTS_KEY, |pruned| {
+                let pruned =
+                    pruned.map_or(0, |pruned| u64::from_be_bytes(pruned.try_into().unwrap()));
+                Some((pruned + 1).to_be_bytes().to_vec())
+            })?;
+        Ok(true)
+    }
+
+    /// Returns the number of oracle events pruned by the retention policy so far
+    pub fn pruned_events(&self) -> Result<u64> {
+        Ok(self.retention.get(PRUNED_EVENTS_KEY)?.map_or(0, |pruned| {
+            u64::from_be_bytes(pruned.as_ref().try_into().unwrap())
+        }))
+    }
+
+    /// Appends an operator action on the oracle event `event` maturing at `maturation` to the
+    /// audit trail
+    pub fn record_audit(
+        &self,
+        action: AuditAction,
+        maturation: &str,
+        event: &DbValue,
+        reason: Option<String>,
+    ) -> Result<()> {
+        let entry = AuditEntry {
+            time: OffsetDateTime::now_utc().format(&Rfc3339).unwrap(),
+            action,
+            maturation: maturation.to_string(),
+            event_id: event.event_id(),
+            announcement: event.1.encode_hex(),
+            reason,
+        };
+        info!("recording audit entry {:?}", entry);
+        let id = self.database.generate_id()?;
+        self.audit_trail
+            .insert(id.to_be_bytes(), serde_json::to_vec(&entry)?)?;
+        Ok(())
+    }
+
+    /// Returns the audit trail of operator actions, oldest first
+    pub fn audit_trail(&self) -> Result<Vec<AuditEntry>> {
+        self.audit_trail
+            .iter()
+            .values()
+            .map(|entry| Ok(serde_json::from_slice(&entry?)?))
+            .collect()
+    }
+
+    /// Records the prices the outcome of the oracle event maturing at `maturation` was
+    /// aggregated from
+    pub fn record_price_sources(
+        &self,
+        maturation: &str,
+        price_sources: &PriceSources,
+    ) -> Result<()> {
+        self.price_sources
+            .insert(maturation, serde_json::to_vec(price_sources)?)?;
+        Ok(())
+    }
+
+    /// Returns the prices the outcome of the oracle event maturing at `maturation` was aggregated
+    /// from, if it was attested with an aggregated price
+    pub fn price_sources(&self, maturation: &str) -> Result<Option<PriceSources>> {
+        self.price_sources
+            .get(maturation)?
+            .map(|price_sources| Ok(serde_json::from_slice(&price_sources)?))
+            .transpose()
+    }
+
+    /// Records a scheduled attestation as failed for good
+    pub fn record_failed_attestation(&self, failed_attestation: &FailedAttestation) -> Result<()> {
+        self.failed_attestations.insert(
+            &failed_attestation.maturation,
+            serde_json::to_vec(failed_attestation)?,
+        )?;
+        Ok(())
+    }
+
+    /// Removes the record of the failed attestation of the oracle event maturing at
+    /// `maturation`, once it is attested
+    pub fn remove_failed_attestation(&self, maturation: &str) -> Result<()> {
+        self.failed_attestations.remove(maturation)?;
+        Ok(())
+    }
+
+    /// Returns the scheduled attestations that failed for good and are still unattested, oldest
+    /// first
+    pub fn failed_attestations(&self) -> Result<Vec<FailedAttestation>> {
+        self.failed_attestations
+            .iter()
+            .values()
+            .map(|failed_attestation| Ok(serde_json::from_slice(&failed_attestation?)?))
+            .collect()
+    }
+
+    /// Records the nonces of the oracle event maturing at `maturation` as signing `outcomes`,
+    /// one per nonce, refusing if any of them already signed another outcome, since signing two
+    /// outcomes with the same nonce leaks the oracle key. Signing the same outcome again yields
+    /// the same signature, so e.g. an attestation whose storage failed can be retried. The
+    /// records are kept by the event database, so they are shared by all instances using it
+    pub fn record_signed_nonces(
+        &self,
+        nonces: &[XOnlyPublicKey],
+        outcomes: &[String],
+        maturation: &str,
+    ) -> Result<()> {
+        let nonces = nonces
+            .iter()
+            .map(XOnlyPublicKey::serialize)
+            .collect::<Vec<_>>();
+        if !self
+            .event_database
+            .record_signed_nonces(&nonces, outcomes)?
+        {
+            return Err(OracleError::NonceReuseError(maturation.to_string()));
+        }
+        Ok(())
+    }
+
+    pub fn event_descriptor(&self) -> EventDescriptor {
+        self.asset_pair_info.event_descriptor.clone().into()
+    }
+
+    /// Cron expression of the maturations of the scheduled events, if scheduled by one
+    pub fn cron(&self) -> Option<&cron::CronSchedule> {
+        self.asset_pair_info.cron.as_ref()
+    }
+
+    /// Times of day of the maturations of the scheduled events, if scheduled by them
+    pub fn maturation_times(&self) -> &[Time] {
+        &self.asset_pair_info.maturation_times
+    }
+
+    /// Returns the timezone of the schedule of the asset pair, if not UTC
+    pub fn timezone(&self) -> Option<Tz> {
+        self.asset_pair_info.timezone
+    }
+
+    /// Signs the SHA256 hash of `data` with the oracle key
+    pub fn sign(&self, data: &[u8]) -> schnorr::Signature {
+        Secp256k1::new().sign_schnorr(
+            &Message::from_hashed_data::<sha256::Hash>(data),
+            &self.keypair,
+        )
+    }
+
+    /// x-only public key this oracle signs announcements and attestations with
+    pub fn public_key(&self) -> XOnlyPublicKey {
+        self.keypair.x_only_public_key().0
+    }
 }
 
 pub use dlc_messages::oracle_msgs::EventDescriptor;
 
+/// Returns the maturation of the scheduled event following the one maturing at `maturation`, as
+/// given by the `cron` expression of the asset pair if any, its times of maturation, or its
+/// frequency. They apply to the wall-clock time in the timezone of the asset pair, so daily
+/// events keep their local time across daylight saving time transitions
+fn following_maturation(
+    oracle_config: &OracleConfig,
+    asset_pair_info: &AssetPairInfo,
+    maturation: OffsetDateTime,
+) -> OffsetDateTime {
+    let timezone = asset_pair_info.timezone.unwrap_or(Tz::UTC);
+    let mut local = timezone::to_local(timezone, maturation);
+    loop {
+        local = match &asset_pair_info.cron {
+            Some(cron) => {
+                // validated to match at least every few years
+                let next = cron
+                    .next_after(local.assume_utc())
+                    .expect("cron expression to match");
+                PrimitiveDateTime::new(next.date(), next.time())
+            }
+            None if !asset_pair_info.maturation_times.is_empty() => {
+                let times = &asset_pair_info.maturation_times;
+                match times.iter().find(|time| **time > local.time()) {
+                    Some(time) => local.replace_time(*time),
+                    None => PrimitiveDateTime::new(local.date() + Duration::DAY, times[0]),
+                }
+            }
+            None => local + oracle_config.frequency,
+        };
+        // local times repeated as clocks are turned back may lie before the maturation
+        if let Some(following) = timezone::from_local(timezone, local)
+            .into_iter()
+            .find(|following| *following > maturation)
+        {
+            return following;
+        }
+    }
+}
+
+/// Name of the event database of the oracle events of `asset_pair_info`, additional schedules
+/// having their own
+fn database_name(asset_pair_info: &AssetPairInfo) -> String {
+    match &asset_pair_info.schedule {
+        None => asset_pair_info.asset_pair.to_string(),
+        Some(schedule) => format!("{}-{schedule}", asset_pair_info.asset_pair),
+    }
+}
+
+pub mod cron;
 pub mod oracle_scheduler;
 pub mod pricefeeds;
+pub mod timezone;
+pub mod tlv;
@@ -8,6 +8,9 @@ pub enum OracleSchedulerError {
     /// database error: {0}
     DatabaseError(#[from] sled::Error),
 
+    /// event storage error: {0}
+    StorageError(#[from] crate::oracle::storage::StorageError),
+
     /// secp256k1 upstream error: {0}
     Secp256k1UpstreamError(#[from] secp256k1_zkp::UpstreamError),
 
@@ -19,4 +22,40 @@ pub enum OracleSchedulerError {
 
     /// pricefeed error: {0}
     PriceFeedError(#[from] crate::oracle::pricefeeds::PriceFeedError),
+
+    /// oracle event with maturation {0} not found
+    OracleEventNotFoundError(String),
+
+    /// oracle event with maturation {0} already attested
+    OracleEventAlreadyAttestedError(String),
+
+    /// oracle event with maturation {0} cancelled
+    OracleEventCancelledError(String),
+
+    /// oracle event with maturation {0} already exists
+    OracleEventAlreadyExistsError(String),
+
+    /// oracle event with event id {0} already exists
+    EventIdAlreadyExistsError(String),
+
+    /// outcome {0} cannot be attested for the oracle event
+    InvalidOutcomeError(i64),
+
+    /// {0} is not an outcome of the enum oracle event
+    UnknownOutcomeError(String),
+
+    /// outcome resolver error: {0}
+    OutcomeResolverError(String),
+
+    /// enum oracle event with maturation {0} has no outcome resolver, an outcome is required
+    OutcomeRequiredError(String),
+
+    /// only {0} of the required {1} pricefeeds returned a usable price
+    InsufficientPriceFeedsError(usize, usize),
+
+    /// attestation of oracle event with maturation {0} failed after {1} attempts: {2}
+    AttestationFailedError(String, u32, String),
+
+    /// oracle error: {0}
+    OracleError(#[from] crate::oracle::OracleError),
 }
@@ -0,0 +1,90 @@
+use super::Result;
+use sled::Tree;
+use time::{format_description::well_known::Rfc3339, OffsetDateTime};
+
+/// Scheduler job of an oracle event, recorded as pending in the `jobs` tree of the event
+/// database until it is done, so a restart resumes it instead of losing track of it
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub(super) enum PendingJob {
+    /// announcement due at the announcement offset before maturation
+    Announcement,
+    /// attestation due at maturation
+    Attestation,
+}
+
+impl PendingJob {
+    fn prefix(self) -> &'static str {
+        match self {
+            PendingJob::Announcement => "announcement/",
+            PendingJob::Attestation => "attestation/",
+        }
+    }
+
+    fn key(self, maturation: OffsetDateTime) -> String {
+        format!("{}{}", self.prefix(), maturation.format(&Rfc3339).unwrap())
+    }
+
+    /// Records the job of the oracle event maturing at `maturation` as pending
+    pub fn record(self, jobs: &Tree, maturation: OffsetDateTime) -> Result<()> {
+        jobs.insert(self.key(maturation), &[] as &[u8])?;
+        Ok(())
+    }
+
+    /// Records the job of the oracle event maturing at `maturation` as done, if pending
+    pub fn complete(self, jobs: &Tree, maturation: OffsetDateTime) -> Result<()> {
+        jobs.remove(self.key(maturation))?;
+        Ok(())
+    }
+
+    /// Returns the maturations of the oracle events with this job pending, oldest first
+    pub fn pending(self, jobs: &Tree) -> Result<Vec<OffsetDateTime>> {
+        jobs.scan_prefix(self.prefix())
+            .map(|entry| {
+                let (key, _) = entry?;
+                let maturation = String::from_utf8_lossy(&key[self.prefix().len()..]).to_string();
+                Ok(OffsetDateTime::parse(&maturation, &Rfc3339)
+                    .expect("maturations of jobs to be RFC3339"))
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use time::macros::datetime;
+
+    #[test]
+    fn pending_jobs_are_listed_until_completed() {
+        let jobs = sled::Config::new()
+            .temporary(true)
+            .open()
+            .unwrap()
+            .open_tree("jobs")
+            .unwrap();
+        let first = datetime!(2022-06-07 08:00 UTC);
+        let second = datetime!(2022-06-08 08:00 UTC);
+        PendingJob::Attestation.record(&jobs, second).unwrap();
+        PendingJob::Attestation.record(&jobs, first).unwrap();
+        PendingJob::Announcement.record(&jobs, second).unwrap();
+        assert_eq!(
+            vec![first, second],
+            PendingJob::Attestation.pending(&jobs).unwrap()
+        );
+        assert_eq!(
+            vec![second],
+            PendingJob::Announcement.pending(&jobs).unwrap()
+        );
+
+        PendingJob::Attestation.complete(&jobs, first).unwrap();
+        PendingJob::Attestation.complete(&jobs, first).unwrap();
+        assert_eq!(
+            vec![second],
+            PendingJob::Attestation.pending(&jobs).unwrap()
+        );
+        assert_eq!(
+            vec![second],
+            PendingJob::Announcement.pending(&jobs).unwrap()
+        );
+    }
+}
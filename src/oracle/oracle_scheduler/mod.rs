@@ -1,30 +1,36 @@
 use super::{
     pricefeeds::{PriceFeed, PriceFeedError},
-    DbValue, Oracle,
+    tlv, DbValue, FailedAttestation, Oracle, OracleEventKind, OracleEventUpdate, PriceSource,
+    PriceSources,
 };
 use crate::{
-    oracle::pricefeeds::{aggregate_price, get_prices},
-    AggregationType, AssetPairInfo, SigningVersion,
+    metrics,
+    oracle::pricefeeds::{
+        aggregate_feed_prices, get_prices, probe_prices, reject_outliers, FeedPrice,
+    },
+    AggregationType, AssetPair, AssetPairInfo, OutcomeResolver, RoundingMode,
+    SerializableEventDescriptor, SigningVersion, TwapConfig,
 };
 use chrono::Utc;
+use chrono_tz::Tz;
 use clokwerk::{AsyncScheduler, Interval, Job};
 use core::ptr;
+use futures::future::join_all;
+use hex::ToHex;
 use lightning::util::ser::Writeable;
-use log::{error, info};
-use queues::{queue, IsQueue, Queue};
+use log::{error, info, warn};
 use secp256k1_sys::{
     types::{c_int, c_uchar, c_void, size_t},
     CPtr, SchnorrSigExtraParams,
 };
 use secp256k1_zkp::{
-    constants::SCHNORR_SIGNATURE_SIZE,
-    hashes::*,
-    rand::{self, RngCore},
-    schnorr::Signature as SchnorrSignature,
-    All, KeyPair, Message, Secp256k1, Signing, XOnlyPublicKey as SchnorrPublicKey,
+    constants::SCHNORR_SIGNATURE_SIZE, hashes::*, rand::Rng,
+    schnorr::Signature as SchnorrSignature, All, KeyPair, Message, Secp256k1, Signing,
+    XOnlyPublicKey as SchnorrPublicKey,
 };
+use serde::Serialize;
 use serde_json;
-use std::sync::Arc;
+use std::{ops::Bound, sync::Arc};
 use time::{format_description::well_known::Rfc3339, macros::format_description, OffsetDateTime};
 use tokio::{
     sync::{mpsc, Mutex},
@@ -35,13 +41,23 @@ mod error;
 pub use error::OracleSchedulerError;
 pub use error::Result;
 
-use dlc_messages::oracle_msgs::{OracleAnnouncement, OracleAttestation, OracleEvent};
-use dlc_messages::ser_impls::write_as_tlv;
+use dlc_messages::oracle_msgs::{
+    EventDescriptor, OracleAnnouncement, OracleAttestation, OracleEvent,
+};
 
+mod jobs;
 mod messaging;
+mod resolver;
+mod retention;
+mod supervisor;
 use crate::oracle::oracle_scheduler::messaging::{DLCV0AnnouncementHash, DLCV0AttestationHash};
+use jobs::PendingJob;
+use retention::{prune_events, DEFAULT_PRUNING_INTERVAL};
+use supervisor::supervise;
 
 const SCHEDULER_SLEEP_TIME: std::time::Duration = std::time::Duration::from_millis(100);
+const PRICE_PROBE_INTERVAL: std::time::Duration = std::time::Duration::from_secs(5 * 60);
+const CATCH_UP_INTERVAL: std::time::Duration = std::time::Duration::from_secs(10 * 60);
 
 extern "C" fn constant_nonce_fn(
     nonce32: *mut c_uchar,
@@ -88,90 +104,1021 @@ fn sign_schnorr_with_nonce<S: Signing>(
 struct OracleScheduler {
     oracle: Oracle,
     secp: Secp256k1<All>,
-    pricefeeds: Vec<Box<dyn PriceFeed + Send + Sync>>,
-    db_values: Queue<DbValue>,
+    pricefeeds: Arc<Vec<Box<dyn PriceFeed + Send + Sync>>>,
     next_announcement: OffsetDateTime,
     next_attestation: OffsetDateTime,
     signing_version: SigningVersion,
+    /// set on shutdown, no announcements or attestations are made afterwards
+    stopped: bool,
 }
 
 impl OracleScheduler {
     fn create_scheduler_event(&mut self) -> Result<()> {
-        let announcement_offset = self.oracle.oracle_config.announcement_offset;
-        create_event(
-            &mut self.oracle,
-            &self.secp,
-            &mut self.db_values,
-            self.next_announcement + announcement_offset,
-            self.signing_version,
-        )?;
-        self.next_announcement += self.oracle.oracle_config.frequency;
+        if self.stopped {
+            return Ok(());
+        }
+        let maturation = self.next_announcement + self.oracle.oracle_config.announcement_offset;
+        // skipped while paused, and pregenerated events are announced already
+        if self.oracle.is_paused() {
+            info!("announcements paused, skipping oracle event with maturation {maturation}");
+        } else if self
+            .oracle
+            .event_database
+            .get(&maturation.format(&Rfc3339).unwrap())?
+            .is_none()
+        {
+            create_event(
+                &mut self.oracle,
+                &self.secp,
+                maturation,
+                self.signing_version,
+            )?;
+        }
+        let following = self.oracle.following_maturation(maturation);
+        PendingJob::Announcement.record(&self.oracle.jobs, following)?;
+        PendingJob::Announcement.complete(&self.oracle.jobs, maturation)?;
+        self.next_announcement = following - self.oracle.oracle_config.announcement_offset;
+        self.pregenerate_events()?;
         Ok(())
     }
 
+    /// Announces the next `pregenerated_events` scheduled events ahead of their regular
+    /// announcement, skipping those announced already, unless announcements are paused. Returns
+    /// the number of announced events
+    fn pregenerate_events(&mut self) -> Result<u32> {
+        if self.oracle.is_paused() {
+            return Ok(0);
+        }
+        let mut maturation = self.next_announcement + self.oracle.oracle_config.announcement_offset;
+        let mut pregenerated = 0;
+        for _ in 0..self.oracle.oracle_config.pregenerated_events {
+            if self
+                .oracle
+                .event_database
+                .get(&maturation.format(&Rfc3339).unwrap())?
+                .is_none()
+            {
+                create_event(
+                    &mut self.oracle,
+                    &self.secp,
+                    maturation,
+                    self.signing_version,
+                )?;
+                pregenerated += 1;
+            }
+            maturation = self.oracle.following_maturation(maturation);
+        }
+        Ok(pregenerated)
+    }
+
+    /// Attests the scheduled oracle event maturing at `maturation`, which may be behind the next
+    /// scheduled attestation if retried, unless it was deleted, cancelled or attested meanwhile
     async fn attest(
-        &mut self,
+        &self,
+        maturation: OffsetDateTime,
         signing_version: SigningVersion,
         price_aggregation_type: AggregationType,
     ) -> Result<()> {
-        info!("retrieving pricefeeds for attestation");
-        let prices = get_prices(
+        if self.stopped {
+            return Ok(());
+        }
+        info!("retrieving outcome for attestation");
+        // the outcome at maturation, even if attesting late or retrying
+        let scheduled = match scheduled_outcome(
+            &self.oracle,
             &self.pricefeeds,
-            self.next_announcement,
-            self.oracle.asset_pair_info.asset_pair,
+            maturation,
+            price_aggregation_type,
+        )
+        .await
+        {
+            Err(OracleSchedulerError::OracleEventNotFoundError(_)) => {
+                info!(
+                    "oracle event with maturation {maturation} deleted or not announced, skipping"
+                );
+                return PendingJob::Attestation.complete(&self.oracle.jobs, maturation);
+            }
+            result => result?,
+        };
+        let Some((outcome, price_sources)) = scheduled else {
+            info!(
+                "leaving enum oracle event with maturation {maturation} to be attested by the operator"
+            );
+            return PendingJob::Attestation.complete(&self.oracle.jobs, maturation);
+        };
+        match store_attestation(
+            &self.oracle,
+            &self.secp,
+            maturation,
+            outcome,
+            price_sources.as_ref(),
+            signing_version,
+        ) {
+            Err(OracleSchedulerError::OracleEventAlreadyAttestedError(maturation)) => {
+                info!("oracle event with maturation {maturation} already attested, skipping");
+            }
+            Err(OracleSchedulerError::OracleEventNotFoundError(maturation)) => {
+                info!("oracle event with maturation {maturation} deleted, skipping");
+            }
+            Err(OracleSchedulerError::OracleEventCancelledError(maturation)) => {
+                info!("oracle event with maturation {maturation} cancelled, skipping");
+            }
+            result => result?,
+        }
+        PendingJob::Attestation.complete(&self.oracle.jobs, maturation)
+    }
+
+    /// Gives up on the scheduled attestation of the oracle event maturing at `maturation` after
+    /// `attempts` failed with `error`, and records it as failed, leaving it to be caught up or
+    /// forced by the operator, as its job stays pending
+    fn fail_attestation(
+        &self,
+        maturation: OffsetDateTime,
+        attempts: u32,
+        error: &str,
+    ) -> Result<()> {
+        let maturation = maturation.format(&Rfc3339).unwrap();
+        let event_id = match self.oracle.event_database.get(&maturation)? {
+            Some(event) => serde_json::from_slice::<DbValue>(&event)?.event_id(),
+            None => String::new(),
+        };
+        self.oracle.record_failed_attestation(&FailedAttestation {
+            maturation,
+            event_id,
+            error: error.to_string(),
+            attempts,
+            failed_at: OffsetDateTime::now_utc().format(&Rfc3339).unwrap(),
+        })?;
+        metrics::ATTESTATIONS_FAILED
+            .with_label_values(&[&self.oracle.asset_pair().to_string()])
+            .inc();
+        Ok(())
+    }
+}
+
+/// Retries the scheduled attestation of the oracle event maturing at `maturation` that failed
+/// with `error`, e.g. for lack of pricefeed quorum or a database error, every
+/// `attestation_retry_interval` until it succeeds, or records it as failed after
+/// `attestation_retries`
+async fn retry_attestation(
+    oracle_scheduler: Arc<Mutex<OracleScheduler>>,
+    maturation: OffsetDateTime,
+    mut error: String,
+    error_transmitter: mpsc::UnboundedSender<OracleSchedulerError>,
+    signing_version: SigningVersion,
+    price_aggregation_type: AggregationType,
+) {
+    let (interval, retries) = {
+        let locked_scheduler = oracle_scheduler.lock().await;
+        let oracle_config = &locked_scheduler.oracle.oracle_config;
+        (
+            oracle_config.attestation_retry_interval,
+            oracle_config.attestation_retries,
+        )
+    };
+    for retry in 1..=retries {
+        sleep(interval.unsigned_abs()).await;
+        let oracle_scheduler = oracle_scheduler.lock().await;
+        if oracle_scheduler.stopped {
+            return;
+        }
+        match oracle_scheduler
+            .attest(maturation, signing_version, price_aggregation_type)
+            .await
+        {
+            Err(err) => {
+                warn!("retry {retry} of {retries} of attestation with maturation {maturation} failed: {err}");
+                error = err.to_string();
+            }
+            Ok(()) => return,
+        }
+    }
+    let oracle_scheduler = oracle_scheduler.lock().await;
+    if oracle_scheduler.stopped {
+        return;
+    }
+    if let Err(err) = oracle_scheduler.fail_attestation(maturation, retries + 1, &error) {
+        error_transmitter.send(err).unwrap();
+    }
+    error_transmitter
+        .send(OracleSchedulerError::AttestationFailedError(
+            maturation.format(&Rfc3339).unwrap(),
+            retries + 1,
+            error,
+        ))
+        .unwrap();
+}
+
+/// Determines the outcome of the oracle event of `oracle` maturing at `maturation` along with
+/// the prices it was derived from, by the type of the stored event: the aggregated price for
+/// numeric events, and the index of the outcome picked by the configured resolver for enum
+/// events of the asset pair. `None` for enum events without resolver and enum events defined by
+/// the operator, which are attested by the operator.
+async fn scheduled_outcome(
+    oracle: &Oracle,
+    pricefeeds: &[Box<dyn PriceFeed + Send + Sync>],
+    maturation: OffsetDateTime,
+    price_aggregation_type: AggregationType,
+) -> Result<Option<(i64, Option<PriceSources>)>> {
+    let event_descriptor = stored_event_descriptor(oracle, maturation)?;
+    event_outcome(
+        oracle,
+        &event_descriptor,
+        pricefeeds,
+        maturation,
+        price_aggregation_type,
+    )
+    .await
+}
+
+/// Determines the outcome of an oracle event of `oracle` with `event_descriptor` maturing at
+/// `maturation` like [`scheduled_outcome`], whether the event is stored or not
+async fn event_outcome(
+    oracle: &Oracle,
+    event_descriptor: &EventDescriptor,
+    pricefeeds: &[Box<dyn PriceFeed + Send + Sync>],
+    maturation: OffsetDateTime,
+    price_aggregation_type: AggregationType,
+) -> Result<Option<(i64, Option<PriceSources>)>> {
+    let asset_pair_info = &oracle.asset_pair_info;
+    let descriptor = SerializableEventDescriptor::from(event_descriptor);
+    if !descriptor.is_enum() {
+        let (price, price_sources) =
+            maturation_price(oracle, pricefeeds, maturation, price_aggregation_type).await?;
+        let outcome = price_outcome(price, descriptor.precision, oracle.oracle_config.rounding);
+        return Ok(Some((
+            clamp_outcome(oracle, &descriptor, outcome),
+            Some(price_sources),
+        )));
+    }
+    if descriptor.outcomes != asset_pair_info.event_descriptor.outcomes {
+        return Ok(None);
+    }
+    match &asset_pair_info.outcome_resolver {
+        None => Ok(None),
+        Some(OutcomeResolver::PriceThresholds(thresholds)) => {
+            let (price, price_sources) =
+                maturation_price(oracle, pricefeeds, maturation, price_aggregation_type).await?;
+            let index = thresholds
+                .iter()
+                .take_while(|threshold| price >= **threshold)
+                .count();
+            info!(
+                "price {price} resolves to outcome {}",
+                asset_pair_info.event_descriptor.outcomes[index]
+            );
+            Ok(Some((index as i64, Some(price_sources))))
+        }
+        Some(OutcomeResolver::Command { command, args }) => {
+            let outcome = resolver::run_resolver_command(
+                command,
+                args,
+                asset_pair_info.asset_pair,
+                maturation,
+            )
+            .await?;
+            Ok(Some((outcome_index(event_descriptor, &outcome)?, None)))
+        }
+    }
+}
+
+/// Scales `price` to the outcome of a numeric event with `precision`, i.e. the number of units
+/// of `10^precision` it is rounded to with `rounding`, e.g. cents with a precision of -2
+fn price_outcome(price: f64, precision: i32, rounding: RoundingMode) -> i64 {
+    let scaled = price / 10f64.powi(precision);
+    // scaling is inexact, e.g. 1.1 / 0.1 is slightly above 11, which must not be taken up to 12
+    let nearest = scaled.round();
+    if (scaled - nearest).abs() <= f64::EPSILON * 16.0 * nearest.abs().max(1.0) {
+        return nearest as i64;
+    }
+    match rounding {
+        RoundingMode::HalfUp => (scaled + 0.5).floor() as i64,
+        RoundingMode::Floor => scaled.floor() as i64,
+        RoundingMode::Ceil => scaled.ceil() as i64,
+    }
+}
+
+/// Clamps the numeric `outcome` to the range representable by `descriptor`, so an unexpected
+/// price still gets attested at the bound instead of failing the attestation
+fn clamp_outcome(oracle: &Oracle, descriptor: &SerializableEventDescriptor, outcome: i64) -> i64 {
+    // validated when the oracle or the ad-hoc event is created
+    let max_outcome = descriptor.max_outcome().unwrap_or(i64::MAX);
+    let min_outcome = if descriptor.is_signed {
+        -max_outcome
+    } else {
+        0
+    };
+    let clamped = outcome.clamp(min_outcome, max_outcome);
+    if clamped != outcome {
+        warn!(
+            "clamping {} outcome {outcome} to {clamped}, the range of {} digits in base {}",
+            oracle.asset_pair(),
+            descriptor.num_digits,
+            descriptor.base
+        );
+        metrics::OUTCOMES_CLAMPED
+            .with_label_values(&[&oracle.asset_pair().to_string()])
+            .inc();
+    }
+    clamped
+}
+
+/// Returns the event descriptor the oracle event maturing at `maturation` was announced with
+fn stored_event_descriptor(oracle: &Oracle, maturation: OffsetDateTime) -> Result<EventDescriptor> {
+    let maturation = maturation.format(&Rfc3339).unwrap();
+    let stored = oracle
+        .event_database
+        .get(&maturation)?
+        .ok_or(OracleSchedulerError::OracleEventNotFoundError(maturation))?;
+    let db_value: DbValue = serde_json::from_slice(&stored)?;
+    Ok(db_value.announcement().oracle_event.event_descriptor)
+}
+
+/// Returns the index of `outcome` among the outcomes of the enum oracle event maturing at
+/// `maturation`, as attested outcomes of enum events are given by index
+pub fn enum_outcome_index(
+    oracle: &Oracle,
+    maturation: OffsetDateTime,
+    outcome: &str,
+) -> Result<i64> {
+    outcome_index(&stored_event_descriptor(oracle, maturation)?, outcome)
+}
+
+/// Returns the index of `outcome` among the outcomes of the enum event described by
+/// `event_descriptor`
+fn outcome_index(event_descriptor: &EventDescriptor, outcome: &str) -> Result<i64> {
+    match event_descriptor {
+        EventDescriptor::EnumEvent(descriptor) => descriptor
+            .outcomes
+            .iter()
+            .position(|enum_outcome| enum_outcome == outcome)
+            .map(|index| index as i64),
+        EventDescriptor::DigitDecompositionEvent(_) => None,
+    }
+    .ok_or_else(|| OracleSchedulerError::UnknownOutcomeError(outcome.to_string()))
+}
+
+/// Retrieves and aggregates the price at `maturation` from `pricefeeds`, or, if `oracle` is
+/// configured to attest a TWAP, averages the prices aggregated at every sampling instant of the
+/// window ending at `maturation`. Samples are retrieved at once as historical prices, which the
+/// pricefeeds resolve to their 1-minute candles, so sampling intervals are validated to be at
+/// least a minute. Samples that cannot be aggregated are left out of the average.
+async fn maturation_price(
+    oracle: &Oracle,
+    pricefeeds: &[Box<dyn PriceFeed + Send + Sync>],
+    maturation: OffsetDateTime,
+    price_aggregation_type: AggregationType,
+) -> Result<(f64, PriceSources)> {
+    let sample_price = |instant: OffsetDateTime| async move {
+        let prices = get_prices(
+            pricefeeds,
+            instant,
+            oracle.asset_pair_info.asset_pair,
+            &oracle.oracle_config.pricefeeds,
+            &oracle.health.pricefeeds,
         )
         .await;
+        if !prices.is_empty() {
+            oracle.health.record_price_response();
+        }
+        aggregate_prices(oracle, prices, price_aggregation_type)
+    };
+    let Some(twap) = &oracle.oracle_config.twap else {
+        return sample_price(maturation).await;
+    };
 
-        match aggregate_price(
-            &prices,
-            price_aggregation_type,
-            self.oracle.asset_pair_info.asset_pair,
-        ) {
-            None => Err(OracleSchedulerError::PriceFeedError(
-                PriceFeedError::InternalError("it seems all price feeds have failed".to_string()),
-            )),
-            Some(avg_price) => {
-                let avg_price_binary = format!(
-                    "{:0width$b}",
-                    avg_price as u64,
-                    width = self.oracle.asset_pair_info.event_descriptor.num_digits as usize
-                );
-                let outcomes = avg_price_binary
-                    .chars()
-                    .map(|char| char.to_string())
-                    .collect::<Vec<_>>();
-                let mut db_value = self
-                    .db_values
-                    .remove()
-                    .expect("db_values should never be empty");
-                let attestation = build_attestation(
-                    &db_value
-                        .0
-                        .take()
-                        .expect("immature db_values should always have outstanding_sk_nonces"),
-                    &self.oracle.keypair,
-                    &self.secp,
-                    outcomes,
-                    signing_version,
-                );
+    let instants = twap_sample_instants(maturation, twap);
+    let samples = join_all(instants.iter().map(|instant| sample_price(*instant))).await;
+    let mut twap_sources = PriceSources {
+        aggregation_type: price_aggregation_type,
+        prices: Vec::new(),
+    };
+    let mut sample_prices = Vec::new();
+    let mut last_error = None;
+    for (instant, sample) in instants.into_iter().zip(samples) {
+        match sample {
+            Ok((price, price_sources)) => {
+                let sampled_at = instant.format(&Rfc3339).unwrap();
+                sample_prices.push(price);
+                twap_sources
+                    .prices
+                    .extend(
+                        price_sources
+                            .prices
+                            .into_iter()
+                            .map(|price_source| PriceSource {
+                                sampled_at: Some(sampled_at.clone()),
+                                ..price_source
+                            }),
+                    );
+            }
+            Err(err) => {
+                warn!("leaving TWAP sample at {instant} out: {err}");
+                last_error = Some(err);
+            }
+        }
+    }
+    match last_error {
+        Some(err) if sample_prices.is_empty() => Err(err),
+        _ => Ok((
+            sample_prices.iter().sum::<f64>() / sample_prices.len() as f64,
+            twap_sources,
+        )),
+    }
+}
 
-                let mut attestation_bytes = Vec::new();
-                write_as_tlv(&attestation, &mut attestation_bytes)
-                    .expect("Error writing attestation");
+/// Returns the equally spaced instants at which prices are sampled for the TWAP at
+/// `maturation`, from the start of the window up to and including `maturation`
+fn twap_sample_instants(maturation: OffsetDateTime, twap: &TwapConfig) -> Vec<OffsetDateTime> {
+    let samples = twap.window.whole_seconds() / twap.interval.whole_seconds();
+    (0..=samples)
+        .rev()
+        .map(|sample| maturation - twap.interval * sample as i32)
+        .collect()
+}
 
-                db_value.2 = Some(attestation_bytes);
-                db_value.3 = Some(avg_price as u64);
-                info!(
-                    "attesting with maturation {} and attestation {:#?}",
-                    self.next_attestation, attestation
-                );
-                self.oracle.event_database.insert(
-                    self.next_attestation.format(&Rfc3339).unwrap().into_bytes(),
-                    serde_json::to_string(&db_value)?.into_bytes(),
-                )?;
-                self.next_attestation += self.oracle.oracle_config.frequency;
-                Ok(())
+/// Aggregates the prices retrieved from the pricefeeds of `oracle`, leaving out outliers if
+/// configured. Fails if fewer prices than the configured quorum are left.
+fn aggregate_prices(
+    oracle: &Oracle,
+    prices: Vec<FeedPrice>,
+    price_aggregation_type: AggregationType,
+) -> Result<(f64, PriceSources)> {
+    let asset_pair = oracle.asset_pair_info.asset_pair;
+    let prices = match price_aggregation_type {
+        AggregationType::Vwap => prices
+            .into_iter()
+            .filter(|feed_price| {
+                let has_volume = feed_price.volume.is_some_and(|volume| volume > 0.0);
+                if !has_volume {
+                    warn!(
+                        "leaving {} price of {} out of the VWAP, no volume reported",
+                        feed_price.pricefeed, asset_pair
+                    );
+                }
+                has_volume
+            })
+            .collect(),
+        _ => prices,
+    };
+    let prices = match oracle.oracle_config.pricefeeds.max_deviation_percent {
+        Some(max_deviation_percent) => reject_outliers(prices, max_deviation_percent, asset_pair),
+        None => prices,
+    };
+    let min_price_feeds = oracle.oracle_config.pricefeeds.min_price_feeds;
+    if prices.len() < min_price_feeds {
+        return Err(OracleSchedulerError::InsufficientPriceFeedsError(
+            prices.len(),
+            min_price_feeds,
+        ));
+    }
+    let price =
+        aggregate_feed_prices(&prices, price_aggregation_type, asset_pair).ok_or_else(|| {
+            OracleSchedulerError::PriceFeedError(PriceFeedError::InternalError(
+                "it seems all price feeds have failed".to_string(),
+            ))
+        })?;
+    let price_sources = PriceSources {
+        aggregation_type: price_aggregation_type,
+        prices: prices.iter().map(PriceSource::from).collect(),
+    };
+    Ok((price, price_sources))
+}
+
+/// Signs and stores the attestation of the announced oracle event maturing at `maturation`,
+/// along with the `price_sources` of `outcome` if it was aggregated from pricefeeds.
+/// The attestation is only stored if the event has not been attested in the meantime, since
+/// signing two different outcomes with the same nonces would leak the oracle's secret key.
+fn store_attestation(
+    oracle: &Oracle,
+    secp: &Secp256k1<All>,
+    maturation: OffsetDateTime,
+    outcome: i64,
+    price_sources: Option<&PriceSources>,
+    signing_version: SigningVersion,
+) -> Result<()> {
+    let release = oracle.attestation_release(maturation);
+    let maturation_time = maturation;
+    let maturation = maturation.format(&Rfc3339).unwrap();
+    let stored = oracle
+        .event_database
+        .get(&maturation)?
+        .ok_or_else(|| OracleSchedulerError::OracleEventNotFoundError(maturation.clone()))?;
+    let mut db_value: DbValue = serde_json::from_slice(&stored)?;
+    if db_value.5 {
+        return Err(OracleSchedulerError::OracleEventCancelledError(maturation));
+    }
+    let outstanding_sk_nonces = match (&db_value.2, db_value.0.take()) {
+        (None, Some(outstanding_sk_nonces)) => outstanding_sk_nonces,
+        _ => {
+            return Err(OracleSchedulerError::OracleEventAlreadyAttestedError(
+                maturation,
+            ))
+        }
+    };
+
+    let oracle_event = db_value.announcement().oracle_event;
+    let outcomes = attested_outcomes(&oracle_event.event_descriptor, outcome)?;
+    // refuses other outcomes for good once signed, even if the event is deleted and announced
+    // again
+    oracle.record_signed_nonces(&oracle_event.oracle_nonces, &outcomes, &maturation)?;
+    let attestation = build_attestation(
+        &outstanding_sk_nonces,
+        &oracle.keypair,
+        secp,
+        outcomes,
+        signing_version,
+    );
+
+    let attestation_bytes = tlv::encode(&attestation);
+    let update = OracleEventUpdate {
+        kind: OracleEventKind::Attestation,
+        asset_pair: oracle.asset_pair_info.asset_pair,
+        event_id: db_value.event_id(),
+        maturation: maturation.clone(),
+        payload: attestation_bytes.encode_hex(),
+    };
+
+    db_value.2 = Some(attestation_bytes);
+    db_value.3 = Some(outcome);
+    if !oracle.event_database.update(
+        &maturation,
+        &stored,
+        serde_json::to_string(&db_value)?.as_bytes(),
+    )? {
+        // attested first by another writer, e.g. an instance sharing the event database. The
+        // recorded nonces only let both sign the same outcome, i.e. the same signatures
+        return Err(OracleSchedulerError::OracleEventAlreadyAttestedError(
+            maturation,
+        ));
+    }
+    info!(
+        "attesting with maturation {} and attestation {:#?}",
+        maturation, attestation
+    );
+    if let Some(price_sources) = price_sources {
+        oracle.record_price_sources(&maturation, price_sources)?;
+    }
+    PendingJob::Attestation.complete(&oracle.jobs, maturation_time)?;
+    oracle.remove_failed_attestation(&maturation)?;
+    metrics::ATTESTATIONS_SIGNED
+        .with_label_values(&[&oracle.asset_pair().to_string()])
+        .inc();
+    metrics::record_database_size(oracle);
+    let embargo = release - OffsetDateTime::now_utc();
+    if embargo.is_positive() {
+        info!("withholding attestation with maturation {maturation} until {release}");
+        let event_updates = oracle.event_updates.clone();
+        tokio::spawn(async move {
+            tokio::time::sleep(embargo.unsigned_abs()).await;
+            let _ = event_updates.send(update);
+        });
+        return Ok(());
+    }
+    // sending only fails if nobody is listening
+    let _ = oracle.event_updates.send(update);
+    Ok(())
+}
+
+/// Returns the outcomes to sign for `outcome`, i.e. its digits, preceded by its sign for signed
+/// events, for numeric events and the outcome with index `outcome` for enum events
+fn attested_outcomes(event_descriptor: &EventDescriptor, outcome: i64) -> Result<Vec<String>> {
+    match event_descriptor {
+        EventDescriptor::DigitDecompositionEvent(descriptor) => {
+            if outcome < 0 && !descriptor.is_signed {
+                return Err(OracleSchedulerError::InvalidOutcomeError(outcome));
+            }
+            let base = u64::from(descriptor.base);
+            let magnitude = outcome.unsigned_abs();
+            if base
+                .checked_pow(descriptor.nb_digits.into())
+                .is_some_and(|max| magnitude >= max)
+            {
+                return Err(OracleSchedulerError::InvalidOutcomeError(outcome));
+            }
+            let mut digits = (0..descriptor.nb_digits)
+                .scan(magnitude, |rest, _| {
+                    let digit = *rest % base;
+                    *rest /= base;
+                    Some(digit.to_string())
+                })
+                .collect::<Vec<_>>();
+            if descriptor.is_signed {
+                // the sign is signed with its own nonce, before the most significant digit
+                digits.push(if outcome < 0 { "-" } else { "+" }.to_string());
             }
+            digits.reverse();
+            Ok(digits)
+        }
+        EventDescriptor::EnumEvent(descriptor) => usize::try_from(outcome)
+            .ok()
+            .and_then(|index| descriptor.outcomes.get(index))
+            .map(|outcome| vec![outcome.clone()])
+            .ok_or(OracleSchedulerError::InvalidOutcomeError(outcome)),
+    }
+}
+
+/// Attests the announced oracle event maturing at `maturation` out of schedule, e.g. after its
+/// scheduled attestation failed, with `outcome` if given or else with the outcome determined
+/// like for scheduled attestations. Returns the attested outcome.
+pub async fn force_attestation(
+    oracle: &Oracle,
+    pricefeeds: &[Box<dyn PriceFeed + Send + Sync>],
+    maturation: OffsetDateTime,
+    outcome: Option<i64>,
+) -> Result<i64> {
+    let (outcome, price_sources) = match outcome {
+        Some(outcome) => (outcome, None),
+        None => {
+            info!("retrieving outcome for forced attestation");
+            scheduled_outcome(
+                oracle,
+                pricefeeds,
+                maturation,
+                oracle.oracle_config.price_aggregation_type,
+            )
+            .await?
+            .ok_or_else(|| {
+                OracleSchedulerError::OutcomeRequiredError(maturation.format(&Rfc3339).unwrap())
+            })?
+        }
+    };
+    store_attestation(
+        oracle,
+        &Secp256k1::new(),
+        maturation,
+        outcome,
+        price_sources.as_ref(),
+        oracle.oracle_config.signing_version,
+    )?;
+    Ok(outcome)
+}
+
+/// Outcome of a dry run of the scheduled events of an asset pair, see [`dry_run`]
+#[derive(Debug, Serialize)]
+pub struct DryRun {
+    pub asset_pair: AssetPair,
+    /// additional schedule of the asset pair, unset for its main schedule
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub schedule: Option<String>,
+    /// maturation of the next scheduled oracle event
+    pub maturation: String,
+    pub event_id: String,
+    /// hex-encoded TLV of the unsigned oracle event that would be announced
+    pub oracle_event: String,
+    /// outcome the event would be attested with if it matured now, unset for events attested
+    /// by the operator
+    pub outcome: Option<i64>,
+    /// outcomes that would be signed for `outcome`, one per nonce
+    pub attested_outcomes: Vec<String>,
+    pub price_sources: Option<PriceSources>,
+}
+
+/// Runs the pipeline of the scheduled events of `oracle` without signing or storing anything:
+/// builds the oracle event of the next maturation after `now` and determines the outcome it
+/// would be attested with from the prices retrieved and aggregated at `now`
+pub async fn dry_run(
+    oracle: &Oracle,
+    pricefeeds: &[Box<dyn PriceFeed + Send + Sync>],
+    now: OffsetDateTime,
+) -> Result<DryRun> {
+    let asset_pair_info = &oracle.asset_pair_info;
+    let maturation = oracle.next_maturation(now);
+    let event_descriptor = EventDescriptor::from(asset_pair_info.event_descriptor.clone());
+    let (oracle_event, _) = build_oracle_event(
+        event_descriptor.clone(),
+        asset_pair_info.event_id(maturation),
+        asset_pair_info.asset_pair,
+        &oracle.keypair,
+        &Secp256k1::new(),
+        maturation,
+    )?;
+    info!(
+        "dry run: built oracle event {} maturing at {maturation}, retrieving outcome",
+        oracle_event.event_id
+    );
+    let (outcome, price_sources) = match event_outcome(
+        oracle,
+        &event_descriptor,
+        pricefeeds,
+        now,
+        oracle.oracle_config.price_aggregation_type,
+    )
+    .await?
+    {
+        Some((outcome, price_sources)) => (Some(outcome), price_sources),
+        None => (None, None),
+    };
+    let attested_outcomes = match outcome {
+        Some(outcome) => attested_outcomes(&event_descriptor, outcome)?,
+        None => Vec::new(),
+    };
+    Ok(DryRun {
+        asset_pair: asset_pair_info.asset_pair,
+        schedule: asset_pair_info.schedule.clone(),
+        maturation: maturation.format(&Rfc3339).unwrap(),
+        event_id: oracle_event.event_id.clone(),
+        oracle_event: tlv::encode(&oracle_event).encode_hex(),
+        outcome,
+        attested_outcomes,
+        price_sources,
+    })
+}
+
+/// Cancels the unattested oracle event maturing at `maturation`, e.g. after an exchange outage
+/// or a configuration mistake, and discards its nonces so it is never attested with a regular
+/// outcome. Enum events offering the `cancellation_outcome` of the asset pair are attested with
+/// it beforehand, so contracts can take their refund path. Returns the attested cancellation
+/// outcome, if any
+pub fn cancel_event(oracle: &Oracle, maturation: OffsetDateTime) -> Result<Option<i64>> {
+    let maturation_key = maturation.format(&Rfc3339).unwrap();
+    let stored = oracle
+        .event_database
+        .get(&maturation_key)?
+        .ok_or_else(|| OracleSchedulerError::OracleEventNotFoundError(maturation_key.clone()))?;
+    let db_value: DbValue = serde_json::from_slice(&stored)?;
+    if db_value.5 {
+        return Err(OracleSchedulerError::OracleEventCancelledError(
+            maturation_key,
+        ));
+    }
+    if db_value.2.is_some() {
+        return Err(OracleSchedulerError::OracleEventAlreadyAttestedError(
+            maturation_key,
+        ));
+    }
+
+    let cancellation_outcome = match (
+        &oracle.asset_pair_info.cancellation_outcome,
+        &db_value.announcement().oracle_event.event_descriptor,
+    ) {
+        (Some(outcome), EventDescriptor::EnumEvent(descriptor)) => descriptor
+            .outcomes
+            .iter()
+            .position(|enum_outcome| enum_outcome == outcome)
+            .map(|index| index as i64),
+        _ => None,
+    };
+    if let Some(outcome) = cancellation_outcome {
+        store_attestation(
+            oracle,
+            &Secp256k1::new(),
+            maturation,
+            outcome,
+            None,
+            oracle.oracle_config.signing_version,
+        )?;
+    }
+
+    // reread, since the cancellation outcome was attested in the meantime
+    let stored = oracle
+        .event_database
+        .get(&maturation_key)?
+        .ok_or_else(|| OracleSchedulerError::OracleEventNotFoundError(maturation_key.clone()))?;
+    let mut db_value: DbValue = serde_json::from_slice(&stored)?;
+    db_value.0 = None;
+    db_value.5 = true;
+    if !oracle.event_database.update(
+        &maturation_key,
+        &stored,
+        serde_json::to_string(&db_value)?.as_bytes(),
+    )? {
+        return Err(OracleSchedulerError::OracleEventAlreadyAttestedError(
+            maturation_key,
+        ));
+    }
+    Ok(cancellation_outcome)
+}
+
+/// Attests the announced oracle events that matured before `until` without being attested,
+/// e.g. while the oracle was down, with the outcome at their maturation: those within the
+/// catch-up window and those whose attestation job is still pending. Events announced with
+/// another descriptor than the scheduled ones, like ad-hoc events, enum events without resolver
+/// and events rebuilt by [`recover_events`], which may have been attested before, are left to the
+/// operator. Returns the number of events attested
+async fn catch_up_attestations(
+    oracle_scheduler: &Mutex<OracleScheduler>,
+    oracle: &Oracle,
+    pricefeeds: &[Box<dyn PriceFeed + Send + Sync>],
+    until: OffsetDateTime,
+) -> Result<usize> {
+    let from = until - oracle.oracle_config.catch_up_window;
+    let event_descriptor = EventDescriptor::from(oracle.asset_pair_info.event_descriptor.clone());
+    let mut candidates = PendingJob::Attestation
+        .pending(&oracle.jobs)?
+        .into_iter()
+        .filter(|maturation| *maturation < until)
+        .collect::<Vec<_>>();
+    for entry in oracle.event_database.range((
+        Bound::Included(from.format(&Rfc3339).unwrap()),
+        Bound::Excluded(until.format(&Rfc3339).unwrap()),
+    ))? {
+        let (maturation, _) = entry?;
+        candidates.push(
+            OffsetDateTime::parse(&maturation, &Rfc3339).expect("maturation keys to be RFC3339"),
+        );
+    }
+    candidates.sort();
+    candidates.dedup();
+    let mut overdue = Vec::new();
+    for maturation in candidates {
+        let event = match oracle
+            .event_database
+            .get(&maturation.format(&Rfc3339).unwrap())?
+        {
+            Some(event) => serde_json::from_slice::<DbValue>(&event)?,
+            None => {
+                PendingJob::Attestation.complete(&oracle.jobs, maturation)?;
+                continue;
+            }
+        };
+        // attested, e.g. forced, or cancelled by the operator
+        if event.2.is_some() || event.5 {
+            PendingJob::Attestation.complete(&oracle.jobs, maturation)?;
+        } else if event.4 {
+            // rebuilt by recover, it may have been attested with another outcome before
+            PendingJob::Attestation.complete(&oracle.jobs, maturation)?;
+        } else if event.announcement().oracle_event.event_descriptor == event_descriptor {
+            overdue.push(maturation);
+        }
+    }
+
+    let mut attested = 0;
+    for maturation in overdue {
+        let (outcome, price_sources) = match scheduled_outcome(
+            oracle,
+            pricefeeds,
+            maturation,
+            oracle.oracle_config.price_aggregation_type,
+        )
+        .await
+        {
+            Ok(Some(outcome)) => outcome,
+            Ok(None) => {
+                PendingJob::Attestation.complete(&oracle.jobs, maturation)?;
+                continue;
+            }
+            Err(err) => {
+                warn!("cannot catch up attestation of oracle event with maturation {maturation}: {err}");
+                continue;
+            }
+        };
+        if oracle_scheduler.lock().await.stopped {
+            break;
+        }
+        warn!(
+            "attesting oracle event with maturation {maturation} late, {} after maturation",
+            OffsetDateTime::now_utc() - maturation
+        );
+        let caught_up = match store_attestation(
+            oracle,
+            &Secp256k1::new(),
+            maturation,
+            outcome,
+            price_sources.as_ref(),
+            oracle.oracle_config.signing_version,
+        ) {
+            // attested, deleted or cancelled by the operator in the meantime
+            Err(
+                OracleSchedulerError::OracleEventAlreadyAttestedError(_)
+                | OracleSchedulerError::OracleEventNotFoundError(_)
+                | OracleSchedulerError::OracleEventCancelledError(_),
+            ) => false,
+            result => {
+                result?;
+                true
+            }
+        };
+        PendingJob::Attestation.complete(&oracle.jobs, maturation)?;
+        if !caught_up {
+            continue;
+        }
+        metrics::LATE_ATTESTATIONS
+            .with_label_values(&[&oracle.asset_pair().to_string()])
+            .inc();
+        attested += 1;
+    }
+    Ok(attested)
+}
+
+/// Handle to a running oracle scheduler
+pub struct OracleSchedulerHandle {
+    oracle_scheduler: Arc<Mutex<OracleScheduler>>,
+}
+
+impl OracleSchedulerHandle {
+    /// Waits for an in-flight announcement or attestation to finish, stops scheduling new ones
+    /// and flushes the event database
+    pub async fn shutdown(&self) -> Result<()> {
+        let mut oracle_scheduler = self.oracle_scheduler.lock().await;
+        oracle_scheduler.stopped = true;
+        info!(
+            "flushing event database of {}",
+            oracle_scheduler.oracle.asset_pair()
+        );
+        oracle_scheduler.oracle.flush().await?;
+        Ok(())
+    }
+}
+
+/// Announces the next scheduled oracle event
+async fn announce_scheduled(
+    oracle_scheduler: Arc<Mutex<OracleScheduler>>,
+    error_transmitter: mpsc::UnboundedSender<OracleSchedulerError>,
+) {
+    if let Err(err) = oracle_scheduler.lock().await.create_scheduler_event() {
+        info!("error from announcement scheduler");
+        error_transmitter.send(err).unwrap();
+    }
+}
+
+/// Attests the next scheduled oracle event, retrying the attestation if it fails, e.g. if the
+/// pricefeeds lack quorum
+async fn attest_scheduled(
+    oracle_scheduler: Arc<Mutex<OracleScheduler>>,
+    error_transmitter: mpsc::UnboundedSender<OracleSchedulerError>,
+    signing_version: SigningVersion,
+    price_aggregation_type: AggregationType,
+) {
+    // prices at maturation are retrieved either way, the delay only spreads the requests
+    let jitter = fetch_jitter(&oracle_scheduler.lock().await.oracle);
+    if !jitter.is_zero() {
+        info!("delaying price retrieval of attestation by {jitter:?}");
+        sleep(jitter).await;
+    }
+    let mut locked_scheduler = oracle_scheduler.lock().await;
+    let maturation = locked_scheduler.next_attestation;
+    // moves on even if deferred, so retries never hold up the following attestations
+    locked_scheduler.next_attestation = locked_scheduler.oracle.following_maturation(maturation);
+    match locked_scheduler
+        .attest(maturation, signing_version, price_aggregation_type)
+        .await
+    {
+        Err(err) => {
+            // retry outside of the scheduler, which waits for its jobs to finish
+            warn!("deferring attestation with maturation {maturation}: {err}");
+            tokio::spawn(retry_attestation(
+                oracle_scheduler.clone(),
+                maturation,
+                err.to_string(),
+                error_transmitter,
+                signing_version,
+                price_aggregation_type,
+            ));
+        }
+        Ok(()) => {}
+    }
+}
+
+/// Returns a random delay of up to the `fetch_jitter` of the pricefeeds of `oracle`, by which
+/// its price retrieval is spread from that of other asset pairs due at the same time
+fn fetch_jitter(oracle: &Oracle) -> std::time::Duration {
+    match oracle.oracle_config.pricefeeds.fetch_jitter {
+        Some(jitter) if !jitter.is_zero() => {
+            secp256k1_zkp::rand::thread_rng().gen_range(std::time::Duration::ZERO..=jitter)
+        }
+        _ => std::time::Duration::ZERO,
+    }
+}
+
+/// Announces and attests the oracle events of a cron schedule, times of maturation or a schedule
+/// in a timezone other than UTC when they are due. Every due announcement and attestation is run
+/// once, like the jobs of schedules by frequency, even if it fails
+async fn run_calendar_schedule(
+    oracle_scheduler: Arc<Mutex<OracleScheduler>>,
+    error_transmitter: mpsc::UnboundedSender<OracleSchedulerError>,
+    signing_version: SigningVersion,
+    price_aggregation_type: AggregationType,
+) {
+    let (oracle, mut announcement, mut attestation) = {
+        let oracle_scheduler = oracle_scheduler.lock().await;
+        (
+            oracle_scheduler.oracle.clone(),
+            oracle_scheduler.next_announcement,
+            oracle_scheduler.next_attestation,
+        )
+    };
+    let announcement_offset = oracle.oracle_config.announcement_offset;
+    loop {
+        let due = announcement.min(attestation);
+        let wait = due - OffsetDateTime::now_utc();
+        if wait.is_positive() {
+            sleep(wait.unsigned_abs()).await;
+        }
+        if announcement == due {
+            announce_scheduled(oracle_scheduler.clone(), error_transmitter.clone()).await;
+            announcement = oracle.following_maturation(announcement + announcement_offset)
+                - announcement_offset;
+        }
+        if attestation == due {
+            attest_scheduled(
+                oracle_scheduler.clone(),
+                error_transmitter.clone(),
+                signing_version,
+                price_aggregation_type,
+            )
+            .await;
+            attestation = oracle.following_maturation(attestation);
         }
     }
 }
@@ -179,163 +1126,323 @@ impl OracleScheduler {
 pub fn init(
     oracle: Oracle,
     secp: Secp256k1<All>,
-    pricefeeds: Vec<Box<dyn PriceFeed + Send + Sync>>,
+    pricefeeds: Arc<Vec<Box<dyn PriceFeed + Send + Sync>>>,
     signing_version: SigningVersion,
     price_aggregation_type: AggregationType,
-) -> Result<()> {
-    // start event creation task
+) -> Result<OracleSchedulerHandle> {
     info!("creating oracle events and schedules");
+    let (tx, mut rx) = mpsc::unbounded_channel();
+    let oracle_scheduler = create_events(
+        oracle,
+        secp,
+        pricefeeds,
+        tx,
+        signing_version,
+        price_aggregation_type,
+    )?;
     tokio::spawn(async move {
-        let (tx, mut rx) = mpsc::unbounded_channel();
-        if let Err(err) = create_events(
-            oracle,
-            secp,
-            pricefeeds,
-            tx,
-            signing_version,
-            price_aggregation_type,
-        ) {
-            error!("oracle scheduler create_events error: {}", err);
-        } else {
-            while let Some(err) = rx.recv().await {
-                error!("oracle scheduler error: {}", err);
-            }
+        while let Some(err) = rx.recv().await {
+            error!("oracle scheduler error: {}", err);
         }
-        // never be reached
-        unreachable!()
     });
-    Ok(())
+    Ok(OracleSchedulerHandle { oracle_scheduler })
 }
 
 fn create_events(
     mut oracle: Oracle,
     secp: Secp256k1<All>,
-    pricefeeds: Vec<Box<dyn PriceFeed + Send + Sync>>,
+    pricefeeds: Arc<Vec<Box<dyn PriceFeed + Send + Sync>>>,
     error_transmitter: mpsc::UnboundedSender<OracleSchedulerError>,
     signing_version: SigningVersion,
     price_aggregation_type: AggregationType,
-) -> Result<()> {
+) -> Result<Arc<Mutex<OracleScheduler>>> {
     let now = OffsetDateTime::now_utc();
-    let mut next_attestation = now.replace_time(oracle.oracle_config.attestation_time);
-    if next_attestation <= now {
-        next_attestation += oracle.oracle_config.frequency;
-    }
+    let next_attestation = oracle.next_maturation(now);
     let mut next_announcement = next_attestation - oracle.oracle_config.announcement_offset;
-    let mut db_values = queue![];
+    // resume the announcement that fell due while the oracle was down, which is off the schedule
+    // if it was changed in the meantime
+    for maturation in PendingJob::Announcement.pending(&oracle.jobs)? {
+        if maturation - oracle.oracle_config.announcement_offset <= now
+            && maturation > now
+            && oracle
+                .event_database
+                .get(&maturation.format(&Rfc3339).unwrap())?
+                .is_none()
+        {
+            info!("resuming announcement of oracle event with maturation {maturation}");
+            create_event(&mut oracle, &secp, maturation, signing_version)?;
+        }
+        PendingJob::Announcement.complete(&oracle.jobs, maturation)?;
+    }
     // create all events that should have already been made
     info!("creating events that should have already been made");
     while next_announcement <= now {
         let next_attestation = next_announcement + oracle.oracle_config.announcement_offset;
         match oracle
             .event_database
-            .get(next_attestation.format(&Rfc3339).unwrap())?
+            .get(&next_attestation.format(&Rfc3339).unwrap())?
         {
-            None => create_event(
-                &mut oracle,
-                &secp,
-                &mut db_values,
-                next_attestation,
-                signing_version,
-            )?,
-            Some(val) => {
+            None => create_event(&mut oracle, &secp, next_attestation, signing_version)?,
+            Some(_) => {
                 info!(
                     "existing oracle event found in db with maturation {}, skipping creation",
                     next_attestation
                 );
-                db_values
-                    .add(serde_json::from_str(&String::from_utf8_lossy(&val))?)
-                    .unwrap();
             }
         };
-        next_announcement += oracle.oracle_config.frequency;
+        next_announcement = oracle.following_maturation(next_attestation)
+            - oracle.oracle_config.announcement_offset;
     }
-    let oracle_scheduler = Arc::new(Mutex::new(OracleScheduler {
+    PendingJob::Announcement.record(
+        &oracle.jobs,
+        next_announcement + oracle.oracle_config.announcement_offset,
+    )?;
+    let mut oracle_scheduler = OracleScheduler {
         oracle: oracle.clone(),
         secp,
-        pricefeeds,
-        db_values,
+        pricefeeds: pricefeeds.clone(),
         next_announcement,
         next_attestation,
         signing_version,
-    }));
-    info!(
-        "created new oracle scheduler with\n\tannouncements at {}\n\tattestations at {}\n\tfrequency of {}\n\tnext announcement at {}\n\tnext attestation at {}",
-        oracle.oracle_config.attestation_time - oracle.oracle_config.announcement_offset,
-        oracle.oracle_config.attestation_time,
-        oracle.oracle_config.frequency,
-        next_announcement,
-        next_attestation
-    );
-
+        stopped: false,
+    };
+    let pregenerated = oracle_scheduler.pregenerate_events()?;
+    if pregenerated > 0 {
+        info!("pregenerated {pregenerated} oracle events");
+    }
+    let oracle_scheduler = Arc::new(Mutex::new(oracle_scheduler));
     let mut scheduler = AsyncScheduler::with_tz(Utc);
-    // schedule announcements
-    let error_transmitter_clone = error_transmitter.clone();
-    let oracle_scheduler_clone = oracle_scheduler.clone();
-    let interval = Interval::Seconds(
-        oracle
-            .oracle_config
-            .frequency
-            .whole_seconds()
-            .try_into()
-            .unwrap(),
+    match (
+        &oracle.asset_pair_info.cron,
+        oracle.asset_pair_info.timezone,
+    ) {
+        (None, None) if !oracle.is_calendar_schedule() => {
+            info!(
+                "created new oracle scheduler with\n\tannouncements at {}\n\tattestations at {}\n\tfrequency of {}\n\tnext announcement at {}\n\tnext attestation at {}",
+                oracle.oracle_config.attestation_time - oracle.oracle_config.announcement_offset,
+                oracle.oracle_config.attestation_time,
+                oracle.oracle_config.frequency,
+                next_announcement,
+                next_attestation
+            );
+            let interval = Interval::Seconds(
+                oracle
+                    .oracle_config
+                    .frequency
+                    .whole_seconds()
+                    .try_into()
+                    .unwrap(),
+            );
+            // schedule announcements
+            info!("starting announcement scheduler");
+            let announcement_scheduler = oracle_scheduler.clone();
+            let announcement_error_transmitter = error_transmitter.clone();
+            scheduler
+                .every(interval)
+                .at(&(oracle.oracle_config.attestation_time
+                    - oracle.oracle_config.announcement_offset)
+                    .format(&format_description!("[hour]:[minute]:[second]"))
+                    .unwrap())
+                .run(move || {
+                    announce_scheduled(
+                        announcement_scheduler.clone(),
+                        announcement_error_transmitter.clone(),
+                    )
+                });
+            // schedule attestations
+            info!("starting attestation scheduler");
+            let attestation_scheduler = oracle_scheduler.clone();
+            let attestation_error_transmitter = error_transmitter.clone();
+            scheduler
+                .every(interval)
+                .at(&oracle
+                    .oracle_config
+                    .attestation_time
+                    .format(&format_description!("[hour]:[minute]:[second]"))
+                    .unwrap())
+                .run(move || {
+                    attest_scheduled(
+                        attestation_scheduler.clone(),
+                        attestation_error_transmitter.clone(),
+                        signing_version,
+                        price_aggregation_type,
+                    )
+                });
+        }
+        (cron, timezone) => {
+            let maturations = match cron {
+                Some(cron) => cron.to_string(),
+                None if !oracle.maturation_times().is_empty() => format!(
+                    "{} daily",
+                    oracle
+                        .maturation_times()
+                        .iter()
+                        .map(ToString::to_string)
+                        .collect::<Vec<_>>()
+                        .join(", ")
+                ),
+                None => format!(
+                    "{} every {}",
+                    oracle.oracle_config.attestation_time, oracle.oracle_config.frequency
+                ),
+            };
+            info!(
+                "created new oracle scheduler with\n\tmaturations at {} in {}\n\tannouncements {} ahead\n\tnext announcement at {}\n\tnext attestation at {}",
+                maturations,
+                timezone.unwrap_or(Tz::UTC),
+                oracle.oracle_config.announcement_offset,
+                next_announcement,
+                next_attestation
+            );
+            // cron schedules and times of day are irregular and those in a timezone shift with
+            // daylight saving time, so events are announced and attested when due
+            info!("starting calendar scheduler");
+            let calendar_scheduler = oracle_scheduler.clone();
+            let calendar_error_transmitter = error_transmitter.clone();
+            supervise(
+                oracle.health.clone(),
+                oracle.asset_pair(),
+                "calendar",
+                move || {
+                    run_calendar_schedule(
+                        calendar_scheduler.clone(),
+                        calendar_error_transmitter.clone(),
+                        signing_version,
+                        price_aggregation_type,
+                    )
+                },
+            );
+        }
+    }
+    // catch up attestations missed before the next scheduled one, first of all those missed
+    // while the oracle was down
+    info!("starting catch-up of missed attestations");
+    let catch_up_scheduler = oracle_scheduler.clone();
+    let catch_up_oracle = oracle.clone();
+    let catch_up_pricefeeds = pricefeeds.clone();
+    let catch_up_error_transmitter = error_transmitter.clone();
+    supervise(
+        oracle.health.clone(),
+        oracle.asset_pair(),
+        "catch-up",
+        move || {
+            let catch_up_scheduler = catch_up_scheduler.clone();
+            let catch_up_oracle = catch_up_oracle.clone();
+            let catch_up_pricefeeds = catch_up_pricefeeds.clone();
+            let catch_up_error_transmitter = catch_up_error_transmitter.clone();
+            async move {
+                // spread from the catch-up of other asset pairs starting at the same time
+                sleep(fetch_jitter(&catch_up_oracle)).await;
+                loop {
+                    let until = catch_up_scheduler.lock().await.next_attestation;
+                    match catch_up_attestations(
+                        &catch_up_scheduler,
+                        &catch_up_oracle,
+                        &catch_up_pricefeeds,
+                        until,
+                    )
+                    .await
+                    {
+                        Ok(0) => {}
+                        Ok(attested) => info!("caught up {attested} missed attestations"),
+                        Err(err) => catch_up_error_transmitter.send(err).unwrap(),
+                    }
+                    sleep(CATCH_UP_INTERVAL).await;
+                }
+            }
+        },
     );
-    info!("starting announcement scheduler");
-    scheduler
-        .every(interval)
-        .at(
-            &(oracle.oracle_config.attestation_time - oracle.oracle_config.announcement_offset)
-                .format(&format_description!("[hour]:[minute]:[second]"))
-                .unwrap(),
-        )
-        .run(move || {
-            let oracle_scheduler_clone = oracle_scheduler_clone.clone();
-            let error_transmitter_clone = error_transmitter_clone.clone();
+    // prune old attested events as configured by the retention policy
+    if oracle.oracle_config.retention.is_enabled() {
+        info!("starting pruning of old oracle events");
+        let pruning_oracle = oracle.clone();
+        let pruning_error_transmitter = error_transmitter.clone();
+        supervise(
+            oracle.health.clone(),
+            oracle.asset_pair(),
+            "pruning",
+            move || {
+                let oracle = pruning_oracle.clone();
+                let error_transmitter = pruning_error_transmitter.clone();
+                async move {
+                    let interval = oracle
+                        .oracle_config
+                        .retention
+                        .interval
+                        .unwrap_or(DEFAULT_PRUNING_INTERVAL);
+                    loop {
+                        match prune_events(&oracle, OffsetDateTime::now_utc()) {
+                            Ok(0) => {}
+                            Ok(pruned) => info!(
+                                "pruned {pruned} attested oracle events of {}, {} in total",
+                                oracle.asset_pair(),
+                                oracle.pruned_events().unwrap_or_default()
+                            ),
+                            Err(err) => error_transmitter.send(err).unwrap(),
+                        }
+                        sleep(interval).await;
+                    }
+                }
+            },
+        );
+    }
+    // busy checking scheduler, whose jobs run within, so a panicking announcement or attestation
+    // restarts it. Attestations missed that way are caught up
+    info!("starting busy checking");
+    let scheduler = Arc::new(Mutex::new(scheduler));
+    let health = oracle.health.clone();
+    supervise(
+        oracle.health.clone(),
+        oracle.asset_pair(),
+        "scheduler",
+        move || {
+            let scheduler = scheduler.clone();
+            let health = health.clone();
             async move {
-                if let Err(err) = oracle_scheduler_clone.lock().await.create_scheduler_event() {
-                    info!("error from announcement scheduler");
-                    error_transmitter_clone.send(err).unwrap();
+                loop {
+                    scheduler.lock().await.run_pending().await;
+                    health.record_scheduler_heartbeat();
+                    sleep(SCHEDULER_SLEEP_TIME).await;
                 }
             }
-        });
-    // schedule attestations
-    info!("starting attestation scheduler");
-    scheduler
-        .every(interval)
-        .at(&oracle
-            .oracle_config
-            .attestation_time
-            .format(&format_description!("[hour]:[minute]:[second]"))
-            .unwrap())
-        .run(move || {
-            let oracle_scheduler_clone = oracle_scheduler.clone();
-            let error_transmitter_clone = error_transmitter.clone();
+        },
+    );
+    // periodically probe pricefeeds so readiness reflects their current state
+    info!("starting pricefeed probing");
+    let probe_oracle = oracle.clone();
+    supervise(
+        oracle.health.clone(),
+        oracle.asset_pair(),
+        "probe",
+        move || {
+            let oracle = probe_oracle.clone();
+            let pricefeeds = pricefeeds.clone();
             async move {
-                if let Err(err) = oracle_scheduler_clone
-                    .lock()
-                    .await
-                    .attest(signing_version, price_aggregation_type)
-                    .await
-                {
-                    info!("error from attestation scheduler");
-                    error_transmitter_clone.send(err).unwrap();
+                let asset_pair = oracle.asset_pair_info.asset_pair;
+                sleep(fetch_jitter(&oracle)).await;
+                loop {
+                    let prices = probe_prices(
+                        &pricefeeds,
+                        OffsetDateTime::now_utc(),
+                        asset_pair,
+                        &oracle.oracle_config.pricefeeds,
+                        &oracle.health.pricefeeds,
+                    )
+                    .await;
+                    if !prices.is_empty() {
+                        oracle.health.record_price_response();
+                    }
+                    sleep(PRICE_PROBE_INTERVAL).await;
                 }
             }
-        });
-    // busy checking scheduler
-    info!("starting busy checking");
-    tokio::spawn(async move {
-        loop {
-            scheduler.run_pending().await;
-            sleep(SCHEDULER_SLEEP_TIME).await;
-        }
-    });
-    Ok(())
+        },
+    );
+    Ok(oracle_scheduler)
 }
 
 fn create_event(
     oracle: &mut Oracle,
     secp: &Secp256k1<All>,
-    db_values: &mut Queue<DbValue>,
     maturation: OffsetDateTime,
     signing_version: SigningVersion,
 ) -> Result<()> {
@@ -346,20 +1453,163 @@ fn create_event(
         maturation,
         signing_version,
     )?;
+    store_announcement(
+        oracle,
+        &announcement,
+        outstanding_sk_nonces,
+        maturation,
+        false,
+    )?;
+    PendingJob::Attestation.record(&oracle.jobs, maturation)
+}
+
+/// Rebuilds the announcements of the scheduled events maturing since `since` that are missing
+/// from the event database, e.g. after it was lost. Nonces are derived from the oracle key, so
+/// the rebuilt announcements match the published ones and their events can still be attested.
+/// Events that matured already may have been attested before the loss, so they are marked as
+/// recovered and left to the operator instead of being attested automatically. Returns the
+/// number of recovered announcements
+pub fn recover_events(
+    oracle: &mut Oracle,
+    secp: &Secp256k1<All>,
+    since: OffsetDateTime,
+    signing_version: SigningVersion,
+) -> Result<usize> {
+    let now = OffsetDateTime::now_utc();
+    let announcement_offset = oracle.oracle_config.announcement_offset;
+    let mut maturation = if oracle.is_calendar_schedule() {
+        oracle.following_maturation(since - time::Duration::NANOSECOND)
+    } else {
+        let mut maturation = oracle.daily_maturation(since);
+        while maturation < since {
+            maturation = oracle.following_maturation(maturation);
+        }
+        maturation
+    };
+    let mut recovered = 0;
+    while maturation - announcement_offset <= now {
+        let maturation_key = maturation.format(&Rfc3339).unwrap();
+        match oracle.event_database.get(&maturation_key)? {
+            None => {
+                info!("recovering announcement of oracle event with maturation {maturation_key}");
+                if maturation > now {
+                    create_event(oracle, secp, maturation, signing_version)?;
+                } else {
+                    let (announcement, outstanding_sk_nonces) = build_announcement(
+                        &oracle.asset_pair_info,
+                        &oracle.keypair,
+                        secp,
+                        maturation,
+                        signing_version,
+                    )?;
+                    store_announcement(
+                        oracle,
+                        &announcement,
+                        outstanding_sk_nonces,
+                        maturation,
+                        true,
+                    )?;
+                }
+                recovered += 1;
+            }
+            Some(event) => {
+                let event: DbValue = serde_json::from_slice(&event)?;
+                let (announcement, _) = build_announcement(
+                    &oracle.asset_pair_info,
+                    &oracle.keypair,
+                    secp,
+                    maturation,
+                    signing_version,
+                )?;
+                if tlv::encode(&announcement) != event.1 {
+                    warn!(
+                        "stored announcement with maturation {maturation_key} differs from the rebuilt one, it was announced with random nonces or another configuration"
+                    );
+                }
+            }
+        }
+        maturation = oracle.following_maturation(maturation);
+    }
+    Ok(recovered)
+}
+
+/// Creates a one-off oracle event with the given descriptor and event id maturing at
+/// `maturation`, independent of the scheduled ones
+pub fn create_adhoc_event(
+    oracle: &Oracle,
+    event_descriptor: EventDescriptor,
+    event_id: String,
+    maturation: OffsetDateTime,
+) -> Result<OracleAnnouncement> {
+    if !event_id.is_empty() && oracle.event_maturation(&event_id)?.is_some() {
+        return Err(OracleSchedulerError::EventIdAlreadyExistsError(event_id));
+    }
+    let (announcement, outstanding_sk_nonces) = build_event_announcement(
+        event_descriptor,
+        event_id,
+        oracle.asset_pair_info.asset_pair,
+        &oracle.keypair,
+        &Secp256k1::new(),
+        maturation,
+        oracle.oracle_config.signing_version,
+    )?;
+    store_announcement(
+        oracle,
+        &announcement,
+        outstanding_sk_nonces,
+        maturation,
+        false,
+    )?;
+    Ok(announcement)
+}
 
-    let mut announcement_bytes = Vec::new();
-    write_as_tlv(&announcement, &mut announcement_bytes).expect("Error writing announcement");
+/// Stores a new announcement, refusing to overwrite an existing oracle event of the same
+/// maturation. `recovered` marks announcements rebuilt after their maturation by
+/// [`recover_events`], which are never attested automatically
+fn store_announcement(
+    oracle: &Oracle,
+    announcement: &OracleAnnouncement,
+    outstanding_sk_nonces: Vec<[u8; 32]>,
+    maturation: OffsetDateTime,
+    recovered: bool,
+) -> Result<()> {
+    let announcement_bytes = tlv::encode(announcement);
+    let update = OracleEventUpdate {
+        kind: OracleEventKind::Announcement,
+        asset_pair: oracle.asset_pair_info.asset_pair,
+        event_id: announcement.oracle_event.event_id.clone(),
+        maturation: maturation.format(&Rfc3339).unwrap(),
+        payload: announcement_bytes.encode_hex(),
+    };
 
-    let db_value = DbValue(Some(outstanding_sk_nonces), announcement_bytes, None, None);
+    let db_value = DbValue(
+        Some(outstanding_sk_nonces),
+        announcement_bytes,
+        None,
+        None,
+        recovered,
+        false,
+    );
     info!(
         "creating oracle event (announcement only) with maturation {} and announcement {:#?}",
         maturation, announcement
     );
-    oracle.event_database.insert(
-        maturation.format(&Rfc3339).unwrap().into_bytes(),
-        serde_json::to_string(&db_value)?.into_bytes(),
-    )?;
-    db_values.add(db_value).unwrap();
+    let maturation_key = maturation.format(&Rfc3339).unwrap();
+    if !oracle.event_database.insert(
+        &maturation_key,
+        &announcement.oracle_event.event_id,
+        serde_json::to_string(&db_value)?.as_bytes(),
+    )? {
+        return Err(OracleSchedulerError::OracleEventAlreadyExistsError(
+            maturation_key,
+        ));
+    }
+    metrics::ANNOUNCEMENTS_CREATED
+        .with_label_values(&[&oracle.asset_pair().to_string()])
+        .inc();
+    metrics::record_database_size(oracle);
+    // sending only fails if nobody is listening
+    let _ = oracle.event_updates.send(update);
     Ok(())
 }
 
@@ -370,13 +1620,66 @@ pub fn build_announcement(
     maturation: OffsetDateTime,
     signing_version: SigningVersion,
 ) -> Result<(OracleAnnouncement, Vec<[u8; 32]>)> {
-    let mut rng = rand::thread_rng();
-    let digits = asset_pair_info.event_descriptor.num_digits;
+    build_event_announcement(
+        asset_pair_info.clone().event_descriptor.into(),
+        asset_pair_info.event_id(maturation),
+        asset_pair_info.asset_pair,
+        keypair,
+        secp,
+        maturation,
+        signing_version,
+    )
+}
+
+/// Derives the secret nonce of the digit at `index` of an event from the oracle key and the
+/// event, so announcements can be rebuilt identically after losing the event database
+fn derive_sk_nonce(
+    keypair: &KeyPair,
+    asset_pair: AssetPair,
+    event_descriptor: &EventDescriptor,
+    event_id: &str,
+    maturation: OffsetDateTime,
+    index: u16,
+) -> [u8; 32] {
+    let mut engine = hmac::HmacEngine::<sha256::Hash>::new(&keypair.secret_bytes());
+    engine.input(b"sibyls/nonce/v0");
+    engine.input(asset_pair.to_string().as_bytes());
+    engine.input(&event_descriptor.encode());
+    engine.input(&(event_id.len() as u64).to_be_bytes());
+    engine.input(event_id.as_bytes());
+    engine.input(&maturation.unix_timestamp().to_be_bytes());
+    engine.input(&index.to_be_bytes());
+    hmac::Hmac::<sha256::Hash>::from_engine(engine).into_inner()
+}
+
+/// Builds the unsigned oracle event with `event_descriptor` and `event_id` maturing at
+/// `maturation`, with nonces derived from the oracle key, along with their secret nonces
+fn build_oracle_event(
+    event_descriptor: EventDescriptor,
+    event_id: String,
+    asset_pair: AssetPair,
+    keypair: &KeyPair,
+    secp: &Secp256k1<All>,
+    maturation: OffsetDateTime,
+) -> Result<(OracleEvent, Vec<[u8; 32]>)> {
+    let digits = match &event_descriptor {
+        // signed events have an additional nonce for the sign
+        EventDescriptor::DigitDecompositionEvent(descriptor) => {
+            descriptor.nb_digits + u16::from(descriptor.is_signed)
+        }
+        EventDescriptor::EnumEvent(_) => 1,
+    };
     let mut sk_nonces = Vec::with_capacity(digits.into());
     let mut oracle_nonces = Vec::with_capacity(digits.into());
-    for _ in 0..digits {
-        let mut sk_nonce = [0u8; 32];
-        rng.fill_bytes(&mut sk_nonce);
+    for index in 0..digits {
+        let sk_nonce = derive_sk_nonce(
+            keypair,
+            asset_pair,
+            &event_descriptor,
+            &event_id,
+            maturation,
+            index,
+        );
         let oracle_r_kp = secp256k1_zkp::KeyPair::from_seckey_slice(secp, &sk_nonce)?;
         let nonce = SchnorrPublicKey::from_keypair(&oracle_r_kp).0;
         sk_nonces.push(sk_nonce);
@@ -386,10 +1689,31 @@ pub fn build_announcement(
     let oracle_event = OracleEvent {
         oracle_nonces,
         event_maturity_epoch: maturation.unix_timestamp() as u32,
-        event_descriptor: asset_pair_info.clone().event_descriptor.into(),
-        event_id: "".to_string(),
+        event_descriptor,
+        event_id,
     };
 
+    Ok((oracle_event, sk_nonces))
+}
+
+pub fn build_event_announcement(
+    event_descriptor: EventDescriptor,
+    event_id: String,
+    asset_pair: AssetPair,
+    keypair: &KeyPair,
+    secp: &Secp256k1<All>,
+    maturation: OffsetDateTime,
+    signing_version: SigningVersion,
+) -> Result<(OracleAnnouncement, Vec<[u8; 32]>)> {
+    let (oracle_event, sk_nonces) = build_oracle_event(
+        event_descriptor,
+        event_id,
+        asset_pair,
+        keypair,
+        secp,
+        maturation,
+    )?;
+
     let msg = match signing_version {
         SigningVersion::Basic => {
             let mut event_bytes = Vec::new();
@@ -399,13 +1723,12 @@ pub fn build_announcement(
             Message::from_hashed_data::<sha256::Hash>(&event_bytes)
         }
         SigningVersion::DLCv0 => {
-            let mut event_bytes = Vec::new();
-            write_as_tlv(&oracle_event, &mut event_bytes).expect("Error writing oracle event");
-            Message::from_hashed_data::<DLCV0AnnouncementHash>(&event_bytes)
+            Message::from_hashed_data::<DLCV0AnnouncementHash>(&tlv::encode(&oracle_event))
         }
     };
 
-    let announcement_signature = secp.sign_schnorr(&msg, keypair);
+    // without auxiliary randomness, so rebuilt announcements are identical
+    let announcement_signature = secp.sign_schnorr_no_aux_rand(&msg, keypair);
 
     Ok((
         OracleAnnouncement {
@@ -449,11 +1772,12 @@ pub fn build_attestation(
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::{AssetPair, SerializableEventDescriptor};
+    use crate::{oracle::EventType, AssetPair, SerializableEventDescriptor};
     use dlc::OracleInfo;
+    use dlc_messages::oracle_msgs::{DigitDecompositionEventDescriptor, EnumEventDescriptor};
     use dlc_messages::ser_impls::write_as_tlv;
     use secp256k1::Scalar;
-    use secp256k1_zkp::rand::{distributions::Alphanumeric, Rng};
+    use secp256k1_zkp::rand::{self, distributions::Alphanumeric, Rng, RngCore};
 
     fn setup() -> (KeyPair, Secp256k1<All>) {
         let secp = Secp256k1::new();
@@ -576,6 +1900,183 @@ mod tests {
         .unwrap();
     }
 
+    #[test]
+    fn announcements_are_rebuilt_identically() {
+        let (keypair, secp) = setup();
+        let maturation = time::macros::datetime!(2022-06-07 08:00 UTC);
+        let build = |event_id: &str, asset_pair| {
+            build_event_announcement(
+                EventDescriptor::EnumEvent(EnumEventDescriptor {
+                    outcomes: vec!["yes".to_string(), "no".to_string()],
+                }),
+                event_id.to_string(),
+                asset_pair,
+                &keypair,
+                &secp,
+                maturation,
+                SigningVersion::DLCv0,
+            )
+            .unwrap()
+        };
+        let (announcement, sk_nonces) = build("btcusd1654588800", AssetPair::BTCUSD);
+        let (rebuilt, rebuilt_sk_nonces) = build("btcusd1654588800", AssetPair::BTCUSD);
+        assert_eq!(announcement, rebuilt);
+        assert_eq!(sk_nonces, rebuilt_sk_nonces);
+
+        let (other_event, _) = build("btcusd1654592400", AssetPair::BTCUSD);
+        let (other_asset_pair, _) = build("btcusd1654588800", AssetPair::BTCUSDT);
+        for other in [other_event, other_asset_pair] {
+            assert_ne!(
+                announcement.oracle_event.oracle_nonces,
+                other.oracle_event.oracle_nonces
+            );
+        }
+    }
+
+    #[test]
+    fn stored_events_tell_their_type() {
+        let (keypair, secp) = setup();
+        let (announcement, _) = build_event_announcement(
+            EventDescriptor::EnumEvent(EnumEventDescriptor {
+                outcomes: vec!["yes".to_string(), "no".to_string()],
+            }),
+            "btc-halving".to_string(),
+            AssetPair::BTCUSD,
+            &keypair,
+            &secp,
+            time::macros::datetime!(2024-04-20 00:00 UTC),
+            SigningVersion::Basic,
+        )
+        .unwrap();
+        let event = DbValue(
+            None,
+            tlv::encode(&announcement),
+            None,
+            Some(1),
+            false,
+            false,
+        );
+        assert_eq!(EventType::Enum, event.event_type());
+        assert_eq!(Some("no".to_string()), event.outcome_name());
+
+        let announcement = build_test_announcement(&keypair, &secp, SigningVersion::Basic).0;
+        let event = DbValue(
+            None,
+            tlv::encode(&announcement),
+            None,
+            Some(30000),
+            false,
+            false,
+        );
+        assert_eq!(EventType::Numeric, event.event_type());
+        assert!(event.outcome_name().is_none());
+    }
+
+    #[test]
+    fn twap_samples_window_up_to_maturation() {
+        let maturation = OffsetDateTime::from_unix_timestamp(1_654_588_800).unwrap();
+        let twap = TwapConfig {
+            window: time::Duration::minutes(10),
+            interval: time::Duration::seconds(150),
+        };
+        let instants = twap_sample_instants(maturation, &twap);
+        assert_eq!(5, instants.len());
+        assert_eq!(maturation - twap.window, instants[0]);
+        assert_eq!(maturation, instants[4]);
+        assert!(instants
+            .windows(2)
+            .all(|pair| pair[1] - pair[0] == twap.interval));
+
+        let twap = TwapConfig {
+            window: time::Duration::ZERO,
+            interval: time::Duration::minutes(1),
+        };
+        assert_eq!(vec![maturation], twap_sample_instants(maturation, &twap));
+    }
+
+    #[test]
+    fn price_outcome_follows_precision() {
+        let half_up = RoundingMode::HalfUp;
+        assert_eq!(30123, price_outcome(30123.4, 0, half_up));
+        assert_eq!(30124, price_outcome(30123.5, 0, half_up));
+        assert_eq!(3012346, price_outcome(30123.456, -2, half_up));
+        assert_eq!(10843, price_outcome(1.08427, -4, half_up));
+        assert_eq!(301, price_outcome(30123.4, 2, half_up));
+        assert_eq!(-1250, price_outcome(-0.125, -4, half_up));
+    }
+
+    #[test]
+    fn price_outcome_follows_rounding_mode() {
+        use RoundingMode::*;
+        assert_eq!(30000, price_outcome(29999.5, 0, HalfUp));
+        assert_eq!(29999, price_outcome(29999.5, 0, Floor));
+        assert_eq!(30000, price_outcome(29999.5, 0, Ceil));
+        assert_eq!(29999, price_outcome(29999.2, 0, HalfUp));
+        assert_eq!(30000, price_outcome(29999.2, 0, Ceil));
+        assert_eq!(-2, price_outcome(-2.5, 0, HalfUp));
+        assert_eq!(-3, price_outcome(-2.5, 0, Floor));
+        assert_eq!(-2, price_outcome(-2.5, 0, Ceil));
+        assert_eq!(300, price_outcome(29999.5, 2, Ceil));
+        assert_eq!(299, price_outcome(29999.5, 2, Floor));
+        // exact prices stay put despite inexact scaling
+        assert_eq!(11, price_outcome(1.1, -1, Ceil));
+        assert_eq!(29, price_outcome(0.29, -2, Floor));
+    }
+
+    #[test]
+    fn attested_outcomes_follow_event_descriptor() {
+        let numeric = EventDescriptor::DigitDecompositionEvent(DigitDecompositionEventDescriptor {
+            base: 10,
+            is_signed: false,
+            unit: "BTCUSD".to_string(),
+            precision: 0,
+            nb_digits: 3,
+        });
+        assert_eq!(attested_outcomes(&numeric, 42).unwrap(), ["0", "4", "2"]);
+        assert!(attested_outcomes(&numeric, 1000).is_err());
+
+        let digits = |base, nb_digits, outcome| {
+            let numeric =
+                EventDescriptor::DigitDecompositionEvent(DigitDecompositionEventDescriptor {
+                    base,
+                    is_signed: false,
+                    unit: "BTCUSD".to_string(),
+                    precision: 0,
+                    nb_digits,
+                });
+            attested_outcomes(&numeric, outcome)
+        };
+        assert_eq!(digits(2, 6, 42).unwrap(), ["1", "0", "1", "0", "1", "0"]);
+        assert!(digits(2, 5, 42).is_err());
+        // hexadecimal digits are attested by their value
+        assert_eq!(digits(16, 3, 0x2af).unwrap(), ["2", "10", "15"]);
+        assert!(digits(16, 3, 0x1000).is_err());
+        assert!(digits(10, 3, -42).is_err());
+
+        let signed = EventDescriptor::DigitDecompositionEvent(DigitDecompositionEventDescriptor {
+            base: 10,
+            is_signed: true,
+            unit: "bps".to_string(),
+            precision: 0,
+            nb_digits: 3,
+        });
+        assert_eq!(
+            attested_outcomes(&signed, -42).unwrap(),
+            ["-", "0", "4", "2"]
+        );
+        assert_eq!(
+            attested_outcomes(&signed, 42).unwrap(),
+            ["+", "0", "4", "2"]
+        );
+        assert!(attested_outcomes(&signed, -1000).is_err());
+
+        let enumeration = EventDescriptor::EnumEvent(EnumEventDescriptor {
+            outcomes: vec!["yes".to_string(), "no".to_string()],
+        });
+        assert_eq!(attested_outcomes(&enumeration, 1).unwrap(), ["no"]);
+        assert!(attested_outcomes(&enumeration, 2).is_err());
+    }
+
     #[ignore]
     #[test]
     fn valid_adaptor_signature() {
@@ -650,9 +2151,25 @@ mod tests {
                     unit: "BTCUSD".to_string(),
                     precision: 0,
                     num_digits: 18,
+                    outcomes: vec![],
                 },
+                symbols: Default::default(),
                 include_price_feeds: vec![],
                 exclude_price_feeds: vec![],
+                price_feed_weights: Default::default(),
+                outcome_resolver: None,
+                cancellation_outcome: None,
+                event_id_format: None,
+                frequency: None,
+                announcement_offset: None,
+                attestation_time: None,
+                maturation_times: vec![],
+                cron: None,
+                timezone: None,
+                attestation_delay: None,
+                pregenerated_events: None,
+                schedules: vec![],
+                schedule: None,
             },
             &keypair,
             &secp,
@@ -0,0 +1,77 @@
+use super::{OracleSchedulerError, Result};
+use crate::{oracle::pricefeeds::fill_template, AssetPair};
+use log::{debug, info};
+use time::OffsetDateTime;
+use tokio::{process::Command, time::timeout};
+
+/// Time after which an outcome resolver command is killed
+const RESOLVER_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(60);
+
+/// Runs the outcome resolver `command` of the enum event of `asset_pair` maturing at
+/// `maturation` and returns the outcome it printed
+pub(super) async fn run_resolver_command(
+    command: &str,
+    args: &[String],
+    asset_pair: AssetPair,
+    maturation: OffsetDateTime,
+) -> Result<String> {
+    let args = args
+        .iter()
+        .map(|arg| fill_template(arg, &asset_pair.to_string(), maturation))
+        .collect::<std::result::Result<Vec<_>, _>>()?;
+    info!("running outcome resolver {command} for {asset_pair} {maturation}");
+    // the command is killed when it times out and its future is dropped
+    let output = timeout(
+        RESOLVER_TIMEOUT,
+        Command::new(command)
+            .args(&args)
+            .kill_on_drop(true)
+            .output(),
+    )
+    .await
+    .map_err(|_| {
+        OracleSchedulerError::OutcomeResolverError(format!(
+            "{command} timed out after {RESOLVER_TIMEOUT:?}"
+        ))
+    })?
+    .map_err(|err| {
+        OracleSchedulerError::OutcomeResolverError(format!("cannot run {command}: {err}"))
+    })?;
+    if !output.status.success() {
+        return Err(OracleSchedulerError::OutcomeResolverError(format!(
+            "{command} failed with {}: {}",
+            output.status,
+            String::from_utf8_lossy(&output.stderr).trim()
+        )));
+    }
+    let outcome = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    debug!("outcome resolver {command} printed {outcome}");
+    Ok(outcome)
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn resolve_printed_outcome() {
+        let outcome = run_resolver_command(
+            "echo",
+            &["{symbol}-{timestamp}".to_string()],
+            AssetPair::BTCUSD,
+            OffsetDateTime::from_unix_timestamp(1_654_588_800).unwrap(),
+        )
+        .await;
+        assert_eq!("BTCUSD-1654588800", outcome.unwrap());
+    }
+
+    #[tokio::test]
+    async fn failing_command() {
+        let outcome =
+            run_resolver_command("false", &[], AssetPair::BTCUSD, OffsetDateTime::now_utc()).await;
+        assert!(matches!(
+            outcome,
+            Err(OracleSchedulerError::OutcomeResolverError(_))
+        ));
+    }
+}
@@ -0,0 +1,125 @@
+use super::Result;
+use crate::{
+    metrics,
+    oracle::{DbValue, Oracle},
+    RetentionConfig,
+};
+use time::{format_description::well_known::Rfc3339, OffsetDateTime};
+
+/// Time between two prunings if the retention policy does not set one
+pub(super) const DEFAULT_PRUNING_INTERVAL: std::time::Duration =
+    std::time::Duration::from_secs(60 * 60);
+
+/// Prunes the attested oracle events of `oracle` due under its retention policy at `now`,
+/// returning how many were pruned
+pub(super) fn prune_events(oracle: &Oracle, now: OffsetDateTime) -> Result<usize> {
+    let events = oracle
+        .event_database
+        .iter()?
+        .map(|entry| {
+            let (maturation, event) = entry?;
+            let event: DbValue = serde_json::from_slice(&event)?;
+            Ok((maturation, event.2.is_some()))
+        })
+        .collect::<Result<Vec<_>>>()?;
+    let mut pruned = 0;
+    for maturation in prunable(&events, &oracle.oracle_config.retention, now) {
+        // attested events never change again, unless deleted meanwhile
+        if let Some(stored) = oracle.event_database.get(maturation)? {
+            if oracle.prune_event(maturation, &stored)? {
+                pruned += 1;
+            }
+        }
+    }
+    if pruned > 0 {
+        metrics::EVENTS_PRUNED
+            .with_label_values(&[&oracle.asset_pair().to_string()])
+            .inc_by(pruned as u64);
+        metrics::record_database_size(oracle);
+    }
+    Ok(pruned)
+}
+
+/// Returns the maturations of the attested ones of `events`, given as maturation and whether
+/// attested in order of maturation, that are due to be pruned under `retention` at `now`: those
+/// maturing more than `max_age` ago, and the oldest ones while more than `max_events` are stored
+fn prunable<'a>(
+    events: &'a [(String, bool)],
+    retention: &RetentionConfig,
+    now: OffsetDateTime,
+) -> Vec<&'a str> {
+    let oldest_kept = retention.max_age.map(|max_age| {
+        (now - max_age)
+            .replace_nanosecond(0)
+            .unwrap()
+            .format(&Rfc3339)
+            .unwrap()
+    });
+    let mut excess = retention
+        .max_events
+        .map_or(0, |max_events| events.len().saturating_sub(max_events));
+    let mut prunable = Vec::new();
+    for (maturation, attested) in events {
+        let expired = oldest_kept
+            .as_ref()
+            .is_some_and(|oldest_kept| maturation < oldest_kept);
+        if !expired && excess == 0 {
+            break;
+        }
+        if *attested {
+            prunable.push(maturation.as_str());
+            excess = excess.saturating_sub(1);
+        }
+    }
+    prunable
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use time::macros::datetime;
+
+    #[test]
+    fn only_old_attested_events_are_prunable() {
+        let events = [
+            ("2022-06-01T08:00:00Z", true),
+            ("2022-06-02T08:00:00Z", false),
+            ("2022-06-03T08:00:00Z", true),
+            ("2022-06-04T08:00:00Z", true),
+            ("2022-06-05T08:00:00Z", false),
+        ]
+        .map(|(maturation, attested)| (maturation.to_string(), attested));
+        let now = datetime!(2022-06-05 08:00:00.5 UTC);
+
+        assert!(prunable(&events, &RetentionConfig::default(), now).is_empty());
+        let retention = RetentionConfig {
+            max_age: Some(std::time::Duration::from_secs(2 * 24 * 60 * 60)),
+            ..Default::default()
+        };
+        assert_eq!(
+            vec!["2022-06-01T08:00:00Z"],
+            prunable(&events, &retention, now)
+        );
+        let retention = RetentionConfig {
+            max_events: Some(2),
+            ..Default::default()
+        };
+        assert_eq!(
+            vec![
+                "2022-06-01T08:00:00Z",
+                "2022-06-03T08:00:00Z",
+                "2022-06-04T08:00:00Z"
+            ],
+            prunable(&events, &retention, now)
+        );
+        let retention = RetentionConfig {
+            max_age: Some(std::time::Duration::from_secs(24 * 60 * 60)),
+            max_events: Some(4),
+            ..Default::default()
+        };
+        assert_eq!(
+            vec!["2022-06-01T08:00:00Z", "2022-06-03T08:00:00Z"],
+            prunable(&events, &retention, now)
+        );
+    }
+}
@@ -0,0 +1,73 @@
+use crate::{metrics, oracle::Health, AssetPair};
+use futures::Future;
+use log::error;
+use std::{any::Any, sync::Arc};
+use tokio::time::sleep;
+
+/// Delay before restarting a task that panicked, so one panicking right away does not spin
+const RESTART_DELAY: std::time::Duration = std::time::Duration::from_secs(5);
+
+/// Spawns the task built by `task` for the scheduler of `asset_pair`, isolated from the tasks of
+/// other asset pairs, and builds and spawns it again whenever it panics. Panics are logged and
+/// recorded in `health`. Supervision ends once the task returns.
+pub(super) fn supervise<F, Fut>(
+    health: Arc<Health>,
+    asset_pair: AssetPair,
+    name: &'static str,
+    task: F,
+) where
+    F: Fn() -> Fut + Send + 'static,
+    Fut: Future<Output = ()> + Send + 'static,
+{
+    tokio::spawn(async move {
+        loop {
+            match tokio::spawn(task()).await {
+                Ok(()) => return,
+                Err(err) if err.is_panic() => {
+                    let panic = panic_message(err.into_panic());
+                    error!(
+                        "{name} task of {asset_pair} panicked, restarting in {RESTART_DELAY:?}: {panic}"
+                    );
+                    health.record_scheduler_restart(panic);
+                    metrics::SCHEDULER_RESTARTS
+                        .with_label_values(&[&asset_pair.to_string(), name])
+                        .inc();
+                    sleep(RESTART_DELAY).await;
+                }
+                // cancelled as the runtime shuts down
+                Err(_) => return,
+            }
+        }
+    });
+}
+
+/// Returns the message a task panicked with, if it panicked with one
+fn panic_message(panic: Box<dyn Any + Send>) -> String {
+    match panic.downcast::<String>() {
+        Ok(message) => *message,
+        Err(panic) => panic.downcast_ref::<&str>().map_or_else(
+            || "unknown panic".to_string(),
+            |message| message.to_string(),
+        ),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn panic_messages_are_recovered() {
+        let panic = tokio::spawn(async { panic!("price of {} missing", "BTCUSD") })
+            .await
+            .unwrap_err()
+            .into_panic();
+        assert_eq!("price of BTCUSD missing", panic_message(panic));
+        let panic = tokio::spawn(async { panic!("no price") })
+            .await
+            .unwrap_err()
+            .into_panic();
+        assert_eq!("no price", panic_message(panic));
+        assert_eq!("unknown panic", panic_message(Box::new(42)));
+    }
+}
@@ -0,0 +1,140 @@
+use super::{FeedId, PriceFeed, PriceFeedError, Result};
+use crate::{AssetPair, MarketDataConfig};
+use async_trait::async_trait;
+use log::{debug, info};
+use reqwest::Client;
+use serde_json::Value;
+use time::OffsetDateTime;
+
+/// Forex rates of Alpha Vantage
+pub struct AlphaVantage {
+    pub config: MarketDataConfig,
+    pub client: Client,
+}
+
+/// Formats `instant` like the UTC timestamps of Alpha Vantage time series, which sort
+/// chronologically
+fn series_key(instant: OffsetDateTime) -> String {
+    format!(
+        "{}-{:02}-{:02} {:02}:{:02}:{:02}",
+        instant.year(),
+        u8::from(instant.month()),
+        instant.day(),
+        instant.hour(),
+        instant.minute(),
+        instant.second()
+    )
+}
+
+/// Returns the open of the bar starting at `key`, or else the close of the last bar before it
+fn price_at(series: &serde_json::Map<String, Value>, key: &str) -> Option<f64> {
+    let (bar_key, bar) = series
+        .iter()
+        .filter(|(bar_key, _)| bar_key.as_str() <= key)
+        .max_by(|(a, _), (b, _)| a.cmp(b))?;
+    let field = if bar_key == key {
+        "1. open"
+    } else {
+        "4. close"
+    };
+    bar.get(field)?.as_str()?.parse().ok()
+}
+
+#[async_trait]
+impl PriceFeed for AlphaVantage {
+    fn id(&self) -> &'static str {
+        "alphavantage"
+    }
+
+    fn translate_asset_pair(&self, asset_pair: AssetPair) -> Result<&'static str> {
+        match asset_pair {
+            AssetPair::EURUSD => Ok("EUR"),
+            _ => asset_pair
+                .symbol(&FeedId::AlphaVantage)
+                .ok_or(PriceFeedError::UnsupportedAssetPairError(asset_pair)),
+        }
+    }
+
+    async fn retrieve_price(&self, asset_pair: AssetPair, instant: OffsetDateTime) -> Result<f64> {
+        let client = &self.client;
+        let asset_pair_translation = self.translate_asset_pair(asset_pair)?;
+        info!("sending alphavantage http request {asset_pair} {instant}");
+        let res: Value = client
+            .get("https://www.alphavantage.co/query")
+            .query(&[
+                ("function", "FX_INTRADAY"),
+                ("from_symbol", asset_pair_translation),
+                ("to_symbol", "USD"),
+                ("interval", "1min"),
+                ("outputsize", "full"),
+                ("apikey", &self.config.api_key.0),
+            ])
+            .send()
+            .await?
+            .error_for_status()?
+            .json()
+            .await?;
+        debug!("received alphavantage response: {:#?}", res);
+
+        // errors and rate limits are reported with status 200
+        if let Some(message) = res
+            .get("Error Message")
+            .or_else(|| res.get("Information"))
+            .or_else(|| res.get("Note"))
+        {
+            return Err(PriceFeedError::InternalError(format!(
+                "alphavantage error: {}",
+                message
+            )));
+        }
+        let price = res
+            .get("Time Series FX (1min)")
+            .and_then(Value::as_object)
+            .and_then(|series| price_at(series, &series_key(instant)))
+            .ok_or(PriceFeedError::PriceNotAvailableError(asset_pair, instant))?;
+        info!("alphavantage price {price}");
+        Ok(price)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{ApiKey, AssetPair};
+
+    use super::*;
+
+    #[test]
+    fn price_at_maturation() {
+        let series = serde_json::json!({
+            "2022-05-31 07:59:00": {"1. open": "1.0730", "4. close": "1.0731"},
+            "2022-05-31 08:00:00": {"1. open": "1.0732", "4. close": "1.0733"},
+            "2022-05-27 20:59:00": {"1. open": "1.0720", "4. close": "1.0721"},
+        });
+        let series = series.as_object().unwrap();
+        let maturation = OffsetDateTime::from_unix_timestamp(1653984000).unwrap();
+        assert_eq!("2022-05-31 08:00:00", series_key(maturation));
+        assert_eq!(Some(1.0732), price_at(series, "2022-05-31 08:00:00"));
+        assert_eq!(Some(1.0721), price_at(series, "2022-05-29 08:00:00"));
+        assert_eq!(None, price_at(series, "2022-05-27 08:00:00"));
+    }
+
+    #[tokio::test]
+    async fn retrieve() {
+        let Ok(api_key) = std::env::var("ALPHA_VANTAGE_API_KEY") else {
+            return;
+        };
+        let feed = AlphaVantage {
+            config: MarketDataConfig {
+                api_key: ApiKey(api_key),
+            },
+            client: Client::new(),
+        };
+        let price = feed
+            .retrieve_price(
+                AssetPair::EURUSD,
+                OffsetDateTime::now_utc() - time::Duration::days(1),
+            )
+            .await;
+        assert!(price.is_ok(), "{:#?}", &price);
+    }
+}
@@ -0,0 +1,93 @@
+use super::{FeedId, PriceFeed, PriceFeedError, Quote, Result};
+use crate::AssetPair;
+use async_trait::async_trait;
+use log::{debug, info};
+use reqwest::Client;
+use serde_json::Value;
+use time::OffsetDateTime;
+
+#[derive(Default)]
+pub struct Binance {
+    pub client: Client,
+}
+
+#[async_trait]
+impl PriceFeed for Binance {
+    fn id(&self) -> &'static str {
+        "binance"
+    }
+
+    fn translate_asset_pair(&self, asset_pair: AssetPair) -> Result<&'static str> {
+        match asset_pair {
+            AssetPair::BTCUSD => Err(PriceFeedError::InternalError(
+                "binance does not support USD".to_string(),
+            )),
+            AssetPair::BTCUSDT => Ok("BTCUSDT"),
+            _ => asset_pair
+                .symbol(&FeedId::Binance)
+                .ok_or(PriceFeedError::UnsupportedAssetPairError(asset_pair)),
+        }
+    }
+
+    async fn retrieve_price(&self, asset_pair: AssetPair, instant: OffsetDateTime) -> Result<f64> {
+        Ok(self.retrieve_quote(asset_pair, instant).await?.price)
+    }
+
+    async fn retrieve_quote(
+        &self,
+        asset_pair: AssetPair,
+        instant: OffsetDateTime,
+    ) -> Result<Quote> {
+        let client = &self.client;
+        let asset_pair_translation = self.translate_asset_pair(asset_pair)?;
+        let start_time = instant.unix_timestamp() * 1000;
+        info!("sending binance http request {asset_pair} {instant}");
+        let res: Vec<Vec<Value>> = client
+            .get("https://api.binance.com/api/v3/klines")
+            .query(&[
+                ("symbol", asset_pair_translation),
+                ("interval", "1m"),
+                ("startTime", &start_time.to_string()),
+                ("limit", "1"),
+            ])
+            .send()
+            .await?
+            .json()
+            .await?;
+        debug!("received binance response: {:#?}", res);
+
+        // klines are [open time, open, high, low, close, volume, ...]
+        let kline = res
+            .first()
+            .ok_or(PriceFeedError::PriceNotAvailableError(asset_pair, instant))?;
+        let field = |index: usize| -> Result<f64> {
+            kline
+                .get(index)
+                .and_then(Value::as_str)
+                .and_then(|field| field.parse().ok())
+                .ok_or(PriceFeedError::PriceNotAvailableError(asset_pair, instant))
+        };
+        let price = field(1)?;
+        info!("binance price {price}");
+        Ok(Quote {
+            price,
+            volume: field(5).ok(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::AssetPair;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn retrieve() {
+        let feed = Binance::default();
+        let price = feed
+            .retrieve_price(AssetPair::BTCUSDT, OffsetDateTime::now_utc())
+            .await;
+        assert!(price.is_ok(), "{:#?}", &price);
+    }
+}
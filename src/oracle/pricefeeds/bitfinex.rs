@@ -1,13 +1,24 @@
-use super::{PriceFeed, PriceFeedError, Result};
+use super::{FeedId, PriceFeed, PriceFeedError, Result};
 use crate::AssetPair;
 use async_trait::async_trait;
 use log::{debug, info};
 use reqwest::Client;
+use serde::Deserialize;
 use time::OffsetDateTime;
 
-pub struct Bitfinex {}
+#[derive(Default)]
+pub struct Bitfinex {
+    pub client: Client,
+}
 
-type Response = Vec<Vec<f64>>;
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+enum Response {
+    /// trades are [id, timestamp in ms, amount, price]
+    Trades(Vec<(u64, u64, f64, f64)>),
+    /// errors are ["error", code, message]
+    Error(String, i64, String),
+}
 
 #[async_trait]
 impl PriceFeed for Bitfinex {
@@ -19,43 +30,48 @@ impl PriceFeed for Bitfinex {
         match asset_pair {
             AssetPair::BTCUSD => Ok("tBTCUSD"),
             AssetPair::BTCUSDT => Ok("tBTCUST"),
+            _ => asset_pair
+                .symbol(&FeedId::Bitfinex)
+                .ok_or(PriceFeedError::UnsupportedAssetPairError(asset_pair)),
         }
     }
 
     async fn retrieve_price(&self, asset_pair: AssetPair, instant: OffsetDateTime) -> Result<f64> {
-        let client = Client::new();
-        let asset_pair_translation = self.translate_asset_pair(asset_pair).unwrap();
-        let start_time: i64 = instant.unix_timestamp();
+        let client = &self.client;
+        let asset_pair_translation = self.translate_asset_pair(asset_pair)?;
+        let start_time = instant.unix_timestamp() * 1000;
 
         info!("sending bitfinex http request {asset_pair} {instant}");
+        // oldest first, so the first trade is the one closest to `instant`
         let res: Response = client
             .get(format!(
                 "https://api-pub.bitfinex.com/v2/trades/{}/hist",
                 asset_pair_translation
             ))
-            .query(&[("start", &start_time.to_string())])
+            .query(&[
+                ("start", start_time.to_string().as_str()),
+                ("sort", "1"),
+                ("limit", "1"),
+            ])
             .send()
             .await?
             .json()
             .await?;
-
-        if res.is_empty() {
-            return Err(PriceFeedError::InternalError(
-                "Invalid response from Bitfinex".to_string(),
-            ));
-        }
-
         debug!("received bitfinex response: {:#?}", res);
-        let price = res
+
+        let trades = match res {
+            Response::Trades(trades) => trades,
+            Response::Error(_, code, message) => {
+                return Err(PriceFeedError::InternalError(format!(
+                    "bitfinex error {code}: {message}"
+                )))
+            }
+        };
+        let (_, _, _, price) = trades
             .get(0)
-            .ok_or(PriceFeedError::PriceNotAvailableError(asset_pair, instant))?
-            .get(3)
-            .ok_or(PriceFeedError::PriceNotAvailableError(asset_pair, instant))?
-            .to_string()
-            .parse()
-            .unwrap();
+            .ok_or(PriceFeedError::PriceNotAvailableError(asset_pair, instant))?;
         info!("bitfinex price: {price}");
-        Ok(price)
+        Ok(*price)
     }
 }
 
@@ -67,11 +83,22 @@ mod tests {
 
     #[tokio::test]
     async fn retrieve() {
-        let feed = Bitfinex {};
-        let price = feed.retrieve_price(BTCUSD, OffsetDateTime::now_utc()).await;
+        let feed = Bitfinex::default();
+        let price = feed
+            .retrieve_price(
+                BTCUSD,
+                OffsetDateTime::now_utc() - time::Duration::minutes(5),
+            )
+            .await;
         match price {
             Ok(_) => assert!(true),
             Err(_) => assert!(false, "{:#?}", &price),
         }
     }
+
+    #[test]
+    fn parse_error_response() {
+        let res: Response = serde_json::from_str(r#"["error",10020,"symbol: invalid"]"#).unwrap();
+        assert!(matches!(res, Response::Error(_, 10020, _)));
+    }
 }
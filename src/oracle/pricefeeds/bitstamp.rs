@@ -1,4 +1,4 @@
-use super::{PriceFeed, PriceFeedError, Result};
+use super::{FeedId, PriceFeed, PriceFeedError, Quote, Result, WebSocketSubscription};
 use crate::AssetPair;
 use async_trait::async_trait;
 use log::{debug, info};
@@ -7,7 +7,10 @@ use serde::Deserialize;
 use serde_json::Value;
 use time::OffsetDateTime;
 
-pub struct Bitstamp {}
+#[derive(Default)]
+pub struct Bitstamp {
+    pub client: Client,
+}
 
 #[derive(Debug, Deserialize)]
 struct Response {
@@ -24,6 +27,7 @@ struct OhlcData {
 #[derive(Debug, Deserialize)]
 struct Ohlc {
     open: String,
+    volume: String,
 }
 
 #[async_trait]
@@ -36,11 +40,22 @@ impl PriceFeed for Bitstamp {
         match asset_pair {
             AssetPair::BTCUSD => Ok("btcusd"),
             AssetPair::BTCUSDT => Ok("btcusdt"),
+            _ => asset_pair
+                .symbol(&FeedId::Bitstamp)
+                .ok_or(PriceFeedError::UnsupportedAssetPairError(asset_pair)),
         }
     }
 
     async fn retrieve_price(&self, asset_pair: AssetPair, instant: OffsetDateTime) -> Result<f64> {
-        let client = Client::new();
+        Ok(self.retrieve_quote(asset_pair, instant).await?.price)
+    }
+
+    async fn retrieve_quote(
+        &self,
+        asset_pair: AssetPair,
+        instant: OffsetDateTime,
+    ) -> Result<Quote> {
+        let client = &self.client;
         let asset_pair_translation = self.translate_asset_pair(asset_pair).unwrap();
         let start_time = instant.unix_timestamp();
         info!("sending bitstamp http request {asset_pair} {instant}");
@@ -71,30 +86,50 @@ impl PriceFeed for Bitstamp {
             )));
         }
 
-        let price = res
-            .data
-            .unwrap()
+        let data = res.data.unwrap();
+        let ohlc = data
             .ohlc
             .get(0)
-            .ok_or(PriceFeedError::PriceNotAvailableError(asset_pair, instant))?
-            .open
-            .parse()
-            .unwrap();
+            .ok_or(PriceFeedError::PriceNotAvailableError(asset_pair, instant))?;
+        let price = ohlc.open.parse().unwrap();
         info!("bitstamp price {price}");
-        Ok(price)
+        Ok(Quote {
+            price,
+            volume: ohlc.volume.parse().ok(),
+        })
+    }
+
+    fn websocket(&self, asset_pair: AssetPair) -> Option<WebSocketSubscription> {
+        let channel = format!(
+            "live_trades_{}",
+            self.translate_asset_pair(asset_pair).ok()?
+        );
+        Some(WebSocketSubscription {
+            url: "wss://ws.bitstamp.net",
+            subscribe: serde_json::json!({
+                "event": "bts:subscribe",
+                "data": {"channel": channel},
+            }),
+            parse: |message| match message.get("event")?.as_str()? {
+                "trade" => message.pointer("/data/price")?.as_f64(),
+                _ => None,
+            },
+        })
     }
 }
 
 #[cfg(test)]
 mod tests {
-    use crate::AssetPair::*;
+    use crate::AssetPair;
 
     use super::*;
 
     #[tokio::test]
     async fn retrieve() {
-        let feed = Bitstamp {};
-        let price = feed.retrieve_price(BTCUSD, OffsetDateTime::now_utc()).await;
+        let feed = Bitstamp::default();
+        let price = feed
+            .retrieve_price(AssetPair::BTCUSD, OffsetDateTime::now_utc())
+            .await;
         match price {
             Ok(_) => assert!(true),
             Err(_) => assert!(false, "{:#?}", &price),
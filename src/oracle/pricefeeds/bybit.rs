@@ -0,0 +1,106 @@
+use super::{FeedId, PriceFeed, PriceFeedError, Result};
+use crate::AssetPair;
+use async_trait::async_trait;
+use log::{debug, info};
+use reqwest::Client;
+use serde::Deserialize;
+use time::OffsetDateTime;
+
+#[derive(Default)]
+pub struct Bybit {
+    pub client: Client,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Response {
+    ret_code: i64,
+    ret_msg: String,
+    result: Option<BybitResult>,
+}
+
+#[derive(Debug, Deserialize)]
+struct BybitResult {
+    /// klines are [start time, open, high, low, close, ...]
+    list: Vec<Vec<String>>,
+}
+
+#[async_trait]
+impl PriceFeed for Bybit {
+    fn id(&self) -> &'static str {
+        "bybit"
+    }
+
+    fn translate_asset_pair(&self, asset_pair: AssetPair) -> Result<&'static str> {
+        match asset_pair {
+            AssetPair::BTCUSD => Err(PriceFeedError::InternalError(
+                "bybit does not support USD".to_string(),
+            )),
+            AssetPair::BTCUSDT => Ok("BTCUSDT"),
+            _ => asset_pair
+                .symbol(&FeedId::Bybit)
+                .ok_or(PriceFeedError::UnsupportedAssetPairError(asset_pair)),
+        }
+    }
+
+    async fn retrieve_price(&self, asset_pair: AssetPair, instant: OffsetDateTime) -> Result<f64> {
+        let client = &self.client;
+        let asset_pair_translation = self.translate_asset_pair(asset_pair)?;
+        // klines are returned most recent first, so bound both ends to get the one at `instant`
+        let start_time = (instant.unix_timestamp() * 1000).to_string();
+        info!("sending bybit http request {asset_pair} {instant}");
+        let res: Response = client
+            .get("https://api.bybit.com/v5/market/kline")
+            .query(&[
+                ("category", "spot"),
+                ("symbol", asset_pair_translation),
+                ("interval", "1"),
+                ("start", &start_time),
+                ("end", &start_time),
+            ])
+            .send()
+            .await?
+            .json()
+            .await?;
+        debug!("received bybit response: {:#?}", res);
+
+        if res.ret_code != 0 {
+            return Err(PriceFeedError::InternalError(format!(
+                "bybit error {}: {}",
+                res.ret_code, res.ret_msg
+            )));
+        }
+
+        let price = res
+            .result
+            .ok_or(PriceFeedError::PriceNotAvailableError(asset_pair, instant))?
+            .list
+            .first()
+            .ok_or(PriceFeedError::PriceNotAvailableError(asset_pair, instant))?
+            .get(1)
+            .ok_or(PriceFeedError::PriceNotAvailableError(asset_pair, instant))?
+            .parse()
+            .map_err(|_| PriceFeedError::PriceNotAvailableError(asset_pair, instant))?;
+        info!("bybit price {price}");
+        Ok(price)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::AssetPair;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn retrieve() {
+        let feed = Bybit::default();
+        let price = feed
+            .retrieve_price(
+                AssetPair::BTCUSDT,
+                OffsetDateTime::now_utc() - time::Duration::minutes(2),
+            )
+            .await;
+        assert!(price.is_ok(), "{:#?}", &price);
+    }
+}
@@ -0,0 +1,116 @@
+use super::{FeedId, PriceFeed, PriceFeedError, Quote, Result, WebSocketSubscription};
+use crate::AssetPair;
+use async_trait::async_trait;
+use log::{debug, info};
+use reqwest::{header::USER_AGENT, Client};
+use time::{format_description::well_known::Rfc3339, Duration, OffsetDateTime};
+
+#[derive(Default)]
+pub struct Coinbase {
+    pub client: Client,
+}
+
+/// candles are [time, low, high, open, close, volume]
+type Response = Vec<Vec<f64>>;
+
+#[async_trait]
+impl PriceFeed for Coinbase {
+    fn id(&self) -> &'static str {
+        "coinbase"
+    }
+
+    fn translate_asset_pair(&self, asset_pair: AssetPair) -> Result<&'static str> {
+        match asset_pair {
+            AssetPair::BTCUSD => Ok("BTC-USD"),
+            AssetPair::BTCUSDT => Ok("BTC-USDT"),
+            _ => asset_pair
+                .symbol(&FeedId::Coinbase)
+                .ok_or(PriceFeedError::UnsupportedAssetPairError(asset_pair)),
+        }
+    }
+
+    async fn retrieve_price(&self, asset_pair: AssetPair, instant: OffsetDateTime) -> Result<f64> {
+        Ok(self.retrieve_quote(asset_pair, instant).await?.price)
+    }
+
+    async fn retrieve_quote(
+        &self,
+        asset_pair: AssetPair,
+        instant: OffsetDateTime,
+    ) -> Result<Quote> {
+        let client = &self.client;
+        let asset_pair_translation = self.translate_asset_pair(asset_pair)?;
+        let format_time = |time: OffsetDateTime| {
+            time.format(&Rfc3339)
+                .map_err(|err| PriceFeedError::InternalError(err.to_string()))
+        };
+        let start_time = format_time(instant)?;
+        let end_time = format_time(instant + Duration::minutes(1))?;
+        info!("sending coinbase http request {asset_pair} {instant}");
+        // the exchange API rejects requests without user agent
+        let res: Response = client
+            .get(format!(
+                "https://api.exchange.coinbase.com/products/{}/candles",
+                asset_pair_translation
+            ))
+            .header(USER_AGENT, "sibyls")
+            .query(&[
+                ("granularity", "60"),
+                ("start", &start_time),
+                ("end", &end_time),
+            ])
+            .send()
+            .await?
+            .error_for_status()?
+            .json()
+            .await?;
+        debug!("received coinbase response: {:#?}", res);
+
+        // candles are sorted most recent first
+        let candle = res
+            .last()
+            .ok_or(PriceFeedError::PriceNotAvailableError(asset_pair, instant))?;
+        let price = *candle
+            .get(3)
+            .ok_or(PriceFeedError::PriceNotAvailableError(asset_pair, instant))?;
+        info!("coinbase price {price}");
+        Ok(Quote {
+            price,
+            volume: candle.get(5).copied(),
+        })
+    }
+
+    fn websocket(&self, asset_pair: AssetPair) -> Option<WebSocketSubscription> {
+        Some(WebSocketSubscription {
+            url: "wss://ws-feed.exchange.coinbase.com",
+            subscribe: serde_json::json!({
+                "type": "subscribe",
+                "product_ids": [self.translate_asset_pair(asset_pair).ok()?],
+                "channels": ["ticker"],
+            }),
+            parse: |message| match message.get("type")?.as_str()? {
+                "ticker" => message.get("price")?.as_str()?.parse().ok(),
+                _ => None,
+            },
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::AssetPair;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn retrieve() {
+        let feed = Coinbase::default();
+        let price = feed
+            .retrieve_price(
+                AssetPair::BTCUSD,
+                OffsetDateTime::now_utc() - Duration::minutes(2),
+            )
+            .await;
+        assert!(price.is_ok(), "{:#?}", &price);
+    }
+}
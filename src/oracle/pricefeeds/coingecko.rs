@@ -0,0 +1,95 @@
+use super::{FeedId, PriceFeed, PriceFeedError, Result};
+use crate::{AssetPair, CoinGeckoConfig};
+use async_trait::async_trait;
+use log::{debug, info};
+use reqwest::Client;
+use serde::Deserialize;
+use time::OffsetDateTime;
+
+/// Aggregated price across the exchanges tracked by CoinGecko
+pub struct CoinGecko {
+    pub config: CoinGeckoConfig,
+    pub client: Client,
+}
+
+#[derive(Debug, Deserialize)]
+struct Response {
+    /// prices are [time in ms, price]
+    prices: Vec<(i64, f64)>,
+}
+
+#[async_trait]
+impl PriceFeed for CoinGecko {
+    fn id(&self) -> &'static str {
+        "coingecko"
+    }
+
+    fn translate_asset_pair(&self, asset_pair: AssetPair) -> Result<&'static str> {
+        match asset_pair {
+            AssetPair::BTCUSD => Ok("usd"),
+            AssetPair::BTCUSDT => Err(PriceFeedError::InternalError(
+                "coingecko does not support USDT".to_string(),
+            )),
+            _ => asset_pair
+                .symbol(&FeedId::CoinGecko)
+                .ok_or(PriceFeedError::UnsupportedAssetPairError(asset_pair)),
+        }
+    }
+
+    async fn retrieve_price(&self, asset_pair: AssetPair, instant: OffsetDateTime) -> Result<f64> {
+        let client = &self.client;
+        let vs_currency = self.translate_asset_pair(asset_pair)?;
+        // prices are sampled every 5 minutes for ranges below a day
+        let start_time = instant.unix_timestamp();
+        let (from, to) = (start_time - 5 * 60, start_time + 5 * 60);
+        let (host, key_header) = match self.config.pro {
+            true => ("pro-api.coingecko.com", "x-cg-pro-api-key"),
+            false => ("api.coingecko.com", "x-cg-demo-api-key"),
+        };
+        info!("sending coingecko http request {asset_pair} {instant}");
+        let mut request = client
+            .get(format!(
+                "https://{host}/api/v3/coins/bitcoin/market_chart/range"
+            ))
+            .query(&[
+                ("vs_currency", vs_currency),
+                ("from", &from.to_string()),
+                ("to", &to.to_string()),
+            ]);
+        if let Some(api_key) = &self.config.api_key {
+            request = request.header(key_header, &api_key.0);
+        }
+        let res: Response = request.send().await?.error_for_status()?.json().await?;
+        debug!("received coingecko response: {:#?}", res);
+
+        let (_, price) = res
+            .prices
+            .into_iter()
+            .min_by_key(|(time, _)| (time - start_time * 1000).abs())
+            .ok_or(PriceFeedError::PriceNotAvailableError(asset_pair, instant))?;
+        info!("coingecko price {price}");
+        Ok(price)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::AssetPair;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn retrieve() {
+        let feed = CoinGecko {
+            config: CoinGeckoConfig::default(),
+            client: Client::new(),
+        };
+        let price = feed
+            .retrieve_price(
+                AssetPair::BTCUSD,
+                OffsetDateTime::now_utc() - time::Duration::minutes(10),
+            )
+            .await;
+        assert!(price.is_ok(), "{:#?}", &price);
+    }
+}
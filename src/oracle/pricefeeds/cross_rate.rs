@@ -0,0 +1,165 @@
+use super::{proxied_client, PriceFeed, PriceFeedError, Quote, Result};
+use crate::{AssetPair, ForexConfig, PriceFeedsConfig};
+use async_trait::async_trait;
+use log::{debug, info};
+use parking_lot::Mutex;
+use reqwest::Client;
+use serde_json::Value;
+use std::{collections::HashMap, sync::Arc};
+use time::{Date, OffsetDateTime};
+
+/// Daily forex rates of USD, shared by all pricefeeds of a cross rate asset pair
+struct Forex {
+    config: ForexConfig,
+    client: Client,
+    /// rates of past days, which no longer change
+    cache: Mutex<HashMap<(&'static str, Date), f64>>,
+}
+
+impl Forex {
+    fn new(config: ForexConfig, client: Client) -> Self {
+        Forex {
+            config,
+            client,
+            cache: Mutex::default(),
+        }
+    }
+
+    fn url(&self, currency: &str, date: Date) -> String {
+        self.config
+            .url
+            .replace("{currency}", currency)
+            .replace("{date}", &date_string(date))
+    }
+
+    /// Returns the price of one USD in `currency` on the day of `instant`
+    async fn retrieve_rate(&self, currency: &'static str, instant: OffsetDateTime) -> Result<f64> {
+        let date = instant.date();
+        if let Some(rate) = self.cache.lock().get(&(currency, date)) {
+            return Ok(*rate);
+        }
+        info!("sending forex http request USD{currency} {date}");
+        let mut request = self.client.get(self.url(currency, date));
+        for (name, value) in &self.config.headers {
+            request = request.header(name, &value.0);
+        }
+        let res: Value = request.send().await?.error_for_status()?.json().await?;
+        debug!("received forex response: {:#?}", res);
+
+        let rate = match res.pointer(&self.config.rate_pointer.replace("{currency}", currency)) {
+            Some(Value::Number(rate)) => rate.as_f64(),
+            Some(Value::String(rate)) => rate.parse().ok(),
+            _ => None,
+        }
+        .ok_or_else(|| {
+            PriceFeedError::InternalError(format!("forex rate USD{currency} not available"))
+        })?;
+        info!("forex rate USD{currency} {rate}");
+        if date < OffsetDateTime::now_utc().date() {
+            self.cache.lock().insert((currency, date), rate);
+        }
+        Ok(rate)
+    }
+}
+
+fn date_string(date: Date) -> String {
+    format!(
+        "{}-{:02}-{:02}",
+        date.year(),
+        u8::from(date.month()),
+        date.day()
+    )
+}
+
+/// Pricefeed deriving cross rate asset pairs such as BTCEUR from the USD price of another
+/// pricefeed and the forex rate of the fiat currency
+struct CrossRate {
+    pricefeed: Box<dyn PriceFeed + Send + Sync>,
+    forex: Arc<Forex>,
+}
+
+#[async_trait]
+impl PriceFeed for CrossRate {
+    fn id(&self) -> &'static str {
+        self.pricefeed.id()
+    }
+
+    fn translate_asset_pair(&self, asset_pair: AssetPair) -> Result<&'static str> {
+        match asset_pair.cross_rate() {
+            Some((usd_pair, _)) => self.pricefeed.translate_asset_pair(usd_pair),
+            None => Err(PriceFeedError::UnsupportedAssetPairError(asset_pair)),
+        }
+    }
+
+    async fn retrieve_price(&self, asset_pair: AssetPair, datetime: OffsetDateTime) -> Result<f64> {
+        Ok(self.retrieve_quote(asset_pair, datetime).await?.price)
+    }
+
+    async fn retrieve_quote(
+        &self,
+        asset_pair: AssetPair,
+        datetime: OffsetDateTime,
+    ) -> Result<Quote> {
+        let (usd_pair, currency) = asset_pair
+            .cross_rate()
+            .ok_or(PriceFeedError::UnsupportedAssetPairError(asset_pair))?;
+        let (quote, rate) = futures::try_join!(
+            self.pricefeed.retrieve_quote(usd_pair, datetime),
+            self.forex.retrieve_rate(currency, datetime)
+        )?;
+        // the volume is traded in bitcoin either way
+        Ok(Quote {
+            price: quote.price * rate,
+            volume: quote.volume,
+        })
+    }
+
+    fn weight(&self) -> f64 {
+        self.pricefeed.weight()
+    }
+}
+
+/// Derives the prices of cross rate asset pairs from the USD prices of `pricefeeds` and the
+/// configured forex rates, so cross rates are aggregated like any other asset pair
+pub fn cross_rate_feeds(
+    pricefeeds: Vec<Box<dyn PriceFeed + Send + Sync>>,
+    config: &PriceFeedsConfig,
+) -> Result<Vec<Box<dyn PriceFeed + Send + Sync>>> {
+    let forex = Arc::new(Forex::new(
+        config.forex.clone(),
+        proxied_client(config.proxy.as_ref())?,
+    ));
+    Ok(pricefeeds
+        .into_iter()
+        .map(|pricefeed| -> Box<dyn PriceFeed + Send + Sync> {
+            Box::new(CrossRate {
+                pricefeed,
+                forex: forex.clone(),
+            })
+        })
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fill_url_template() {
+        let forex = Forex::new(ForexConfig::default(), Client::new());
+        let date = Date::from_calendar_date(2022, time::Month::May, 31).unwrap();
+        assert_eq!(
+            "https://api.frankfurter.app/2022-05-31?from=USD&to=EUR",
+            forex.url("EUR", date)
+        );
+    }
+
+    #[tokio::test]
+    async fn retrieve() {
+        let forex = Forex::new(ForexConfig::default(), Client::new());
+        let rate = forex
+            .retrieve_rate("EUR", OffsetDateTime::now_utc() - time::Duration::days(7))
+            .await;
+        assert!(rate.is_ok(), "{:#?}", &rate);
+    }
+}
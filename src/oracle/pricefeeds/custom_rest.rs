@@ -0,0 +1,129 @@
+use super::{PriceFeed, PriceFeedError, Result};
+use crate::{AssetPair, CustomRestFeedConfig};
+use async_trait::async_trait;
+use log::{debug, info};
+use reqwest::Client;
+use serde_json::Value;
+use std::collections::HashMap;
+use time::{format_description::well_known::Rfc3339, OffsetDateTime};
+
+/// Pricefeed fully defined in config, for exchanges or internal price services without a
+/// dedicated implementation
+pub struct CustomRestFeed {
+    id: &'static str,
+    symbols: HashMap<AssetPair, &'static str>,
+    config: CustomRestFeedConfig,
+    client: Client,
+}
+
+impl CustomRestFeed {
+    pub fn new(config: CustomRestFeedConfig, client: Client) -> Self {
+        // pricefeeds are created once at startup, so leaking the few configured strings is
+        // cheaper than making the trait return owned ones
+        let leak = |s: &str| -> &'static str { Box::leak(s.to_string().into_boxed_str()) };
+        CustomRestFeed {
+            id: leak(&config.name),
+            symbols: config
+                .symbols
+                .iter()
+                .map(|(asset_pair, symbol)| (*asset_pair, leak(symbol)))
+                .collect(),
+            config,
+            client,
+        }
+    }
+
+    fn url(&self, symbol: &str, instant: OffsetDateTime) -> Result<String> {
+        fill_template(&self.config.url, symbol, instant)
+    }
+}
+
+/// Replaces the `{symbol}`, `{timestamp}`, `{timestamp_ms}` and `{rfc3339}` placeholders of
+/// `template`
+pub(crate) fn fill_template(
+    template: &str,
+    symbol: &str,
+    instant: OffsetDateTime,
+) -> Result<String> {
+    let rfc3339 = instant
+        .format(&Rfc3339)
+        .map_err(|err| PriceFeedError::InternalError(err.to_string()))?;
+    Ok(template
+        .replace("{symbol}", symbol)
+        .replace(
+            "{timestamp_ms}",
+            &(instant.unix_timestamp() * 1000).to_string(),
+        )
+        .replace("{timestamp}", &instant.unix_timestamp().to_string())
+        .replace("{rfc3339}", &rfc3339))
+}
+
+#[async_trait]
+impl PriceFeed for CustomRestFeed {
+    fn id(&self) -> &'static str {
+        self.id
+    }
+
+    fn translate_asset_pair(&self, asset_pair: AssetPair) -> Result<&'static str> {
+        self.symbols.get(&asset_pair).copied().ok_or_else(|| {
+            PriceFeedError::InternalError(format!("{} does not support {}", self.id, asset_pair))
+        })
+    }
+
+    async fn retrieve_price(&self, asset_pair: AssetPair, instant: OffsetDateTime) -> Result<f64> {
+        let client = &self.client;
+        let url = self.url(self.translate_asset_pair(asset_pair)?, instant)?;
+        info!("sending {} http request {asset_pair} {instant}", self.id);
+        let mut request = client.get(url);
+        for (name, value) in &self.config.headers {
+            request = request.header(name, &value.0);
+        }
+        let res: Value = request.send().await?.error_for_status()?.json().await?;
+        debug!("received {} response: {:#?}", self.id, res);
+
+        // prices are commonly served as strings to preserve precision
+        let price = match res.pointer(&self.config.price_pointer) {
+            Some(Value::Number(price)) => price.as_f64(),
+            Some(Value::String(price)) => price.parse().ok(),
+            _ => None,
+        }
+        .ok_or(PriceFeedError::PriceNotAvailableError(asset_pair, instant))?;
+        info!("{} price {price}", self.id);
+        Ok(price)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn feed(url: &str) -> CustomRestFeed {
+        CustomRestFeed::new(
+            serde_json::from_str(&format!(
+                r#"{{
+                    "name": "internal",
+                    "url": "{url}",
+                    "price_pointer": "/data/0/price",
+                    "symbols": {{"BTCUSD": "BTC-USD"}}
+                }}"#
+            ))
+            .unwrap(),
+            Client::new(),
+        )
+    }
+
+    #[test]
+    fn fill_url_template() {
+        let feed = feed(
+            "https://prices.example.com/{symbol}?at={timestamp}&ms={timestamp_ms}&t={rfc3339}",
+        );
+        let instant = OffsetDateTime::from_unix_timestamp(1653984000).unwrap();
+        assert_eq!(
+            "https://prices.example.com/BTC-USD?at=1653984000&ms=1653984000000&t=2022-05-31T08:00:00Z",
+            feed.url(feed.translate_asset_pair(AssetPair::BTCUSD).unwrap(), instant)
+                .unwrap()
+        );
+        assert_eq!("internal", feed.id());
+        assert!(feed.translate_asset_pair(AssetPair::BTCUSDT).is_err());
+    }
+}
@@ -0,0 +1,155 @@
+use super::{PriceFeed, PriceFeedError, Quote, Result, WebSocketSubscription};
+use crate::AssetPair;
+use async_trait::async_trait;
+use futures::future::{BoxFuture, FutureExt, Shared};
+use log::debug;
+use parking_lot::Mutex;
+use std::{
+    collections::HashMap,
+    sync::{Arc, LazyLock},
+    time::{Duration, Instant},
+};
+use time::OffsetDateTime;
+
+/// Time a retrieved quote is handed to further requests for the same price
+const SHARED_QUOTE_TTL: Duration = Duration::from_secs(5);
+
+type SharedQuote = Shared<BoxFuture<'static, std::result::Result<Quote, Arc<PriceFeedError>>>>;
+
+/// Pending or recently answered request of a pricefeed
+struct Request {
+    started: Instant,
+    quote: SharedQuote,
+}
+
+/// Requests of all pricefeeds by pricefeed id, asset pair and time of the price, shared by the
+/// pricefeeds of all asset pairs
+static REQUESTS: LazyLock<Mutex<HashMap<(&'static str, AssetPair, OffsetDateTime), Request>>> =
+    LazyLock::new(Default::default);
+
+/// Pricefeed joining requests for a price that is already being retrieved, e.g. the BTCUSD price
+/// at maturation needed both for BTCUSD and for its BTCEUR cross rate, instead of sending them
+/// to the exchange again
+pub(super) struct Deduplicated {
+    pricefeed: Arc<dyn PriceFeed + Send + Sync>,
+}
+
+impl Deduplicated {
+    pub(super) fn new(pricefeed: Box<dyn PriceFeed + Send + Sync>) -> Self {
+        Deduplicated {
+            pricefeed: pricefeed.into(),
+        }
+    }
+}
+
+#[async_trait]
+impl PriceFeed for Deduplicated {
+    fn id(&self) -> &'static str {
+        self.pricefeed.id()
+    }
+
+    fn translate_asset_pair(&self, asset_pair: AssetPair) -> Result<&'static str> {
+        self.pricefeed.translate_asset_pair(asset_pair)
+    }
+
+    async fn retrieve_price(&self, asset_pair: AssetPair, datetime: OffsetDateTime) -> Result<f64> {
+        Ok(self.retrieve_quote(asset_pair, datetime).await?.price)
+    }
+
+    async fn retrieve_quote(
+        &self,
+        asset_pair: AssetPair,
+        datetime: OffsetDateTime,
+    ) -> Result<Quote> {
+        let key = (self.id(), asset_pair, datetime);
+        let quote = {
+            let mut requests = REQUESTS.lock();
+            requests.retain(|_, request| request.started.elapsed() < SHARED_QUOTE_TTL);
+            requests
+                .entry(key)
+                .and_modify(|_| debug!("joining {} request {asset_pair} {datetime}", key.0))
+                .or_insert_with(|| {
+                    let pricefeed = self.pricefeed.clone();
+                    Request {
+                        started: Instant::now(),
+                        quote: async move {
+                            pricefeed
+                                .retrieve_quote(asset_pair, datetime)
+                                .await
+                                .map_err(Arc::new)
+                        }
+                        .boxed()
+                        .shared(),
+                    }
+                })
+                .quote
+                .clone()
+        };
+        quote.await.map_err(|err| {
+            // failures are not shared, so retries reach the exchange again
+            REQUESTS.lock().remove(&key);
+            Arc::try_unwrap(err).unwrap_or_else(PriceFeedError::SharedError)
+        })
+    }
+
+    fn weight(&self) -> f64 {
+        self.pricefeed.weight()
+    }
+
+    fn websocket(&self, asset_pair: AssetPair) -> Option<WebSocketSubscription> {
+        self.pricefeed.websocket(asset_pair)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    struct CountingFeed {
+        requests: Arc<AtomicUsize>,
+    }
+
+    #[async_trait]
+    impl PriceFeed for CountingFeed {
+        fn id(&self) -> &'static str {
+            "counting"
+        }
+
+        fn translate_asset_pair(&self, _asset_pair: AssetPair) -> Result<&'static str> {
+            Ok("counting")
+        }
+
+        async fn retrieve_price(
+            &self,
+            _asset_pair: AssetPair,
+            _datetime: OffsetDateTime,
+        ) -> Result<f64> {
+            self.requests.fetch_add(1, Ordering::SeqCst);
+            tokio::time::sleep(Duration::from_millis(50)).await;
+            Ok(30000.0)
+        }
+    }
+
+    #[tokio::test]
+    async fn concurrent_requests_are_joined() {
+        let requests = Arc::new(AtomicUsize::new(0));
+        let feeds = [(); 2].map(|_| {
+            Deduplicated::new(Box::new(CountingFeed {
+                requests: requests.clone(),
+            }))
+        });
+        let datetime = OffsetDateTime::now_utc();
+        let (first, second) = futures::join!(
+            feeds[0].retrieve_quote(AssetPair::BTCUSD, datetime),
+            feeds[1].retrieve_quote(AssetPair::BTCUSD, datetime)
+        );
+        assert_eq!(30000.0, first.unwrap().price);
+        assert_eq!(30000.0, second.unwrap().price);
+        assert_eq!(1, requests.load(Ordering::SeqCst));
+
+        let other = feeds[0].retrieve_quote(AssetPair::BTCUSDT, datetime).await;
+        assert!(other.is_ok());
+        assert_eq!(2, requests.load(Ordering::SeqCst));
+    }
+}
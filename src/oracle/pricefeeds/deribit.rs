@@ -1,30 +1,73 @@
-use super::{PriceFeed, PriceFeedError, Result};
+use super::{FeedId, PriceFeed, PriceFeedError, Result};
 use crate::AssetPair;
 use log::{debug, info};
 use reqwest::Client;
-use serde::Deserialize;
+use serde::{de::DeserializeOwned, Deserialize};
 use serde_json::Value;
-use time::OffsetDateTime;
+use time::{Duration, OffsetDateTime};
+
+/// How far from now a requested instant may be to be answered with the live index price
+const LIVE_INDEX_TOLERANCE: Duration = Duration::minutes(1);
 
 #[derive(Deserialize, Debug)]
-struct Response {
-    result: Option<DeribitResult>,
+struct Response<T> {
+    result: Option<T>,
     error: Option<Value>,
 }
 
 #[derive(Deserialize, Debug)]
-struct DeribitResult {
+struct SettlementsResult {
     settlements: Vec<DeribitSettlement>,
 }
 
 #[derive(Deserialize, Debug)]
 struct DeribitSettlement {
     index_price: f64,
+    /// time of the delivery in ms
+    timestamp: i64,
+}
+
+#[derive(Deserialize, Debug)]
+struct IndexPriceResult {
+    index_price: f64,
 }
 
 use async_trait::async_trait;
 
-pub struct Deribit {}
+/// Deribit BTC index, a composite of the major spot exchanges used to settle Deribit derivatives
+#[derive(Default)]
+pub struct Deribit {
+    pub client: Client,
+}
+
+impl Deribit {
+    async fn request<T: DeserializeOwned>(
+        &self,
+        asset_pair: AssetPair,
+        instant: OffsetDateTime,
+        method: &str,
+        query: &[(&str, &str)],
+    ) -> Result<T> {
+        let res: Response<T> = self
+            .client
+            .get(format!("https://www.deribit.com/api/v2/public/{method}"))
+            .query(query)
+            .send()
+            .await?
+            .json()
+            .await?;
+
+        if let Some(error) = res.error {
+            return Err(PriceFeedError::InternalError(format!(
+                "deribit error: {:#?}",
+                error
+            )));
+        }
+
+        res.result
+            .ok_or(PriceFeedError::PriceNotAvailableError(asset_pair, instant))
+    }
+}
 
 #[async_trait]
 impl PriceFeed for Deribit {
@@ -38,43 +81,52 @@ impl PriceFeed for Deribit {
             AssetPair::BTCUSDT => Err(PriceFeedError::InternalError(
                 "deribit does not support USDT".to_string(),
             )),
+            _ => asset_pair
+                .symbol(&FeedId::Deribit)
+                .ok_or(PriceFeedError::UnsupportedAssetPairError(asset_pair)),
         }
     }
 
     async fn retrieve_price(&self, asset_pair: AssetPair, instant: OffsetDateTime) -> Result<f64> {
-        let client = Client::new();
-        let asset_pair_translation = self.translate_asset_pair(asset_pair).unwrap();
-        let start_time = instant.unix_timestamp() * 1000;
+        let asset_pair_translation = self.translate_asset_pair(asset_pair)?;
         info!("sending deribit http request {asset_pair} {instant}");
-        let res: Response = client
-            .get("https://www.deribit.com/api/v2/public/get_last_settlements_by_currency")
-            .query(&[
-                ("currency", asset_pair_translation),
-                ("type", "delivery"),
-                ("count", "1"),
-                ("search_start_timestamp", &start_time.to_string()),
-            ])
-            .send()
-            .await?
-            .json()
-            .await?;
-
-        debug!("received deribit response: {:#?}", res);
 
-        if let Some(error) = res.error {
-            return Err(PriceFeedError::InternalError(format!(
-                "deribit error: {:#?}",
-                error
-            )));
+        // the index itself has no history, so past instants are only available at deliveries
+        if (OffsetDateTime::now_utc() - instant).abs() <= LIVE_INDEX_TOLERANCE {
+            let index_name = format!("{}_usd", asset_pair_translation.to_lowercase());
+            let res: IndexPriceResult = self
+                .request(
+                    asset_pair,
+                    instant,
+                    "get_index_price",
+                    &[("index_name", index_name.as_str())],
+                )
+                .await?;
+            debug!("received deribit response: {:#?}", res);
+            info!("deribit price {}", res.index_price);
+            return Ok(res.index_price);
         }
 
-        let res = res
-            .result
-            .ok_or(PriceFeedError::PriceNotAvailableError(asset_pair, instant))?;
+        let start_time = (instant.unix_timestamp() * 1000).to_string();
+        let res: SettlementsResult = self
+            .request(
+                asset_pair,
+                instant,
+                "get_last_settlements_by_currency",
+                &[
+                    ("currency", asset_pair_translation),
+                    ("type", "delivery"),
+                    ("count", "1"),
+                    ("search_start_timestamp", start_time.as_str()),
+                ],
+            )
+            .await?;
+        debug!("received deribit response: {:#?}", res);
 
         let index_price = res
             .settlements
             .get(0)
+            .filter(|settlement| settlement.timestamp / 1000 == instant.unix_timestamp())
             .ok_or(PriceFeedError::PriceNotAvailableError(asset_pair, instant))?
             .index_price;
         info!("deribit price {index_price}");
@@ -89,7 +141,7 @@ mod tests {
 
     #[tokio::test]
     async fn retrieve() {
-        let deribit = Deribit {};
+        let deribit = Deribit::default();
         let now = OffsetDateTime::now_utc();
         let result = deribit.retrieve_price(AssetPair::BTCUSD, now).await;
 
@@ -101,4 +153,16 @@ mod tests {
             Err(e) => panic!("API call failed with error: {:?}", e),
         }
     }
+
+    // queries the settlements of the live API, run with `cargo test -- --ignored`
+    #[ignore]
+    #[tokio::test]
+    async fn retrieve_delivery() {
+        let deribit = Deribit::default();
+        // deliveries happen daily at 08:00 UTC
+        let yesterday = OffsetDateTime::now_utc().date().previous_day().unwrap();
+        let delivery = yesterday.with_hms(8, 0, 0).unwrap().assume_utc();
+        let price = deribit.retrieve_price(AssetPair::BTCUSD, delivery).await;
+        assert!(price.is_ok(), "{:#?}", &price);
+    }
 }
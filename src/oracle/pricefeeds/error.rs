@@ -1,5 +1,6 @@
 use crate::AssetPair;
 use displaydoc::Display;
+use std::{sync::Arc, time::Duration};
 use thiserror::Error;
 use time::OffsetDateTime;
 
@@ -15,4 +16,31 @@ pub enum PriceFeedError {
 
     /// http error: {0}
     HttpError(#[from] reqwest::Error),
+
+    /// request timed out after {0:?}
+    TimeoutError(Duration),
+
+    /// asset pair {0} not supported
+    UnsupportedAssetPairError(AssetPair),
+
+    /// {0}
+    SharedError(Arc<PriceFeedError>),
+}
+
+impl PriceFeedError {
+    /// Whether retrying the request may succeed, e.g. after a timeout or a server error
+    pub fn is_transient(&self) -> bool {
+        match self {
+            PriceFeedError::HttpError(err) => {
+                err.is_timeout()
+                    || err.is_connect()
+                    || err.status().is_some_and(|status| {
+                        status.is_server_error() || status == reqwest::StatusCode::TOO_MANY_REQUESTS
+                    })
+            }
+            PriceFeedError::TimeoutError(_) => true,
+            PriceFeedError::SharedError(err) => err.is_transient(),
+            _ => false,
+        }
+    }
 }
@@ -0,0 +1,121 @@
+use super::{custom_rest::fill_template, PriceFeed, PriceFeedError, Result};
+use crate::{AssetPair, ExecFeedConfig};
+use async_trait::async_trait;
+use log::{debug, info};
+use std::collections::HashMap;
+use time::OffsetDateTime;
+use tokio::process::Command;
+
+/// Pricefeed running an external command that prints the price, for proprietary or exotic data
+/// sources without a dedicated implementation
+pub struct ExecFeed {
+    id: &'static str,
+    symbols: HashMap<AssetPair, &'static str>,
+    config: ExecFeedConfig,
+}
+
+impl ExecFeed {
+    pub fn new(config: ExecFeedConfig) -> Self {
+        // pricefeeds are created once at startup, see `CustomRestFeed::new`
+        let leak = |s: &str| -> &'static str { Box::leak(s.to_string().into_boxed_str()) };
+        ExecFeed {
+            id: leak(&config.name),
+            symbols: config
+                .symbols
+                .iter()
+                .map(|(asset_pair, symbol)| (*asset_pair, leak(symbol)))
+                .collect(),
+            config,
+        }
+    }
+}
+
+#[async_trait]
+impl PriceFeed for ExecFeed {
+    fn id(&self) -> &'static str {
+        self.id
+    }
+
+    fn translate_asset_pair(&self, asset_pair: AssetPair) -> Result<&'static str> {
+        self.symbols.get(&asset_pair).copied().ok_or_else(|| {
+            PriceFeedError::InternalError(format!("{} does not support {}", self.id, asset_pair))
+        })
+    }
+
+    async fn retrieve_price(&self, asset_pair: AssetPair, instant: OffsetDateTime) -> Result<f64> {
+        let symbol = self.translate_asset_pair(asset_pair)?;
+        let args = self
+            .config
+            .args
+            .iter()
+            .map(|arg| fill_template(arg, symbol, instant))
+            .collect::<Result<Vec<_>>>()?;
+        info!("running {} command {asset_pair} {instant}", self.id);
+        // the command is killed when the request times out and this future is dropped
+        let output = Command::new(&self.config.command)
+            .args(&args)
+            .kill_on_drop(true)
+            .output()
+            .await
+            .map_err(|err| {
+                PriceFeedError::InternalError(format!("cannot run {} command: {}", self.id, err))
+            })?;
+        if !output.status.success() {
+            return Err(PriceFeedError::InternalError(format!(
+                "{} command failed with {}: {}",
+                self.id,
+                output.status,
+                String::from_utf8_lossy(&output.stderr).trim()
+            )));
+        }
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        debug!("received {} output: {}", self.id, stdout.trim());
+
+        let price = stdout
+            .trim()
+            .parse()
+            .map_err(|_| PriceFeedError::PriceNotAvailableError(asset_pair, instant))?;
+        info!("{} price {price}", self.id);
+        Ok(price)
+    }
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+
+    fn feed(command: &str, args: &[&str]) -> ExecFeed {
+        ExecFeed::new(ExecFeedConfig {
+            name: "script".to_string(),
+            command: command.to_string(),
+            args: args.iter().map(|arg| arg.to_string()).collect(),
+            symbols: HashMap::from([(AssetPair::BTCUSD, "BTC-USD".to_string())]),
+        })
+    }
+
+    #[tokio::test]
+    async fn retrieve() {
+        let instant = OffsetDateTime::from_unix_timestamp(1653984000).unwrap();
+        let price = feed("sh", &["-c", "echo 30236.5 # {symbol} {timestamp}"])
+            .retrieve_price(AssetPair::BTCUSD, instant)
+            .await;
+        assert_eq!(30236.5, price.unwrap());
+
+        let price = feed("sh", &["-c", "echo {symbol}"])
+            .retrieve_price(AssetPair::BTCUSD, instant)
+            .await;
+        assert!(matches!(
+            price,
+            Err(PriceFeedError::PriceNotAvailableError(..))
+        ));
+
+        let price = feed("false", &[])
+            .retrieve_price(AssetPair::BTCUSD, instant)
+            .await;
+        assert!(matches!(price, Err(PriceFeedError::InternalError(_))));
+        assert!(feed("false", &[])
+            .retrieve_price(AssetPair::BTCUSDT, instant)
+            .await
+            .is_err());
+    }
+}
@@ -1,4 +1,4 @@
-use super::{PriceFeed, PriceFeedError, Result};
+use super::{FeedId, PriceFeed, PriceFeedError, Result};
 use crate::AssetPair;
 use async_trait::async_trait;
 use log::{debug, info};
@@ -6,7 +6,10 @@ use reqwest::Client;
 use serde_json::Value;
 use time::OffsetDateTime;
 
-pub struct GateIo {}
+#[derive(Default)]
+pub struct GateIo {
+    pub client: Client,
+}
 
 #[async_trait]
 impl PriceFeed for GateIo {
@@ -18,11 +21,14 @@ impl PriceFeed for GateIo {
         match asset_pair {
             AssetPair::BTCUSD => Ok("BTC_USD"),
             AssetPair::BTCUSDT => Ok("BTC_USDT"),
+            _ => asset_pair
+                .symbol(&FeedId::GateIO)
+                .ok_or(PriceFeedError::UnsupportedAssetPairError(asset_pair)),
         }
     }
 
     async fn retrieve_price(&self, asset_pair: AssetPair, instant: OffsetDateTime) -> Result<f64> {
-        let client = Client::new();
+        let client = &self.client;
         let start_time = instant.unix_timestamp();
         info!("sending gateio http request {asset_pair} {instant}");
         let res: Vec<Vec<Value>> = client
@@ -61,15 +67,15 @@ impl PriceFeed for GateIo {
 
 #[cfg(test)]
 mod tests {
-    use crate::AssetPair::*;
+    use crate::AssetPair;
 
     use super::*;
 
     #[tokio::test]
     async fn retrieve() {
-        let feed = GateIo {};
+        let feed = GateIo::default();
         let price = feed
-            .retrieve_price(BTCUSDT, OffsetDateTime::now_utc())
+            .retrieve_price(AssetPair::BTCUSDT, OffsetDateTime::now_utc())
             .await;
         match price {
             Ok(_) => assert!(true),
@@ -0,0 +1,93 @@
+use super::{FeedId, PriceFeed, PriceFeedError, Result};
+use crate::AssetPair;
+use async_trait::async_trait;
+use log::{debug, info};
+use reqwest::Client;
+use serde::Deserialize;
+use time::OffsetDateTime;
+
+#[derive(Default)]
+pub struct Gemini {
+    pub client: Client,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+enum Response {
+    /// candles are [open time in ms, open, high, low, close, volume], most recent first
+    Candles(Vec<(i64, f64, f64, f64, f64, f64)>),
+    Error {
+        reason: String,
+        message: String,
+    },
+}
+
+#[async_trait]
+impl PriceFeed for Gemini {
+    fn id(&self) -> &'static str {
+        "gemini"
+    }
+
+    fn translate_asset_pair(&self, asset_pair: AssetPair) -> Result<&'static str> {
+        match asset_pair {
+            AssetPair::BTCUSD => Ok("btcusd"),
+            AssetPair::BTCUSDT => Ok("btcusdt"),
+            _ => asset_pair
+                .symbol(&FeedId::Gemini)
+                .ok_or(PriceFeedError::UnsupportedAssetPairError(asset_pair)),
+        }
+    }
+
+    async fn retrieve_price(&self, asset_pair: AssetPair, instant: OffsetDateTime) -> Result<f64> {
+        let client = &self.client;
+        let asset_pair_translation = self.translate_asset_pair(asset_pair)?;
+        info!("sending gemini http request {asset_pair} {instant}");
+        // the candles endpoint takes no time range and only covers the last day or so
+        let res: Response = client
+            .get(format!(
+                "https://api.gemini.com/v2/candles/{}/1m",
+                asset_pair_translation
+            ))
+            .send()
+            .await?
+            .json()
+            .await?;
+        debug!("received gemini response: {:#?}", res);
+
+        let candles = match res {
+            Response::Candles(candles) => candles,
+            Response::Error { reason, message } => {
+                return Err(PriceFeedError::InternalError(format!(
+                    "gemini error {reason}: {message}"
+                )))
+            }
+        };
+        let start_time = instant.unix_timestamp() * 1000;
+        let (_, price, ..) = candles
+            .into_iter()
+            .find(|(open_time, ..)| *open_time <= start_time)
+            .filter(|(open_time, ..)| start_time - open_time < 60 * 1000)
+            .ok_or(PriceFeedError::PriceNotAvailableError(asset_pair, instant))?;
+        info!("gemini price {price}");
+        Ok(price)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::AssetPair;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn retrieve() {
+        let feed = Gemini::default();
+        let price = feed
+            .retrieve_price(
+                AssetPair::BTCUSD,
+                OffsetDateTime::now_utc() - time::Duration::minutes(2),
+            )
+            .await;
+        assert!(price.is_ok(), "{:#?}", &price);
+    }
+}
@@ -0,0 +1,140 @@
+use log::{info, warn};
+use parking_lot::Mutex;
+use serde::Serialize;
+use std::{collections::HashMap, time::Duration};
+use time::{format_description::well_known::Rfc3339, OffsetDateTime};
+use utoipa::ToSchema;
+
+#[derive(Debug, Default)]
+struct FeedStats {
+    successes: u64,
+    failures: u64,
+    consecutive_failures: u32,
+    total_latency: Duration,
+    last_success: Option<OffsetDateTime>,
+    last_error: Option<String>,
+    disabled: bool,
+}
+
+/// Health of a single pricefeed
+#[derive(Clone, Debug, Serialize, ToSchema)]
+pub struct PriceFeedStatus {
+    pub pricefeed: &'static str,
+    /// whether the pricefeed is left out of attestations until a probe succeeds
+    pub disabled: bool,
+    pub successes: u64,
+    pub failures: u64,
+    pub consecutive_failures: u32,
+    /// share of successful requests, if any were made
+    pub success_rate: Option<f64>,
+    /// average duration of successful requests in milliseconds
+    pub average_latency_ms: Option<f64>,
+    /// RFC3339 time of the last successful request
+    #[schema(example = "2022-06-07T08:00:00Z")]
+    pub last_success: Option<String>,
+    pub last_error: Option<String>,
+}
+
+/// Success rate and latency of the pricefeeds of an oracle, disabling pricefeeds failing
+/// `max_consecutive_failures` times in a row until they answer a probe again
+#[derive(Debug, Default)]
+pub struct PriceFeedHealth {
+    feeds: Mutex<HashMap<&'static str, FeedStats>>,
+}
+
+impl PriceFeedHealth {
+    pub fn record_success(&self, pricefeed: &'static str, latency: Duration) {
+        let mut feeds = self.feeds.lock();
+        let stats = feeds.entry(pricefeed).or_default();
+        stats.successes += 1;
+        stats.consecutive_failures = 0;
+        stats.total_latency += latency;
+        stats.last_success = Some(OffsetDateTime::now_utc());
+        if stats.disabled {
+            info!("re-enabling pricefeed {pricefeed}");
+            stats.disabled = false;
+        }
+    }
+
+    pub fn record_failure(
+        &self,
+        pricefeed: &'static str,
+        error: String,
+        max_consecutive_failures: u32,
+    ) {
+        let mut feeds = self.feeds.lock();
+        let stats = feeds.entry(pricefeed).or_default();
+        stats.failures += 1;
+        stats.consecutive_failures += 1;
+        stats.last_error = Some(error);
+        if !stats.disabled && stats.consecutive_failures >= max_consecutive_failures {
+            warn!(
+                "disabling pricefeed {pricefeed} after {} consecutive failures",
+                stats.consecutive_failures
+            );
+            stats.disabled = true;
+        }
+    }
+
+    pub fn is_disabled(&self, pricefeed: &str) -> bool {
+        self.feeds
+            .lock()
+            .get(pricefeed)
+            .is_some_and(|stats| stats.disabled)
+    }
+
+    /// Health of every pricefeed queried so far, sorted by id
+    pub fn statuses(&self) -> Vec<PriceFeedStatus> {
+        let mut statuses = self
+            .feeds
+            .lock()
+            .iter()
+            .map(|(pricefeed, stats)| {
+                let requests = stats.successes + stats.failures;
+                PriceFeedStatus {
+                    pricefeed: *pricefeed,
+                    disabled: stats.disabled,
+                    successes: stats.successes,
+                    failures: stats.failures,
+                    consecutive_failures: stats.consecutive_failures,
+                    success_rate: (requests > 0).then(|| stats.successes as f64 / requests as f64),
+                    average_latency_ms: (stats.successes > 0).then(|| {
+                        stats.total_latency.as_secs_f64() * 1000.0 / stats.successes as f64
+                    }),
+                    last_success: stats
+                        .last_success
+                        .map(|time| time.format(&Rfc3339).unwrap()),
+                    last_error: stats.last_error.clone(),
+                }
+            })
+            .collect::<Vec<_>>();
+        statuses.sort_by_key(|status| status.pricefeed);
+        statuses
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn disable_and_reenable() {
+        let health = PriceFeedHealth::default();
+        health.record_success("kraken", Duration::from_millis(200));
+        health.record_failure("kraken", "timeout".to_string(), 2);
+        assert!(!health.is_disabled("kraken"));
+        health.record_failure("kraken", "timeout".to_string(), 2);
+        assert!(health.is_disabled("kraken"));
+        assert!(!health.is_disabled("bitstamp"));
+
+        let status = &health.statuses()[0];
+        assert_eq!(status.consecutive_failures, 2);
+        assert_eq!(status.success_rate, Some(1.0 / 3.0));
+        assert_eq!(status.average_latency_ms, Some(200.0));
+        assert_eq!(status.last_error.as_deref(), Some("timeout"));
+
+        health.record_success("kraken", Duration::from_millis(400));
+        assert!(!health.is_disabled("kraken"));
+        assert_eq!(health.statuses()[0].average_latency_ms, Some(300.0));
+    }
+}
@@ -9,9 +9,12 @@ use time::OffsetDateTime;
 
 use crate::AssetPair;
 
-use super::{PriceFeed, PriceFeedError, Result};
+use super::{FeedId, PriceFeed, PriceFeedError, Quote, Result, WebSocketSubscription};
 
-pub struct Kraken {}
+#[derive(Default)]
+pub struct Kraken {
+    pub client: Client,
+}
 
 #[derive(Debug, Deserialize)]
 struct Response {
@@ -29,11 +32,22 @@ impl PriceFeed for Kraken {
         match asset_pair {
             AssetPair::BTCUSD => Ok("XXBTZUSD"),
             AssetPair::BTCUSDT => Ok("XXBTZUSDT"),
+            _ => asset_pair
+                .symbol(&FeedId::Kraken)
+                .ok_or(PriceFeedError::UnsupportedAssetPairError(asset_pair)),
         }
     }
 
     async fn retrieve_price(&self, asset_pair: AssetPair, instant: OffsetDateTime) -> Result<f64> {
-        let client = Client::new();
+        Ok(self.retrieve_quote(asset_pair, instant).await?.price)
+    }
+
+    async fn retrieve_quote(
+        &self,
+        asset_pair: AssetPair,
+        instant: OffsetDateTime,
+    ) -> Result<Quote> {
+        let client = &self.client;
         let asset_pair_translation = self.translate_asset_pair(asset_pair).unwrap();
         let start_time = instant.unix_timestamp();
         info!("sending kraken http request {asset_pair} {instant}");
@@ -61,9 +75,11 @@ impl PriceFeed for Kraken {
             .get(asset_pair_translation)
             .ok_or(PriceFeedError::PriceNotAvailableError(asset_pair, instant))?;
 
-        let price = res
+        // candles are [time, open, high, low, close, vwap, volume, count]
+        let candle = res
             .get(0)
-            .ok_or(PriceFeedError::PriceNotAvailableError(asset_pair, instant))?
+            .ok_or(PriceFeedError::PriceNotAvailableError(asset_pair, instant))?;
+        let price = candle
             .get(1)
             .ok_or(PriceFeedError::PriceNotAvailableError(asset_pair, instant))?
             .as_str()
@@ -71,20 +87,47 @@ impl PriceFeed for Kraken {
             .parse()
             .unwrap();
         info!("kraken price {price}");
-        Ok(price)
+        Ok(Quote {
+            price,
+            volume: candle
+                .get(6)
+                .and_then(Value::as_str)
+                .and_then(|volume| volume.parse().ok()),
+        })
+    }
+
+    fn websocket(&self, asset_pair: AssetPair) -> Option<WebSocketSubscription> {
+        let symbol = match asset_pair {
+            AssetPair::BTCUSD => "BTC/USD",
+            AssetPair::BTCUSDT => "BTC/USDT",
+            _ => return None,
+        };
+        Some(WebSocketSubscription {
+            url: "wss://ws.kraken.com/v2",
+            subscribe: serde_json::json!({
+                "method": "subscribe",
+                "params": {"channel": "ticker", "symbol": [symbol]},
+            }),
+            parse: |message| match message.get("channel")?.as_str()? {
+                "ticker" => message.pointer("/data/0/last")?.as_f64(),
+                _ => None,
+            },
+        })
     }
 }
 
 #[cfg(test)]
 mod tests {
-    use crate::AssetPair::*;
+    use crate::AssetPair;
 
     use super::*;
 
     #[tokio::test]
     async fn retrieve() {
-        let feed = Kraken {};
-        let price = feed.retrieve_price(BTCUSD, OffsetDateTime::now_utc()).await;
+        let feed = Kraken::default();
+        let price = feed
+            .retrieve_price(AssetPair::BTCUSD, OffsetDateTime::now_utc())
+            .await;
         match price {
             Ok(_) => assert!(true),
             Err(_) => assert!(false, "{:#?}", &price),
@@ -0,0 +1,98 @@
+use super::{FeedId, PriceFeed, PriceFeedError, Result};
+use crate::AssetPair;
+use async_trait::async_trait;
+use log::{debug, info};
+use reqwest::Client;
+use serde::Deserialize;
+use time::OffsetDateTime;
+
+/// Mark price of the Kraken Futures perpetual contract
+#[derive(Default)]
+pub struct KrakenFutures {
+    pub client: Client,
+}
+
+#[derive(Debug, Deserialize)]
+struct Response {
+    candles: Vec<Candle>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Candle {
+    /// open time in ms
+    time: i64,
+    open: String,
+}
+
+#[async_trait]
+impl PriceFeed for KrakenFutures {
+    fn id(&self) -> &'static str {
+        "kraken_futures"
+    }
+
+    fn translate_asset_pair(&self, asset_pair: AssetPair) -> Result<&'static str> {
+        match asset_pair {
+            AssetPair::BTCUSD => Ok("PF_XBTUSD"),
+            AssetPair::BTCUSDT => Err(PriceFeedError::InternalError(
+                "kraken futures does not support USDT".to_string(),
+            )),
+            _ => asset_pair
+                .symbol(&FeedId::KrakenFutures)
+                .ok_or(PriceFeedError::UnsupportedAssetPairError(asset_pair)),
+        }
+    }
+
+    async fn retrieve_price(&self, asset_pair: AssetPair, instant: OffsetDateTime) -> Result<f64> {
+        let client = &self.client;
+        let asset_pair_translation = self.translate_asset_pair(asset_pair)?;
+        let start_time = instant.unix_timestamp();
+        info!("sending kraken futures http request {asset_pair} {instant}");
+        let res: Response = client
+            .get(format!(
+                "https://futures.kraken.com/api/charts/v1/mark/{}/1m",
+                asset_pair_translation
+            ))
+            .query(&[
+                ("from", start_time.to_string()),
+                ("to", (start_time + 60).to_string()),
+            ])
+            .send()
+            .await?
+            .error_for_status()?
+            .json()
+            .await?;
+        debug!("received kraken futures response: {:#?}", res);
+
+        let price = res
+            .candles
+            .iter()
+            .find(|candle| candle.time / 1000 == start_time)
+            .ok_or(PriceFeedError::PriceNotAvailableError(asset_pair, instant))?
+            .open
+            .parse()
+            .map_err(|_| PriceFeedError::PriceNotAvailableError(asset_pair, instant))?;
+        info!("kraken futures price {price}");
+        Ok(price)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::AssetPair;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn retrieve() {
+        let feed = KrakenFutures::default();
+        // candles start on the minute
+        let instant = OffsetDateTime::now_utc() - time::Duration::minutes(2);
+        let instant = instant
+            .replace_second(0)
+            .unwrap()
+            .replace_nanosecond(0)
+            .unwrap();
+        let price = feed.retrieve_price(AssetPair::BTCUSD, instant).await;
+        assert!(price.is_ok(), "{:#?}", &price);
+    }
+}
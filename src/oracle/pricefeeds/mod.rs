@@ -1,22 +1,44 @@
 use async_trait::async_trait;
-use futures::StreamExt;
-use log::{error, info};
+use log::{error, info, warn};
+use reqwest::{Client, Proxy};
 use serde::Deserialize;
 use serde::Serialize;
+use std::time::Duration;
 use time::OffsetDateTime;
+use tokio::time::{sleep, timeout, timeout_at, Instant};
 
+pub use alpha_vantage::AlphaVantage;
+pub use binance::Binance;
 pub use bitfinex::Bitfinex;
 pub use bitstamp::Bitstamp;
+pub use bybit::Bybit;
+pub use coinbase::Coinbase;
+pub use coingecko::CoinGecko;
+pub use cross_rate::cross_rate_feeds;
+pub(crate) use custom_rest::fill_template;
+pub use custom_rest::CustomRestFeed;
 pub use deribit::Deribit;
 pub use error::PriceFeedError;
 pub use error::Result;
+pub use exec::ExecFeed;
 pub use gateio::GateIo;
+pub use gemini::Gemini;
+pub use health::{PriceFeedHealth, PriceFeedStatus};
 pub use kraken::Kraken;
+pub use kraken_futures::KrakenFutures;
+pub use okx::Okx;
+pub use onchain::{Backend, OnChain};
+pub use polygon::Polygon;
+pub use streaming::{stream_prices, WebSocketSubscription};
 #[cfg(feature = "test-feed")]
 pub use test_feed::TestFeed;
 
+use crate::metrics::{PRICEFEED_REQUEST_DURATION, PRICEFEED_REQUEST_FAILURES};
 use crate::AggregationType;
+use crate::ApiKey;
 use crate::AssetPair;
+use crate::PriceFeedsConfig;
+use dedup::Deduplicated;
 
 mod error;
 
@@ -24,7 +46,70 @@ mod error;
 pub trait PriceFeed {
     fn id(&self) -> &'static str;
     fn translate_asset_pair(&self, asset_pair: AssetPair) -> Result<&'static str>;
+    /// Returns the price at `datetime` rather than the current one, e.g. the open of the
+    /// one-minute candle starting at `datetime`, so late attestations still attest the price at
+    /// maturation. Fails with `PriceNotAvailableError` if the history doesn't reach back that far.
     async fn retrieve_price(&self, asset_pair: AssetPair, datetime: OffsetDateTime) -> Result<f64>;
+
+    /// Returns the price at `datetime` like [`PriceFeed::retrieve_price`], along with the volume
+    /// traded in the candle it is taken from if the exchange reports it
+    async fn retrieve_quote(
+        &self,
+        asset_pair: AssetPair,
+        datetime: OffsetDateTime,
+    ) -> Result<Quote> {
+        Ok(Quote {
+            price: self.retrieve_price(asset_pair, datetime).await?,
+            volume: None,
+        })
+    }
+
+    /// Weight of the retrieved prices in the aggregation
+    fn weight(&self) -> f64 {
+        1.0
+    }
+
+    /// WebSocket API pushing live prices of the asset pair, if the exchange has one
+    fn websocket(&self, _asset_pair: AssetPair) -> Option<WebSocketSubscription> {
+        None
+    }
+}
+
+/// Pricefeed whose prices count `weight` times as much as those of unweighted pricefeeds
+struct Weighted {
+    pricefeed: Box<dyn PriceFeed + Send + Sync>,
+    weight: f64,
+}
+
+#[async_trait]
+impl PriceFeed for Weighted {
+    fn id(&self) -> &'static str {
+        self.pricefeed.id()
+    }
+
+    fn translate_asset_pair(&self, asset_pair: AssetPair) -> Result<&'static str> {
+        self.pricefeed.translate_asset_pair(asset_pair)
+    }
+
+    async fn retrieve_price(&self, asset_pair: AssetPair, datetime: OffsetDateTime) -> Result<f64> {
+        self.pricefeed.retrieve_price(asset_pair, datetime).await
+    }
+
+    async fn retrieve_quote(
+        &self,
+        asset_pair: AssetPair,
+        datetime: OffsetDateTime,
+    ) -> Result<Quote> {
+        self.pricefeed.retrieve_quote(asset_pair, datetime).await
+    }
+
+    fn weight(&self) -> f64 {
+        self.weight
+    }
+
+    fn websocket(&self, asset_pair: AssetPair) -> Option<WebSocketSubscription> {
+        self.pricefeed.websocket(asset_pair)
+    }
 }
 
 #[cfg(not(feature = "test-feed"))]
@@ -34,20 +119,46 @@ pub static ALL_PRICE_FEEDS: &[FeedId] = &[
     FeedId::Kraken,
     FeedId::Bitfinex,
     FeedId::Deribit,
+    FeedId::Binance,
+    FeedId::Coinbase,
+    FeedId::Okx,
+    FeedId::Bybit,
+    FeedId::Gemini,
+    FeedId::CoinGecko,
 ];
 
 #[cfg(not(feature = "test-feed"))]
-#[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq)]
+#[derive(Clone, Debug, Serialize, Deserialize, Eq, Hash, PartialEq)]
 pub enum FeedId {
     Bitstamp,
     GateIO,
     Kraken,
     Bitfinex,
     Deribit,
+    Binance,
+    Coinbase,
+    Okx,
+    Bybit,
+    Gemini,
+    CoinGecko,
+    /// not part of the default set, as the perpetual mark price can deviate from spot
+    KrakenFutures,
+    /// part of the default set only if configured, as it needs an API key
+    AlphaVantage,
+    /// part of the default set only if configured, as it needs an API key
+    Polygon,
+    /// part of the default set only if configured, as it needs a bitcoind instance
+    Bitcoind,
+    /// part of the default set only if configured, as it needs an esplora instance
+    Esplora,
+    /// pricefeed with the given name in the `custom` pricefeeds config
+    Custom(String),
+    /// pricefeed with the given name in the `exec` pricefeeds config
+    Exec(String),
 }
 
 #[cfg(feature = "test-feed")]
-#[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq)]
+#[derive(Clone, Debug, Serialize, Deserialize, Eq, Hash, PartialEq)]
 pub enum FeedId {
     Test,
 }
@@ -55,82 +166,413 @@ pub enum FeedId {
 pub struct ParseFeedIdError;
 
 #[cfg(not(feature = "test-feed"))]
-pub fn create_price_feed(feed_id: &FeedId) -> Box<dyn PriceFeed + Send + Sync> {
-    match feed_id {
-        FeedId::Bitstamp => Box::new(Bitstamp {}),
-        FeedId::GateIO => Box::new(GateIo {}),
-        FeedId::Kraken => Box::new(Kraken {}),
-        FeedId::Bitfinex => Box::new(Bitfinex {}),
-        FeedId::Deribit => Box::new(Deribit {}),
+pub fn create_price_feed(
+    feed_id: &FeedId,
+    config: &PriceFeedsConfig,
+) -> Result<Box<dyn PriceFeed + Send + Sync>> {
+    let client = http_client(feed_id, config)?;
+    Ok(match feed_id {
+        FeedId::Bitstamp => Box::new(Bitstamp { client }),
+        FeedId::GateIO => Box::new(GateIo { client }),
+        FeedId::Kraken => Box::new(Kraken { client }),
+        FeedId::Bitfinex => Box::new(Bitfinex { client }),
+        FeedId::Deribit => Box::new(Deribit { client }),
+        FeedId::Binance => Box::new(Binance { client }),
+        FeedId::Coinbase => Box::new(Coinbase { client }),
+        FeedId::Okx => Box::new(Okx { client }),
+        FeedId::Bybit => Box::new(Bybit { client }),
+        FeedId::Gemini => Box::new(Gemini { client }),
+        FeedId::CoinGecko => Box::new(CoinGecko {
+            config: config.coingecko.clone(),
+            client,
+        }),
+        FeedId::KrakenFutures => Box::new(KrakenFutures { client }),
+        FeedId::AlphaVantage => Box::new(AlphaVantage {
+            config: config.alpha_vantage.clone().ok_or_else(|| {
+                PriceFeedError::InternalError("alpha_vantage not configured".to_string())
+            })?,
+            client,
+        }),
+        FeedId::Polygon => Box::new(Polygon {
+            config: config.polygon.clone().ok_or_else(|| {
+                PriceFeedError::InternalError("polygon not configured".to_string())
+            })?,
+            client,
+        }),
+        FeedId::Bitcoind => Box::new(OnChain {
+            backend: Backend::Bitcoind(config.bitcoind.clone().ok_or_else(|| {
+                PriceFeedError::InternalError("bitcoind not configured".to_string())
+            })?),
+            client,
+        }),
+        FeedId::Esplora => Box::new(OnChain {
+            backend: Backend::Esplora(config.esplora.clone().ok_or_else(|| {
+                PriceFeedError::InternalError("esplora not configured".to_string())
+            })?),
+            client,
+        }),
+        FeedId::Custom(name) => Box::new(CustomRestFeed::new(
+            config
+                .custom
+                .iter()
+                .find(|custom| &custom.name == name)
+                .cloned()
+                .ok_or_else(|| {
+                    PriceFeedError::InternalError(format!("custom pricefeed {name} not configured"))
+                })?,
+            client,
+        )),
+        FeedId::Exec(name) => Box::new(ExecFeed::new(
+            config
+                .exec
+                .iter()
+                .find(|exec| &exec.name == name)
+                .cloned()
+                .ok_or_else(|| {
+                    PriceFeedError::InternalError(format!("exec pricefeed {name} not configured"))
+                })?,
+        )),
+    })
+}
+
+/// HTTP client of the given pricefeed, sending its requests through its own proxy if configured
+/// or else through the global one
+#[cfg(not(feature = "test-feed"))]
+fn http_client(feed_id: &FeedId, config: &PriceFeedsConfig) -> Result<Client> {
+    proxied_client(config.proxies.get(feed_id).or(config.proxy.as_ref()))
+}
+
+/// HTTP client sending its requests through `proxy`, if any
+fn proxied_client(proxy: Option<&ApiKey>) -> Result<Client> {
+    let mut builder = Client::builder();
+    if let Some(proxy) = proxy {
+        // the proxy URL may contain credentials, so it's kept out of the error
+        let proxy = Proxy::all(&proxy.0)
+            .map_err(|err| PriceFeedError::InternalError(format!("invalid proxy: {err}")))?;
+        builder = builder.proxy(proxy);
     }
+    Ok(builder.build()?)
 }
 
 #[cfg(feature = "test-feed")]
-pub fn create_price_feed(feed_id: &FeedId) -> Box<dyn PriceFeed + Send + Sync> {
+pub fn create_price_feed(
+    feed_id: &FeedId,
+    _config: &PriceFeedsConfig,
+) -> Result<Box<dyn PriceFeed + Send + Sync>> {
     match feed_id {
-        FeedId::Test => Box::new(TestFeed {}),
+        FeedId::Test => Ok(Box::new(TestFeed {})),
     }
 }
 
-pub fn create_price_feeds(feed_ids: &[FeedId]) -> Vec<Box<dyn PriceFeed + Send + Sync>> {
-    feed_ids.iter().map(|x| create_price_feed(x)).collect()
+pub fn create_price_feeds(
+    feed_ids: &[FeedId],
+    config: &PriceFeedsConfig,
+) -> Result<Vec<Box<dyn PriceFeed + Send + Sync>>> {
+    feed_ids
+        .iter()
+        .map(|x| -> Result<Box<dyn PriceFeed + Send + Sync>> {
+            // requests are shared with the pricefeeds of other asset pairs, e.g. cross rates
+            let pricefeed: Box<dyn PriceFeed + Send + Sync> =
+                Box::new(Deduplicated::new(create_price_feed(x, config)?));
+            Ok(match config.weights.get(x) {
+                Some(weight) => Box::new(Weighted {
+                    pricefeed,
+                    weight: *weight,
+                }),
+                None => pricefeed,
+            })
+        })
+        .collect()
+}
+
+/// Backoff before the first retry of a failed pricefeed request, doubled on every retry
+const RETRY_INITIAL_BACKOFF: Duration = Duration::from_millis(500);
+/// Time after which a failing pricefeed request is no longer retried
+const RETRY_DEADLINE: Duration = Duration::from_secs(30);
+/// Time after which a single pricefeed request is abandoned, unless configured otherwise
+const DEFAULT_REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+/// Time after which pricefeeds that haven't answered yet are left out of the aggregation, unless
+/// configured otherwise
+const DEFAULT_AGGREGATION_DEADLINE: Duration = Duration::from_secs(60);
+/// Consecutive failures after which a pricefeed is disabled, unless configured otherwise
+const DEFAULT_MAX_CONSECUTIVE_FAILURES: u32 = 5;
+
+/// Price of an asset pair along with the traded volume, as reported by a pricefeed
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Quote {
+    pub price: f64,
+    /// volume of the base asset traded in the candle the price is taken from, if reported
+    pub volume: Option<f64>,
+}
+
+/// Price retrieved from a single pricefeed
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct FeedPrice {
+    /// id of the pricefeed
+    pub pricefeed: &'static str,
+    pub price: f64,
+    /// weight of the pricefeed in the aggregation
+    pub weight: f64,
+    /// volume traded around the price, if the pricefeed reports it
+    pub volume: Option<f64>,
+    /// time the price was retrieved
+    pub retrieved: OffsetDateTime,
 }
 
+/// Retrieves the prices of all enabled pricefeeds concurrently, leaving out those failing or not
+/// answering within the aggregation deadline
 pub async fn get_prices(
     price_feeds: &[Box<dyn PriceFeed + Send + Sync>],
     timestamp: OffsetDateTime,
     asset_pair: AssetPair,
-) -> Vec<f64> {
-    futures::stream::iter(price_feeds.iter())
-        .then(|pricefeed| async {
-            pricefeed
-                .retrieve_price(asset_pair, timestamp)
-                .await
-                .map_err(|err| {
-                    error!("cannot retrieve price {}", err);
-                    err
-                })
-                .ok()
+    config: &PriceFeedsConfig,
+    health: &PriceFeedHealth,
+) -> Vec<FeedPrice> {
+    let enabled_feeds = price_feeds.iter().filter(|pricefeed| {
+        let disabled = health.is_disabled(pricefeed.id());
+        if disabled {
+            info!("skipping disabled pricefeed {}", pricefeed.id());
+        }
+        !disabled
+    });
+    query_prices(enabled_feeds, timestamp, asset_pair, config, health).await
+}
+
+/// Retrieves the prices of all pricefeeds like [`get_prices`], including disabled ones so they
+/// are re-enabled once they answer again
+pub async fn probe_prices(
+    price_feeds: &[Box<dyn PriceFeed + Send + Sync>],
+    timestamp: OffsetDateTime,
+    asset_pair: AssetPair,
+    config: &PriceFeedsConfig,
+    health: &PriceFeedHealth,
+) -> Vec<FeedPrice> {
+    query_prices(price_feeds.iter(), timestamp, asset_pair, config, health).await
+}
+
+async fn query_prices<'a>(
+    price_feeds: impl Iterator<Item = &'a Box<dyn PriceFeed + Send + Sync>>,
+    timestamp: OffsetDateTime,
+    asset_pair: AssetPair,
+    config: &PriceFeedsConfig,
+    health: &PriceFeedHealth,
+) -> Vec<FeedPrice> {
+    let max_consecutive_failures = config
+        .max_consecutive_failures
+        .unwrap_or(DEFAULT_MAX_CONSECUTIVE_FAILURES);
+    let request_timeout = config.request_timeout.unwrap_or(DEFAULT_REQUEST_TIMEOUT);
+    let aggregation_deadline = config
+        .aggregation_deadline
+        .unwrap_or(DEFAULT_AGGREGATION_DEADLINE);
+    let deadline = Instant::now() + aggregation_deadline;
+    futures::future::join_all(price_feeds.map(|pricefeed| async move {
+        let _timer = PRICEFEED_REQUEST_DURATION
+            .with_label_values(&[pricefeed.id()])
+            .start_timer();
+        let start = Instant::now();
+        timeout_at(
+            deadline,
+            retrieve_price_with_retry(pricefeed.as_ref(), asset_pair, timestamp, request_timeout),
+        )
+        .await
+        .unwrap_or(Err(PriceFeedError::TimeoutError(aggregation_deadline)))
+        .map(|quote| {
+            health.record_success(pricefeed.id(), start.elapsed());
+            FeedPrice {
+                pricefeed: pricefeed.id(),
+                price: quote.price,
+                weight: pricefeed.weight(),
+                volume: quote.volume,
+                retrieved: OffsetDateTime::now_utc(),
+            }
         })
-        .collect::<Vec<Option<f64>>>()
+        .map_err(|err| {
+            error!("cannot retrieve price {}", err);
+            PRICEFEED_REQUEST_FAILURES
+                .with_label_values(&[pricefeed.id()])
+                .inc();
+            health.record_failure(pricefeed.id(), err.to_string(), max_consecutive_failures);
+            err
+        })
+        .ok()
+    }))
+    .await
+    .into_iter()
+    .flatten()
+    .collect::<Vec<FeedPrice>>()
+}
+
+/// Retrieves the quote from `pricefeed`, giving up on every attempt after `request_timeout` and
+/// retrying transient failures with exponential backoff until `RETRY_DEADLINE`
+async fn retrieve_price_with_retry(
+    pricefeed: &(dyn PriceFeed + Send + Sync),
+    asset_pair: AssetPair,
+    timestamp: OffsetDateTime,
+    request_timeout: Duration,
+) -> Result<Quote> {
+    let deadline = Instant::now() + RETRY_DEADLINE;
+    let mut backoff = RETRY_INITIAL_BACKOFF;
+    loop {
+        let result = timeout(
+            request_timeout,
+            pricefeed.retrieve_quote(asset_pair, timestamp),
+        )
         .await
-        .into_iter()
-        .flatten()
-        .collect::<Vec<f64>>()
+        .unwrap_or(Err(PriceFeedError::TimeoutError(request_timeout)));
+        match result {
+            Err(err) if err.is_transient() && Instant::now() + backoff < deadline => {
+                warn!(
+                    "retrying {} request in {:?} after {}",
+                    pricefeed.id(),
+                    backoff,
+                    err
+                );
+                sleep(backoff).await;
+                backoff *= 2;
+            }
+            result => return result,
+        }
+    }
 }
 
+/// Aggregates equally weighted prices
 pub fn aggregate_price(
     prices: &Vec<f64>,
     aggregation_type: AggregationType,
     asset_pair: AssetPair,
+) -> Option<f64> {
+    let prices = prices.iter().map(|price| (*price, 1.0)).collect::<Vec<_>>();
+    aggregate_weighted_price(&prices, aggregation_type, asset_pair)
+}
+
+/// Aggregates the prices retrieved from pricefeeds according to their weights, and with `vwap`
+/// also according to their volumes, leaving out prices without volume
+pub fn aggregate_feed_prices(
+    prices: &[FeedPrice],
+    aggregation_type: AggregationType,
+    asset_pair: AssetPair,
+) -> Option<f64> {
+    let prices = prices
+        .iter()
+        .filter_map(|feed_price| match aggregation_type {
+            AggregationType::Vwap => feed_price
+                .volume
+                .filter(|volume| *volume > 0.0)
+                .map(|volume| (feed_price.price, feed_price.weight * volume)),
+            _ => Some((feed_price.price, feed_price.weight)),
+        })
+        .collect::<Vec<_>>();
+    aggregate_weighted_price(&prices, aggregation_type, asset_pair)
+}
+
+/// Returns the price of the given `(price, weight)` pairs at which half of the total weight is
+/// reached, or the average of the two prices around it if it's reached exactly in between
+fn weighted_median(prices: &[(f64, f64)]) -> f64 {
+    let mut sorted_prices = prices.to_vec();
+    sorted_prices.sort_by(|(a, _), (b, _)| a.partial_cmp(b).unwrap());
+    let half_weight = sorted_prices.iter().map(|(_, weight)| weight).sum::<f64>() / 2.0;
+    let mut cumulative_weight = 0.0;
+    for (i, (price, weight)) in sorted_prices.iter().enumerate() {
+        cumulative_weight += weight;
+        if cumulative_weight == half_weight && i + 1 < sorted_prices.len() {
+            return (price + sorted_prices[i + 1].0) / 2.0;
+        }
+        if cumulative_weight >= half_weight {
+            return *price;
+        }
+    }
+    unreachable!("the cumulative weight reaches the total weight")
+}
+
+/// Discards the prices deviating more than `max_deviation_percent` from the weighted median of
+/// all prices, so a single flash crash or fat-fingered tick cannot skew the aggregated price
+pub fn reject_outliers(
+    prices: Vec<FeedPrice>,
+    max_deviation_percent: f64,
+    asset_pair: AssetPair,
+) -> Vec<FeedPrice> {
+    if prices.is_empty() {
+        return prices;
+    }
+    let median_price = weighted_median(
+        &prices
+            .iter()
+            .map(|feed_price| (feed_price.price, feed_price.weight))
+            .collect::<Vec<_>>(),
+    );
+    prices
+        .into_iter()
+        .filter(|feed_price| {
+            let deviation_percent = (feed_price.price - median_price).abs() / median_price * 100.0;
+            if deviation_percent > max_deviation_percent {
+                warn!(
+                    "rejecting {} price {} of {}, deviating {:.2}% from median {}",
+                    feed_price.pricefeed,
+                    feed_price.price,
+                    asset_pair,
+                    deviation_percent,
+                    median_price
+                );
+                return false;
+            }
+            true
+        })
+        .collect()
+}
+
+/// Returns the weighted mean of the given `(price, weight)` pairs
+fn weighted_mean(prices: &[(f64, f64)]) -> f64 {
+    let total_weight = prices.iter().map(|(_, weight)| weight).sum::<f64>();
+    prices
+        .iter()
+        .map(|(price, weight)| price * weight)
+        .sum::<f64>()
+        / total_weight
+}
+
+fn aggregate_weighted_price(
+    prices: &[(f64, f64)],
+    aggregation_type: AggregationType,
+    asset_pair: AssetPair,
 ) -> Option<f64> {
     if prices.is_empty() {
         None
     } else {
         match aggregation_type {
             AggregationType::Average => {
-                let avg_price = prices.iter().sum::<f64>() / prices.len() as f64;
-                let avg_price = avg_price.round();
+                let avg_price = weighted_mean(prices);
                 info!("average price of {} is {}", asset_pair, avg_price);
                 Some(avg_price)
             }
+            AggregationType::Vwap => {
+                let vwap_price = weighted_mean(prices);
+                info!(
+                    "volume-weighted average price of {} is {}",
+                    asset_pair, vwap_price
+                );
+                Some(vwap_price)
+            }
             AggregationType::Median => {
+                let median_price = weighted_median(prices);
+                info!("median price of {} is {}", asset_pair, median_price);
+                Some(median_price)
+            }
+            AggregationType::TrimmedMean => {
                 let mut sorted_prices = prices.to_vec();
-                sorted_prices.sort_by(|a, b| a.partial_cmp(b).unwrap());
-                if sorted_prices.len() % 2 == 0 {
-                    let i = sorted_prices.len() / 2 - 1;
-                    let j = sorted_prices.len() / 2;
-                    let median_price = (sorted_prices[i] + sorted_prices[j]) / 2.0;
-                    info!(
-                        "median price of {} is {} (avg of {} and {})",
-                        asset_pair, median_price, sorted_prices[i], sorted_prices[j]
-                    );
-                    Some(median_price)
-                } else {
-                    let median_price = sorted_prices[sorted_prices.len() / 2];
-                    info!("median price of {} is {}", asset_pair, median_price);
-                    Some(median_price)
-                }
+                sorted_prices.sort_by(|(a, _), (b, _)| a.partial_cmp(b).unwrap());
+                // with fewer than 3 prices nothing would be left
+                let trimmed_prices = match sorted_prices.len() {
+                    0..=2 => &sorted_prices[..],
+                    len => &sorted_prices[1..len - 1],
+                };
+                let trimmed_price = weighted_mean(trimmed_prices);
+                info!(
+                    "trimmed mean price of {} is {} (of {} prices)",
+                    asset_pair,
+                    trimmed_price,
+                    trimmed_prices.len()
+                );
+                Some(trimmed_price)
             }
         }
     }
@@ -143,21 +585,42 @@ pub async fn get_aggregate_price_from_feeds(
     timestamp: OffsetDateTime,
     asset_pair: AssetPair,
     aggregation_type: AggregationType,
+    config: &PriceFeedsConfig,
+    health: &PriceFeedHealth,
 ) -> Option<f64> {
-    let prices = get_prices(price_feeds, timestamp, asset_pair).await;
-    aggregate_price(&prices, aggregation_type, asset_pair)
+    let prices = get_prices(price_feeds, timestamp, asset_pair, config, health).await;
+    aggregate_feed_prices(&prices, aggregation_type, asset_pair)
 }
 
+mod alpha_vantage;
+mod binance;
 mod bitfinex;
 mod bitstamp;
+mod bybit;
+mod coinbase;
+mod coingecko;
+mod cross_rate;
+mod custom_rest;
+mod dedup;
 mod deribit;
+mod exec;
 mod gateio;
+mod gemini;
+mod health;
 mod kraken;
+mod kraken_futures;
+mod okx;
+mod onchain;
+mod polygon;
+mod streaming;
 mod test_feed;
 
 #[cfg(test)]
 mod tests {
-    use crate::{oracle::pricefeeds::aggregate_price, AggregationType, AssetPair};
+    use crate::{
+        oracle::pricefeeds::{aggregate_feed_prices, aggregate_price, reject_outliers, FeedPrice},
+        AggregationType, AssetPair,
+    };
 
     #[test]
     fn test_aggregate() {
@@ -241,5 +704,126 @@ mod tests {
                 AssetPair::BTCUSD
             )
         );
+        assert_eq!(
+            None,
+            aggregate_price(&vec![], AggregationType::TrimmedMean, AssetPair::BTCUSD)
+        );
+        assert_eq!(
+            Some(15.0),
+            aggregate_price(
+                &vec![10.0, 20.0],
+                AggregationType::TrimmedMean,
+                AssetPair::BTCUSD
+            )
+        );
+        assert_eq!(
+            Some(35.0),
+            aggregate_price(
+                &vec![40.0, 1000.0, 30.0, 1.0],
+                AggregationType::TrimmedMean,
+                AssetPair::BTCUSD
+            )
+        );
+    }
+
+    #[test]
+    fn test_aggregate_weighted() {
+        let feed_price = |price, weight| FeedPrice {
+            pricefeed: "test",
+            price,
+            weight,
+            volume: None,
+            retrieved: OffsetDateTime::UNIX_EPOCH,
+        };
+        let prices = [
+            feed_price(100.0, 0.5),
+            feed_price(110.0, 0.3),
+            feed_price(200.0, 0.2),
+        ];
+        assert_eq!(
+            Some(123.0),
+            aggregate_feed_prices(&prices, AggregationType::Average, AssetPair::BTCUSD)
+        );
+        assert_eq!(
+            Some(105.0),
+            aggregate_feed_prices(&prices, AggregationType::Median, AssetPair::BTCUSD)
+        );
+        assert_eq!(
+            Some(110.0),
+            aggregate_feed_prices(
+                &[feed_price(100.0, 1.0), feed_price(110.0, 3.0)],
+                AggregationType::Median,
+                AssetPair::BTCUSD
+            )
+        );
+        assert_eq!(
+            Some(110.0),
+            aggregate_feed_prices(&prices, AggregationType::TrimmedMean, AssetPair::BTCUSD)
+        );
+    }
+
+    #[test]
+    fn test_aggregate_vwap() {
+        let feed_price = |price, weight, volume| FeedPrice {
+            pricefeed: "test",
+            price,
+            weight,
+            volume,
+            retrieved: OffsetDateTime::UNIX_EPOCH,
+        };
+        assert_eq!(
+            Some(103.0),
+            aggregate_feed_prices(
+                &[
+                    feed_price(100.0, 1.0, Some(7.0)),
+                    feed_price(110.0, 1.0, Some(3.0)),
+                    feed_price(200.0, 1.0, None),
+                    feed_price(300.0, 1.0, Some(0.0)),
+                ],
+                AggregationType::Vwap,
+                AssetPair::BTCUSD
+            )
+        );
+        assert_eq!(
+            Some(105.0),
+            aggregate_feed_prices(
+                &[
+                    feed_price(100.0, 0.5, Some(10.0)),
+                    feed_price(110.0, 1.0, Some(5.0)),
+                ],
+                AggregationType::Vwap,
+                AssetPair::BTCUSD
+            )
+        );
+        assert_eq!(
+            None,
+            aggregate_feed_prices(
+                &[feed_price(100.0, 1.0, None)],
+                AggregationType::Vwap,
+                AssetPair::BTCUSD
+            )
+        );
+    }
+
+    #[test]
+    fn test_reject_outliers() {
+        let feed_price = |pricefeed, price| FeedPrice {
+            pricefeed,
+            price,
+            weight: 1.0,
+            volume: None,
+            retrieved: OffsetDateTime::UNIX_EPOCH,
+        };
+        let prices = vec![
+            feed_price("bitstamp", 30000.0),
+            feed_price("kraken", 30300.0),
+            feed_price("gateio", 29800.0),
+            feed_price("bitfinex", 15000.0),
+        ];
+        let accepted = reject_outliers(prices.clone(), 2.0, AssetPair::BTCUSD);
+        assert_eq!(&prices[..3], &accepted[..]);
+        let accepted = reject_outliers(prices.clone(), 50.0, AssetPair::BTCUSD);
+        assert_eq!(prices, accepted);
+        assert!(reject_outliers(vec![], 2.0, AssetPair::BTCUSD).is_empty());
     }
 }
@@ -0,0 +1,97 @@
+use super::{FeedId, PriceFeed, PriceFeedError, Result};
+use crate::AssetPair;
+use async_trait::async_trait;
+use log::{debug, info};
+use reqwest::Client;
+use serde::Deserialize;
+use time::OffsetDateTime;
+
+#[derive(Default)]
+pub struct Okx {
+    pub client: Client,
+}
+
+#[derive(Debug, Deserialize)]
+struct Response {
+    code: String,
+    msg: String,
+    /// candles are [open time, open, high, low, close, ...]
+    data: Vec<Vec<String>>,
+}
+
+#[async_trait]
+impl PriceFeed for Okx {
+    fn id(&self) -> &'static str {
+        "okx"
+    }
+
+    fn translate_asset_pair(&self, asset_pair: AssetPair) -> Result<&'static str> {
+        match asset_pair {
+            AssetPair::BTCUSD => Err(PriceFeedError::InternalError(
+                "okx does not support USD".to_string(),
+            )),
+            AssetPair::BTCUSDT => Ok("BTC-USDT"),
+            _ => asset_pair
+                .symbol(&FeedId::Okx)
+                .ok_or(PriceFeedError::UnsupportedAssetPairError(asset_pair)),
+        }
+    }
+
+    async fn retrieve_price(&self, asset_pair: AssetPair, instant: OffsetDateTime) -> Result<f64> {
+        let client = &self.client;
+        let asset_pair_translation = self.translate_asset_pair(asset_pair)?;
+        // `after` is exclusive and pages towards older candles, so this yields the one at `instant`
+        let after = instant.unix_timestamp() * 1000 + 1;
+        info!("sending okx http request {asset_pair} {instant}");
+        let res: Response = client
+            .get("https://www.okx.com/api/v5/market/history-candles")
+            .query(&[
+                ("instId", asset_pair_translation),
+                ("bar", "1m"),
+                ("after", &after.to_string()),
+                ("limit", "1"),
+            ])
+            .send()
+            .await?
+            .json()
+            .await?;
+        debug!("received okx response: {:#?}", res);
+
+        if res.code != "0" {
+            return Err(PriceFeedError::InternalError(format!(
+                "okx error {}: {}",
+                res.code, res.msg
+            )));
+        }
+
+        let price = res
+            .data
+            .first()
+            .ok_or(PriceFeedError::PriceNotAvailableError(asset_pair, instant))?
+            .get(1)
+            .ok_or(PriceFeedError::PriceNotAvailableError(asset_pair, instant))?
+            .parse()
+            .map_err(|_| PriceFeedError::PriceNotAvailableError(asset_pair, instant))?;
+        info!("okx price {price}");
+        Ok(price)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::AssetPair;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn retrieve() {
+        let feed = Okx::default();
+        let price = feed
+            .retrieve_price(
+                AssetPair::BTCUSDT,
+                OffsetDateTime::now_utc() - time::Duration::minutes(2),
+            )
+            .await;
+        assert!(price.is_ok(), "{:#?}", &price);
+    }
+}
@@ -0,0 +1,276 @@
+use super::{PriceFeed, PriceFeedError, Result};
+use crate::{AssetPair, BitcoindConfig, EsploraConfig};
+use async_trait::async_trait;
+use log::{debug, info};
+use reqwest::Client;
+use serde::{de::DeserializeOwned, Deserialize};
+use serde_json::{json, Value};
+use time::{Duration, OffsetDateTime};
+
+/// Number of blocks the hashrate is estimated over, like the default of bitcoind's
+/// `getnetworkhashps`
+const HASHRATE_BLOCKS: u64 = 120;
+/// Attested hashrates are in TH/s, as H/s would overflow the outcome
+const HASHES_PER_TERAHASH: f64 = 1e12;
+/// Fee rates are only known for the current mempool
+const MAX_FEE_RATE_AGE: Duration = Duration::minutes(1);
+/// bitcoind estimates fee rates in BTC/kvB, attested fee rates are in sat/vB
+const SAT_PER_VB_PER_BTC_PER_KVB: f64 = 100_000.0;
+
+pub enum Backend {
+    Bitcoind(BitcoindConfig),
+    Esplora(EsploraConfig),
+}
+
+/// Bitcoin block height, difficulty and hashrate at maturation, retrieved from a bitcoind or
+/// esplora instance rather than an exchange
+pub struct OnChain {
+    pub backend: Backend,
+    pub client: Client,
+}
+
+#[derive(Debug, Deserialize)]
+struct BlockHeader {
+    /// UNIX time of the block
+    #[serde(alias = "timestamp")]
+    time: i64,
+    difficulty: f64,
+}
+
+#[derive(Debug, Deserialize)]
+struct SmartFeeEstimate {
+    /// BTC/kvB, missing if bitcoind has too little data
+    feerate: Option<f64>,
+}
+
+/// Block projected from the mempool by mempool.space
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct MempoolBlock {
+    /// sat/vB
+    median_fee: f64,
+}
+
+#[derive(Debug, Deserialize)]
+struct RpcResponse<T> {
+    result: Option<T>,
+    error: Option<Value>,
+}
+
+impl OnChain {
+    async fn rpc<T: DeserializeOwned>(
+        &self,
+        config: &BitcoindConfig,
+        method: &str,
+        params: Value,
+    ) -> Result<T> {
+        let mut request = self.client.post(&config.url).json(&json!({
+            "jsonrpc": "1.0",
+            "id": "sibyls",
+            "method": method,
+            "params": params,
+        }));
+        if let Some(user) = &config.user {
+            request =
+                request.basic_auth(user, config.password.as_ref().map(|password| &password.0));
+        }
+        // bitcoind answers errors with status 500 and a JSON body
+        let res: RpcResponse<T> = request.send().await?.json().await?;
+        match res {
+            RpcResponse {
+                result: Some(result),
+                error: None,
+            } => Ok(result),
+            RpcResponse { error, .. } => Err(PriceFeedError::InternalError(format!(
+                "bitcoind error calling {method}: {:?}",
+                error
+            ))),
+        }
+    }
+
+    async fn esplora<T: DeserializeOwned>(&self, config: &EsploraConfig, path: &str) -> Result<T> {
+        let url = format!("{}/{}", config.url.trim_end_matches('/'), path);
+        let res = self.client.get(url).send().await?.error_for_status()?;
+        // some endpoints answer plain text, which is valid JSON for numbers and quoted for hashes
+        let text = res.text().await?;
+        serde_json::from_str(&text)
+            .or_else(|_| serde_json::from_value(Value::String(text.clone())))
+            .map_err(|err| PriceFeedError::InternalError(format!("esplora response {text}: {err}")))
+    }
+
+    async fn tip_height(&self) -> Result<u64> {
+        match &self.backend {
+            Backend::Bitcoind(config) => self.rpc(config, "getblockcount", json!([])).await,
+            Backend::Esplora(config) => self.esplora(config, "blocks/tip/height").await,
+        }
+    }
+
+    async fn header(&self, height: u64) -> Result<BlockHeader> {
+        match &self.backend {
+            Backend::Bitcoind(config) => {
+                let hash: String = self.rpc(config, "getblockhash", json!([height])).await?;
+                self.rpc(config, "getblockheader", json!([hash])).await
+            }
+            Backend::Esplora(config) => {
+                let hash: String = self
+                    .esplora(config, &format!("block-height/{height}"))
+                    .await?;
+                self.esplora(config, &format!("block/{hash}")).await
+            }
+        }
+    }
+
+    /// Returns the height of the last block mined at or before `instant`. Block times are only
+    /// roughly increasing, so this is the last block whose time doesn't exceed `instant` as
+    /// found by bisection.
+    async fn height_at(&self, instant: OffsetDateTime) -> Result<u64> {
+        let timestamp = instant.unix_timestamp();
+        let tip = self.tip_height().await?;
+        if self.header(tip).await?.time <= timestamp {
+            return Ok(tip);
+        }
+        // the time of `low` never exceeds `timestamp`, the one of `high` always does
+        let (mut low, mut high) = (0, tip);
+        while high - low > 1 {
+            let middle = low + (high - low) / 2;
+            if self.header(middle).await?.time <= timestamp {
+                low = middle;
+            } else {
+                high = middle;
+            }
+        }
+        Ok(low)
+    }
+
+    /// Returns the median fee rate of the next block in sat/vB, as projected from the current
+    /// mempool by mempool.space or estimated by bitcoind
+    async fn fee_rate(&self, asset_pair: AssetPair, instant: OffsetDateTime) -> Result<f64> {
+        if (OffsetDateTime::now_utc() - instant).abs() > MAX_FEE_RATE_AGE {
+            return Err(PriceFeedError::PriceNotAvailableError(asset_pair, instant));
+        }
+        match &self.backend {
+            Backend::Bitcoind(config) => {
+                let estimate: SmartFeeEstimate =
+                    self.rpc(config, "estimatesmartfee", json!([1])).await?;
+                estimate
+                    .feerate
+                    .map(|feerate| feerate * SAT_PER_VB_PER_BTC_PER_KVB)
+                    .ok_or(PriceFeedError::PriceNotAvailableError(asset_pair, instant))
+            }
+            Backend::Esplora(config) => {
+                let blocks: Vec<MempoolBlock> =
+                    self.esplora(config, "v1/fees/mempool-blocks").await?;
+                blocks
+                    .first()
+                    .map(|block| block.median_fee)
+                    .ok_or(PriceFeedError::PriceNotAvailableError(asset_pair, instant))
+            }
+        }
+    }
+
+    /// Estimates the hashrate in H/s from the work of the last `HASHRATE_BLOCKS` blocks up to
+    /// `height`
+    async fn hashrate(&self, height: u64) -> Result<f64> {
+        match &self.backend {
+            Backend::Bitcoind(config) => {
+                self.rpc(config, "getnetworkhashps", json!([HASHRATE_BLOCKS, height]))
+                    .await
+            }
+            Backend::Esplora(_) => {
+                let start = height.saturating_sub(HASHRATE_BLOCKS);
+                let (first, last) = (self.header(start).await?, self.header(height).await?);
+                let seconds = (last.time - first.time).max(1) as f64;
+                // assumes the difficulty didn't change in between, which it does at most once
+                Ok(last.difficulty * 2f64.powi(32) * (height - start) as f64 / seconds)
+            }
+        }
+    }
+}
+
+#[async_trait]
+impl PriceFeed for OnChain {
+    fn id(&self) -> &'static str {
+        match self.backend {
+            Backend::Bitcoind(_) => "bitcoind",
+            Backend::Esplora(_) => "esplora",
+        }
+    }
+
+    fn translate_asset_pair(&self, asset_pair: AssetPair) -> Result<&'static str> {
+        match asset_pair {
+            AssetPair::BLOCKHEIGHT => Ok("height"),
+            AssetPair::DIFFICULTY => Ok("difficulty"),
+            AssetPair::HASHRATE => Ok("hashrate"),
+            AssetPair::FEERATE => Ok("feerate"),
+            _ => Err(PriceFeedError::UnsupportedAssetPairError(asset_pair)),
+        }
+    }
+
+    async fn retrieve_price(&self, asset_pair: AssetPair, instant: OffsetDateTime) -> Result<f64> {
+        let metric = self.translate_asset_pair(asset_pair)?;
+        if instant > OffsetDateTime::now_utc() + Duration::minutes(1) {
+            return Err(PriceFeedError::PriceNotAvailableError(asset_pair, instant));
+        }
+        info!("sending {} request {asset_pair} {instant}", self.id());
+        if asset_pair == AssetPair::FEERATE {
+            let value = self.fee_rate(asset_pair, instant).await?;
+            info!("{} {metric} {value}", self.id());
+            return Ok(value);
+        }
+        let height = self.height_at(instant).await?;
+        debug!("{} block at {instant}: {height}", self.id());
+        let value = match asset_pair {
+            AssetPair::DIFFICULTY => self.header(height).await?.difficulty,
+            AssetPair::HASHRATE => self.hashrate(height).await? / HASHES_PER_TERAHASH,
+            _ => height as f64,
+        };
+        info!("{} {metric} {value}", self.id());
+        Ok(value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::AssetPair;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn retrieve() {
+        let feed = OnChain {
+            backend: Backend::Esplora(EsploraConfig {
+                url: "https://blockstream.info/api".to_string(),
+            }),
+            client: Client::new(),
+        };
+        let instant = OffsetDateTime::now_utc() - Duration::days(30);
+        let height = feed.retrieve_price(AssetPair::BLOCKHEIGHT, instant).await;
+        assert!(height.is_ok(), "{:#?}", &height);
+        let height = height.unwrap() as u64;
+        let header = feed.header(height).await.unwrap();
+        assert!(header.time <= instant.unix_timestamp());
+        assert!(feed.header(height + 1).await.unwrap().time > instant.unix_timestamp());
+        assert!(feed.translate_asset_pair(AssetPair::BTCUSD).is_err());
+    }
+
+    #[tokio::test]
+    async fn retrieve_fee_rate() {
+        let feed = OnChain {
+            backend: Backend::Esplora(EsploraConfig {
+                url: "https://mempool.space/api".to_string(),
+            }),
+            client: Client::new(),
+        };
+        let fee_rate = feed
+            .retrieve_price(AssetPair::FEERATE, OffsetDateTime::now_utc())
+            .await;
+        assert!(fee_rate.is_ok(), "{:#?}", &fee_rate);
+        assert!(feed
+            .retrieve_price(
+                AssetPair::FEERATE,
+                OffsetDateTime::now_utc() - Duration::hours(1)
+            )
+            .await
+            .is_err());
+    }
+}
@@ -0,0 +1,108 @@
+use super::{FeedId, PriceFeed, PriceFeedError, Result};
+use crate::{AssetPair, MarketDataConfig};
+use async_trait::async_trait;
+use log::{debug, info};
+use reqwest::Client;
+use serde::Deserialize;
+use time::{Duration, OffsetDateTime};
+
+/// Forex rates and equity index levels of polygon.io
+pub struct Polygon {
+    pub config: MarketDataConfig,
+    pub client: Client,
+}
+
+#[derive(Debug, Deserialize)]
+struct Bar {
+    /// start of the minute in ms
+    t: i64,
+    o: f64,
+    c: f64,
+}
+
+#[derive(Debug, Deserialize)]
+struct Response {
+    #[serde(default)]
+    results: Vec<Bar>,
+}
+
+/// Time span searched for the last bar before a maturation outside trading hours, covering
+/// weekends and holidays
+const LOOKBACK: Duration = Duration::days(4);
+
+#[async_trait]
+impl PriceFeed for Polygon {
+    fn id(&self) -> &'static str {
+        "polygon"
+    }
+
+    fn translate_asset_pair(&self, asset_pair: AssetPair) -> Result<&'static str> {
+        match asset_pair {
+            AssetPair::EURUSD => Ok("C:EURUSD"),
+            AssetPair::SPX => Ok("I:SPX"),
+            _ => asset_pair
+                .symbol(&FeedId::Polygon)
+                .ok_or(PriceFeedError::UnsupportedAssetPairError(asset_pair)),
+        }
+    }
+
+    async fn retrieve_price(&self, asset_pair: AssetPair, instant: OffsetDateTime) -> Result<f64> {
+        let client = &self.client;
+        let asset_pair_translation = self.translate_asset_pair(asset_pair)?;
+        let end = instant.unix_timestamp() * 1000;
+        let start = (instant - LOOKBACK).unix_timestamp() * 1000;
+        info!("sending polygon http request {asset_pair} {instant}");
+        let res: Response = client
+            .get(format!(
+                "https://api.polygon.io/v2/aggs/ticker/{}/range/1/minute/{}/{}",
+                asset_pair_translation, start, end
+            ))
+            .query(&[
+                ("sort", "desc"),
+                ("limit", "1"),
+                ("apiKey", &self.config.api_key.0),
+            ])
+            .send()
+            .await?
+            .error_for_status()?
+            .json()
+            .await?;
+        debug!("received polygon response: {:#?}", res);
+
+        // outside trading hours, the last level before maturation is the one at maturation
+        let bar = res
+            .results
+            .first()
+            .ok_or(PriceFeedError::PriceNotAvailableError(asset_pair, instant))?;
+        let price = if bar.t == end { bar.o } else { bar.c };
+        info!("polygon price {price}");
+        Ok(price)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{ApiKey, AssetPair};
+
+    use super::*;
+
+    #[tokio::test]
+    async fn retrieve() {
+        let Ok(api_key) = std::env::var("POLYGON_API_KEY") else {
+            return;
+        };
+        let feed = Polygon {
+            config: MarketDataConfig {
+                api_key: ApiKey(api_key),
+            },
+            client: Client::new(),
+        };
+        let price = feed
+            .retrieve_price(
+                AssetPair::EURUSD,
+                OffsetDateTime::now_utc() - Duration::days(1),
+            )
+            .await;
+        assert!(price.is_ok(), "{:#?}", &price);
+    }
+}
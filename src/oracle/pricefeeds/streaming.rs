@@ -0,0 +1,135 @@
+use super::{PriceFeed, Quote, Result};
+use crate::AssetPair;
+use async_trait::async_trait;
+use futures::{SinkExt, StreamExt};
+use log::{debug, info, warn};
+use parking_lot::RwLock;
+use serde_json::Value;
+use std::sync::Arc;
+use time::{Duration, OffsetDateTime};
+use tokio::time::sleep;
+use tokio_tungstenite::{connect_async, tungstenite::Message};
+
+/// Maximum age of a streamed price to be used instead of querying the REST API
+const MAX_PRICE_AGE: Duration = Duration::seconds(10);
+const RECONNECT_DELAY: std::time::Duration = std::time::Duration::from_secs(5);
+
+/// Live price updates of an exchange WebSocket API
+pub struct WebSocketSubscription {
+    pub url: &'static str,
+    /// message subscribing to the price updates of the asset pair
+    pub subscribe: Value,
+    /// extracts the price from a message, `None` for messages that aren't price updates
+    pub parse: fn(&Value) -> Option<f64>,
+}
+
+/// Last streamed price and when it was received
+type PriceCache = Arc<RwLock<Option<(OffsetDateTime, f64)>>>;
+
+/// Pricefeed answering with the last price pushed by the exchange WebSocket API if it is recent
+/// enough, falling back to the REST API otherwise
+struct Streaming {
+    pricefeed: Box<dyn PriceFeed + Send + Sync>,
+    cache: PriceCache,
+}
+
+#[async_trait]
+impl PriceFeed for Streaming {
+    fn id(&self) -> &'static str {
+        self.pricefeed.id()
+    }
+
+    fn translate_asset_pair(&self, asset_pair: AssetPair) -> Result<&'static str> {
+        self.pricefeed.translate_asset_pair(asset_pair)
+    }
+
+    async fn retrieve_price(&self, asset_pair: AssetPair, datetime: OffsetDateTime) -> Result<f64> {
+        Ok(self.retrieve_quote(asset_pair, datetime).await?.price)
+    }
+
+    async fn retrieve_quote(
+        &self,
+        asset_pair: AssetPair,
+        datetime: OffsetDateTime,
+    ) -> Result<Quote> {
+        let now = OffsetDateTime::now_utc();
+        // the cache only knows the current price, streamed trades and tickers carry no volume
+        if (now - datetime).abs() <= MAX_PRICE_AGE {
+            if let Some((received, price)) = *self.cache.read() {
+                if now - received <= MAX_PRICE_AGE {
+                    debug!("using streamed {} price {price}", self.id());
+                    return Ok(Quote {
+                        price,
+                        volume: None,
+                    });
+                }
+            }
+        }
+        self.pricefeed.retrieve_quote(asset_pair, datetime).await
+    }
+
+    fn weight(&self) -> f64 {
+        self.pricefeed.weight()
+    }
+
+    fn websocket(&self, asset_pair: AssetPair) -> Option<WebSocketSubscription> {
+        self.pricefeed.websocket(asset_pair)
+    }
+}
+
+/// Keeps a live price cache for every pricefeed with a WebSocket API, so prices at maturation
+/// don't depend on REST latency and rate limits
+pub fn stream_prices(
+    pricefeeds: Vec<Box<dyn PriceFeed + Send + Sync>>,
+    asset_pair: AssetPair,
+) -> Vec<Box<dyn PriceFeed + Send + Sync>> {
+    pricefeeds
+        .into_iter()
+        .map(|pricefeed| -> Box<dyn PriceFeed + Send + Sync> {
+            match pricefeed.websocket(asset_pair) {
+                Some(subscription) => {
+                    let cache = PriceCache::default();
+                    tokio::spawn(stream(pricefeed.id(), subscription, cache.clone()));
+                    Box::new(Streaming { pricefeed, cache })
+                }
+                None => pricefeed,
+            }
+        })
+        .collect()
+}
+
+/// Updates `cache` with the prices pushed over `subscription`, reconnecting when disconnected
+async fn stream(id: &'static str, subscription: WebSocketSubscription, cache: PriceCache) {
+    loop {
+        info!("connecting to {} websocket {}", id, subscription.url);
+        match connect_async(subscription.url).await {
+            Ok((mut socket, _)) => {
+                let subscribe = Message::Text(subscription.subscribe.to_string());
+                if let Err(err) = socket.send(subscribe).await {
+                    warn!("cannot subscribe to {} websocket: {}", id, err);
+                }
+                while let Some(message) = socket.next().await {
+                    match message {
+                        Ok(Message::Text(text)) => {
+                            let price = serde_json::from_str::<Value>(&text)
+                                .ok()
+                                .as_ref()
+                                .and_then(subscription.parse);
+                            if let Some(price) = price {
+                                *cache.write() = Some((OffsetDateTime::now_utc(), price));
+                            }
+                        }
+                        Ok(Message::Close(_)) => break,
+                        Ok(_) => {}
+                        Err(err) => {
+                            warn!("{} websocket error: {}", id, err);
+                            break;
+                        }
+                    }
+                }
+            }
+            Err(err) => warn!("cannot connect to {} websocket: {}", id, err),
+        }
+        sleep(RECONNECT_DELAY).await;
+    }
+}
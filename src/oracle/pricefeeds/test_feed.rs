@@ -17,10 +17,7 @@ impl PriceFeed for TestFeed {
         &self,
         asset_pair: AssetPair,
     ) -> crate::oracle::pricefeeds::Result<&'static str> {
-        match asset_pair {
-            AssetPair::BTCUSD => Ok("BTCUSD"),
-            AssetPair::BTCUSDT => Ok("BTCUSDT"),
-        }
+        Ok(asset_pair.as_str())
     }
 
     async fn retrieve_price(
@@ -53,7 +50,7 @@ impl PriceFeed for TestFeed {
 
 #[cfg(test)]
 mod tests {
-    use crate::AssetPair::*;
+    use crate::AssetPair;
     use std::fs;
     use std::fs::File;
 
@@ -68,19 +65,25 @@ mod tests {
 
         let feed = TestFeed {};
 
-        let price = feed.retrieve_price(BTCUSD, OffsetDateTime::now_utc()).await;
+        let price = feed
+            .retrieve_price(AssetPair::BTCUSD, OffsetDateTime::now_utc())
+            .await;
         assert!(price.is_err());
 
         let mut output = File::create(path).unwrap();
         let _ = write!(output, "ABC");
 
-        let price = feed.retrieve_price(BTCUSD, OffsetDateTime::now_utc()).await;
+        let price = feed
+            .retrieve_price(AssetPair::BTCUSD, OffsetDateTime::now_utc())
+            .await;
         assert!(price.is_err());
 
         let mut output = File::create(path).unwrap();
         let _ = write!(output, "123");
 
-        let price = feed.retrieve_price(BTCUSD, OffsetDateTime::now_utc()).await;
+        let price = feed
+            .retrieve_price(AssetPair::BTCUSD, OffsetDateTime::now_utc())
+            .await;
         match price {
             Ok(value) => assert_eq!(123., value),
             Err(_) => assert!(false, "{:#?}", &price),
@@ -89,7 +92,9 @@ mod tests {
         let mut output = File::create(path).unwrap();
         let _ = write!(output, "456.789");
 
-        let price = feed.retrieve_price(BTCUSD, OffsetDateTime::now_utc()).await;
+        let price = feed
+            .retrieve_price(AssetPair::BTCUSD, OffsetDateTime::now_utc())
+            .await;
         match price {
             Ok(value) => assert_eq!(456.789, value),
             Err(_) => assert!(false, "{:#?}", &price),
@@ -0,0 +1,163 @@
+use super::{EventStorage, Events, Result};
+use parking_lot::RwLock;
+use std::{
+    collections::{BTreeMap, HashMap},
+    ops::Bound,
+};
+
+#[derive(Debug, Default)]
+struct Stored {
+    events: BTreeMap<String, Vec<u8>>,
+    /// maps event ids to the maturations of `events`
+    event_ids: HashMap<String, String>,
+    /// maps signed nonces to the outcomes they signed
+    signed_nonces: HashMap<[u8; 32], String>,
+}
+
+/// Oracle events kept in memory and lost once dropped, e.g. for tests and ephemeral runs, which
+/// neither need a directory of their own nor lock one
+#[derive(Debug, Default)]
+pub struct MemoryStorage {
+    stored: RwLock<Stored>,
+}
+
+impl MemoryStorage {
+    pub fn new() -> MemoryStorage {
+        MemoryStorage::default()
+    }
+}
+
+impl EventStorage for MemoryStorage {
+    fn get(&self, maturation: &str) -> Result<Option<Vec<u8>>> {
+        Ok(self.stored.read().events.get(maturation).cloned())
+    }
+
+    fn event_maturation(&self, event_id: &str) -> Result<Option<String>> {
+        Ok(self.stored.read().event_ids.get(event_id).cloned())
+    }
+
+    fn insert(&self, maturation: &str, event_id: &str, event: &[u8]) -> Result<bool> {
+        let mut stored = self.stored.write();
+        if stored.events.contains_key(maturation) {
+            return Ok(false);
+        }
+        stored.events.insert(maturation.to_string(), event.to_vec());
+        if !event_id.is_empty() {
+            stored
+                .event_ids
+                .insert(event_id.to_string(), maturation.to_string());
+        }
+        Ok(true)
+    }
+
+    fn update(&self, maturation: &str, current: &[u8], new: &[u8]) -> Result<bool> {
+        match self.stored.write().events.get_mut(maturation) {
+            Some(event) if event == current => {
+                *event = new.to_vec();
+                Ok(true)
+            }
+            _ => Ok(false),
+        }
+    }
+
+    fn remove(&self, maturation: &str, event_id: &str, current: &[u8]) -> Result<bool> {
+        let mut stored = self.stored.write();
+        if stored.events.get(maturation).map(Vec::as_slice) != Some(current) {
+            return Ok(false);
+        }
+        stored.events.remove(maturation);
+        if !event_id.is_empty() {
+            stored.event_ids.remove(event_id);
+        }
+        Ok(true)
+    }
+
+    fn range(&self, range: (Bound<String>, Bound<String>)) -> Result<Events<'_>> {
+        // unlike the other backends, BTreeMap::range panics on ranges ending before they start
+        if let (
+            Bound::Included(start) | Bound::Excluded(start),
+            Bound::Included(end) | Bound::Excluded(end),
+        ) = &range
+        {
+            if start > end
+                || start == end && matches!(range, (Bound::Excluded(_), Bound::Excluded(_)))
+            {
+                return Ok(Box::new(std::iter::empty()));
+            }
+        }
+        let events = self
+            .stored
+            .read()
+            .events
+            .range(range)
+            .map(|(maturation, event)| Ok((maturation.clone(), event.clone())))
+            .collect::<Vec<_>>();
+        Ok(Box::new(events.into_iter()))
+    }
+
+    fn record_signed_nonces(&self, nonces: &[[u8; 32]], outcomes: &[String]) -> Result<bool> {
+        let mut stored = self.stored.write();
+        let signed_other = nonces.iter().zip(outcomes).any(|(nonce, outcome)| {
+            stored
+                .signed_nonces
+                .get(nonce)
+                .is_some_and(|signed| signed != outcome)
+        });
+        if signed_other {
+            return Ok(false);
+        }
+        for (nonce, outcome) in nonces.iter().zip(outcomes) {
+            stored.signed_nonces.insert(*nonce, outcome.clone());
+        }
+        Ok(true)
+    }
+
+    fn has_signed_nonces(&self) -> Result<bool> {
+        Ok(!self.stored.read().signed_nonces.is_empty())
+    }
+
+    fn size_on_disk(&self) -> Result<u64> {
+        Ok(0)
+    }
+
+    fn flush(&self) -> Result<()> {
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn events_are_only_replaced_if_unchanged() {
+        let storage = MemoryStorage::new();
+        let first = "2022-06-07T08:00:00Z";
+        let second = "2022-06-08T08:00:00Z";
+        assert!(storage.is_empty().unwrap());
+        assert!(storage.insert(second, "second", b"announced").unwrap());
+        assert!(storage.insert(first, "", b"announced").unwrap());
+        assert!(!storage.insert(first, "", b"again").unwrap());
+        assert_eq!(
+            Some(second.to_string()),
+            storage.event_maturation("second").unwrap()
+        );
+
+        assert!(!storage.update(first, b"again", b"attested").unwrap());
+        assert!(storage.update(first, b"announced", b"attested").unwrap());
+        assert_eq!(
+            vec![(first.to_string(), b"attested".to_vec())],
+            storage
+                .range((Bound::Unbounded, Bound::Excluded(second.to_string())))
+                .unwrap()
+                .map(Result::unwrap)
+                .collect::<Vec<_>>()
+        );
+
+        assert!(!storage.remove(second, "second", b"attested").unwrap());
+        assert!(storage.remove(second, "second", b"announced").unwrap());
+        assert_eq!(None, storage.get(second).unwrap());
+        assert_eq!(None, storage.event_maturation("second").unwrap());
+        assert!(storage.contains(first).unwrap());
+    }
+}
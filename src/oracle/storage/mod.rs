@@ -0,0 +1,139 @@
+//! Storage of the oracle events of an asset pair, keyed by their RFC3339 maturation, so they can
+//! live in the local sled database or in a database shared by several instances
+
+use crate::DatabaseConfig;
+use displaydoc::Display;
+use std::{fmt::Debug, ops::Bound, sync::Arc};
+use thiserror::Error;
+
+mod memory_storage;
+#[cfg(feature = "postgres")]
+mod postgres_storage;
+mod sled_storage;
+#[cfg(feature = "sqlite")]
+mod sqlite_storage;
+
+pub use memory_storage::MemoryStorage;
+#[cfg(feature = "postgres")]
+pub use postgres_storage::PostgresStorage;
+pub use sled_storage::SledStorage;
+#[cfg(feature = "sqlite")]
+pub use sqlite_storage::SqliteStorage;
+
+pub type Result<T> = std::result::Result<T, StorageError>;
+
+#[derive(Debug, Display, Error)]
+pub enum StorageError {
+    /// sled error: {0}
+    SledError(#[from] sled::Error),
+
+    /// json serialization/deserialization error: {0}
+    SerdeJsonError(#[from] serde_json::Error),
+
+    /// PostgreSQL error: {0}
+    #[cfg(feature = "postgres")]
+    PostgresError(#[from] postgres::Error),
+
+    /// SQLite error: {0}
+    #[cfg(feature = "sqlite")]
+    SqliteError(#[from] rusqlite::Error),
+
+    /// lost connection to the {0} database
+    ConnectionError(&'static str),
+
+    /// storage backend {0} not supported by this build, enable its feature
+    UnsupportedBackendError(&'static str),
+}
+
+/// Stored oracle events with their RFC3339 maturation, in order of maturation
+pub type Events<'a> = Box<dyn DoubleEndedIterator<Item = Result<(String, Vec<u8>)>> + 'a>;
+
+/// Oracle events of an asset pair by their RFC3339 maturation, each the JSON of its
+/// [`DbValue`](super::DbValue). RFC3339 maturations in UTC order lexicographically, so ranges of
+/// maturations are ranges of keys. Events are only ever inserted, replaced or removed if stored
+/// as expected, so concurrent writers, e.g. several instances sharing a database, cannot
+/// overwrite each other's attestations. The outcomes signed by the nonces of the events are
+/// recorded alongside, so that no instance sharing the database signs another outcome with
+/// them.
+pub trait EventStorage: Debug + Send + Sync {
+    /// Returns the oracle event maturing at `maturation`, if stored
+    fn get(&self, maturation: &str) -> Result<Option<Vec<u8>>>;
+
+    /// Returns the maturation of the oracle event with `event_id`, if stored
+    fn event_maturation(&self, event_id: &str) -> Result<Option<String>>;
+
+    /// Stores `event` with `event_id` maturing at `maturation`, unless an event maturing then
+    /// is stored already. Returns whether it was stored
+    fn insert(&self, maturation: &str, event_id: &str, event: &[u8]) -> Result<bool>;
+
+    /// Replaces the oracle event maturing at `maturation` with `new`, e.g. once attested, if it
+    /// is still `current`. Returns whether it was replaced
+    fn update(&self, maturation: &str, current: &[u8], new: &[u8]) -> Result<bool>;
+
+    /// Removes the oracle event with `event_id` maturing at `maturation`, if it is still
+    /// `current`. Returns whether it was removed
+    fn remove(&self, maturation: &str, event_id: &str, current: &[u8]) -> Result<bool>;
+
+    /// Returns the oracle events maturing within `range`
+    fn range(&self, range: (Bound<String>, Bound<String>)) -> Result<Events<'_>>;
+
+    /// Records `nonces` as signing `outcomes`, one per nonce, unless any of them signed another
+    /// outcome already. The records are kept when events are removed. Returns whether they were
+    /// recorded
+    fn record_signed_nonces(&self, nonces: &[[u8; 32]], outcomes: &[String]) -> Result<bool>;
+
+    /// Returns whether signed nonces of these oracle events were recorded
+    fn has_signed_nonces(&self) -> Result<bool>;
+
+    /// Returns the size of the stored oracle events in bytes
+    fn size_on_disk(&self) -> Result<u64>;
+
+    /// Waits for all stored oracle events to be durable
+    fn flush(&self) -> Result<()>;
+
+    /// Returns all oracle events
+    fn iter(&self) -> Result<Events<'_>> {
+        self.range((Bound::Unbounded, Bound::Unbounded))
+    }
+
+    fn contains(&self, maturation: &str) -> Result<bool> {
+        Ok(self.get(maturation)?.is_some())
+    }
+
+    fn is_empty(&self) -> Result<bool> {
+        Ok(self.iter()?.next().is_none())
+    }
+}
+
+/// Opens the storage configured by `config` of the oracle events stored as `name`, i.e. the
+/// asset pair optionally followed by its additional schedule. `database` is the local sled
+/// database of the oracle, which stores the oracle events unless another backend is configured
+pub fn open(
+    config: &DatabaseConfig,
+    name: &str,
+    database: &sled::Db,
+) -> Result<Arc<dyn EventStorage>> {
+    match config {
+        DatabaseConfig::Sled => Ok(Arc::new(SledStorage::new(database)?)),
+        DatabaseConfig::Memory => Ok(Arc::new(MemoryStorage::new())),
+        #[cfg(feature = "postgres")]
+        DatabaseConfig::Postgres { url } => Ok(Arc::new(PostgresStorage::connect(
+            &url.0,
+            name.to_string(),
+        )?)),
+        #[cfg(not(feature = "postgres"))]
+        DatabaseConfig::Postgres { .. } => {
+            let _ = name;
+            Err(StorageError::UnsupportedBackendError("postgres"))
+        }
+        #[cfg(feature = "sqlite")]
+        DatabaseConfig::Sqlite { path } => {
+            Ok(Arc::new(SqliteStorage::open(path, name.to_string())?))
+        }
+        #[cfg(not(feature = "sqlite"))]
+        DatabaseConfig::Sqlite { .. } => {
+            let _ = name;
+            Err(StorageError::UnsupportedBackendError("sqlite"))
+        }
+    }
+}
@@ -0,0 +1,241 @@
+use super::{EventStorage, Events, Result, StorageError};
+use log::{error, info};
+use postgres::{types::ToSql, Client, NoTls};
+use std::{ops::Bound, sync::mpsc, thread};
+
+/// Table of the oracle events of all asset pairs and schedules, told apart by `namespace`.
+/// Maturations are compared bytewise, as RFC3339 maturations order lexicographically. The
+/// outcomes signed by nonces are shared by all namespaces, as no nonce may sign two outcomes
+const SCHEMA: &str = r#"
+CREATE TABLE IF NOT EXISTS oracle_events (
+    namespace TEXT NOT NULL,
+    maturation TEXT COLLATE "C" NOT NULL,
+    event_id TEXT NOT NULL,
+    event BYTEA NOT NULL,
+    PRIMARY KEY (namespace, maturation)
+);
+CREATE INDEX IF NOT EXISTS oracle_events_event_id
+    ON oracle_events (namespace, event_id) WHERE event_id <> '';
+CREATE TABLE IF NOT EXISTS signed_nonces (
+    nonce BYTEA PRIMARY KEY,
+    namespace TEXT NOT NULL,
+    outcome TEXT NOT NULL
+);
+"#;
+
+type Query = Box<dyn FnOnce(&mut Client) + Send>;
+
+/// Oracle events stored in a PostgreSQL database, which can be shared by several instances or
+/// managed apart from the oracle. The blocking client cannot run within the async runtime of
+/// the oracle, so queries are run by a worker thread owning the connection
+#[derive(Debug)]
+pub struct PostgresStorage {
+    namespace: String,
+    queries: mpsc::Sender<Query>,
+}
+
+impl PostgresStorage {
+    /// Connects to the database at `url` and creates the table of oracle events if missing.
+    /// The oracle events are stored as `namespace`
+    pub fn connect(url: &str, namespace: String) -> Result<PostgresStorage> {
+        let (queries, received_queries) = mpsc::channel::<Query>();
+        let (connected, connection) = mpsc::channel();
+        let url = url.to_string();
+        thread::Builder::new()
+            .name(format!("postgres-{namespace}"))
+            .spawn(move || {
+                let connect = || {
+                    let mut client = Client::connect(&url, NoTls)?;
+                    client.batch_execute(SCHEMA)?;
+                    Ok::<_, postgres::Error>(client)
+                };
+                let mut client = match connect() {
+                    Ok(client) => client,
+                    Err(err) => {
+                        let _ = connected.send(Err(err));
+                        return;
+                    }
+                };
+                let _ = connected.send(Ok(()));
+                for query in received_queries {
+                    if client.is_closed() {
+                        info!("reconnecting to PostgreSQL");
+                        match connect() {
+                            Ok(reconnected) => client = reconnected,
+                            Err(err) => error!("cannot reconnect to PostgreSQL: {err}"),
+                        }
+                    }
+                    query(&mut client);
+                }
+            })
+            .map_err(|_| StorageError::ConnectionError("postgres"))?;
+        connection
+            .recv()
+            .map_err(|_| StorageError::ConnectionError("postgres"))??;
+        Ok(PostgresStorage { namespace, queries })
+    }
+
+    /// Runs `query` with the namespace of the oracle events on the worker thread and waits for
+    /// its result
+    fn run<T: Send + 'static>(
+        &self,
+        query: impl FnOnce(&mut Client, &str) -> std::result::Result<T, postgres::Error>
+            + Send
+            + 'static,
+    ) -> Result<T> {
+        let (result, received_result) = mpsc::channel();
+        let namespace = self.namespace.clone();
+        self.queries
+            .send(Box::new(move |client| {
+                let _ = result.send(query(client, &namespace));
+            }))
+            .map_err(|_| StorageError::ConnectionError("postgres"))?;
+        Ok(received_result
+            .recv()
+            .map_err(|_| StorageError::ConnectionError("postgres"))??)
+    }
+}
+
+impl EventStorage for PostgresStorage {
+    fn get(&self, maturation: &str) -> Result<Option<Vec<u8>>> {
+        let maturation = maturation.to_string();
+        self.run(move |client, namespace| {
+            Ok(client
+                .query_opt(
+                    "SELECT event FROM oracle_events WHERE namespace = $1 AND maturation = $2",
+                    &[&namespace, &maturation],
+                )?
+                .map(|row| row.get(0)))
+        })
+    }
+
+    fn event_maturation(&self, event_id: &str) -> Result<Option<String>> {
+        let event_id = event_id.to_string();
+        self.run(move |client, namespace| {
+            Ok(client
+                .query_opt(
+                    "SELECT maturation FROM oracle_events WHERE namespace = $1 AND event_id = $2",
+                    &[&namespace, &event_id],
+                )?
+                .map(|row| row.get(0)))
+        })
+    }
+
+    fn insert(&self, maturation: &str, event_id: &str, event: &[u8]) -> Result<bool> {
+        let (maturation, event_id, event) =
+            (maturation.to_string(), event_id.to_string(), event.to_vec());
+        self.run(move |client, namespace| {
+            Ok(client.execute(
+                "INSERT INTO oracle_events (namespace, maturation, event_id, event)
+                    VALUES ($1, $2, $3, $4) ON CONFLICT DO NOTHING",
+                &[&namespace, &maturation, &event_id, &event],
+            )? == 1)
+        })
+    }
+
+    fn update(&self, maturation: &str, current: &[u8], new: &[u8]) -> Result<bool> {
+        let (maturation, current, new) = (maturation.to_string(), current.to_vec(), new.to_vec());
+        self.run(move |client, namespace| {
+            Ok(client.execute(
+                "UPDATE oracle_events SET event = $4
+                    WHERE namespace = $1 AND maturation = $2 AND event = $3",
+                &[&namespace, &maturation, &current, &new],
+            )? == 1)
+        })
+    }
+
+    fn remove(&self, maturation: &str, _event_id: &str, current: &[u8]) -> Result<bool> {
+        let (maturation, current) = (maturation.to_string(), current.to_vec());
+        self.run(move |client, namespace| {
+            Ok(client.execute(
+                "DELETE FROM oracle_events
+                    WHERE namespace = $1 AND maturation = $2 AND event = $3",
+                &[&namespace, &maturation, &current],
+            )? == 1)
+        })
+    }
+
+    fn range(&self, range: (Bound<String>, Bound<String>)) -> Result<Events<'_>> {
+        let events = self.run(move |client, namespace| {
+            let mut query =
+                "SELECT maturation, event FROM oracle_events WHERE namespace = $1".to_string();
+            let mut params: Vec<&(dyn ToSql + Sync)> = vec![&namespace];
+            for (bound, inclusive, exclusive) in [(&range.0, ">=", ">"), (&range.1, "<=", "<")] {
+                let (operator, maturation) = match bound {
+                    Bound::Included(maturation) => (inclusive, maturation),
+                    Bound::Excluded(maturation) => (exclusive, maturation),
+                    Bound::Unbounded => continue,
+                };
+                params.push(maturation);
+                query += &format!(" AND maturation {operator} ${}", params.len());
+            }
+            query += " ORDER BY maturation";
+            Ok(client
+                .query(&query, &params)?
+                .into_iter()
+                .map(|row| Ok((row.get(0), row.get(1))))
+                .collect::<Vec<Result<(String, Vec<u8>)>>>())
+        })?;
+        Ok(Box::new(events.into_iter()))
+    }
+
+    fn record_signed_nonces(&self, nonces: &[[u8; 32]], outcomes: &[String]) -> Result<bool> {
+        let nonces = nonces
+            .iter()
+            .map(|nonce| nonce.to_vec())
+            .collect::<Vec<_>>();
+        let outcomes = outcomes.to_vec();
+        self.run(move |client, namespace| {
+            let mut transaction = client.transaction()?;
+            for (nonce, outcome) in nonces.iter().zip(&outcomes) {
+                // waits for instances recording the same nonce concurrently to commit
+                transaction.execute(
+                    "INSERT INTO signed_nonces (nonce, namespace, outcome)
+                        VALUES ($1, $2, $3) ON CONFLICT DO NOTHING",
+                    &[nonce, &namespace, outcome],
+                )?;
+                let signed: String = transaction
+                    .query_one(
+                        "SELECT outcome FROM signed_nonces WHERE nonce = $1",
+                        &[nonce],
+                    )?
+                    .get(0);
+                if signed != *outcome {
+                    // rolled back once dropped
+                    return Ok(false);
+                }
+            }
+            transaction.commit()?;
+            Ok(true)
+        })
+    }
+
+    fn has_signed_nonces(&self) -> Result<bool> {
+        self.run(|client, namespace| {
+            Ok(client
+                .query_one(
+                    "SELECT EXISTS (SELECT 1 FROM signed_nonces WHERE namespace = $1)",
+                    &[&namespace],
+                )?
+                .get(0))
+        })
+    }
+
+    fn size_on_disk(&self) -> Result<u64> {
+        self.run(|client, namespace| {
+            let size: i64 = client
+                .query_one(
+                    "SELECT COALESCE(SUM(pg_column_size(oracle_events.*)), 0)::BIGINT
+                        FROM oracle_events WHERE namespace = $1",
+                    &[&namespace],
+                )?
+                .get(0);
+            Ok(size as u64)
+        })
+    }
+
+    fn flush(&self) -> Result<()> {
+        // every statement is committed once it returns
+        Ok(())
+    }
+}
@@ -0,0 +1,196 @@
+use super::{EventStorage, Events, Result};
+use crate::oracle::DbValue;
+use log::info;
+use sled::{transaction::TransactionError, Db, Tree};
+use std::ops::Bound;
+
+/// Oracle events stored in the default tree of the local sled database of the oracle, with their
+/// event ids indexed in its `event_ids` tree and their signed nonces in its `signed_nonces` tree
+#[derive(Clone, Debug)]
+pub struct SledStorage {
+    events: Db,
+    /// maps event ids to the maturations of `events`
+    event_ids: Tree,
+    /// maps signed nonces to the outcomes they signed
+    signed_nonces: Tree,
+}
+
+impl SledStorage {
+    pub fn new(database: &Db) -> Result<SledStorage> {
+        let event_ids = database.open_tree("event_ids")?;
+        if event_ids.is_empty() && !database.is_empty() {
+            info!("indexing event ids of existing oracle events");
+            for entry in database.iter() {
+                let (maturation, event) = entry?;
+                let event: DbValue = serde_json::from_slice(&event)?;
+                let event_id = event.event_id();
+                if !event_id.is_empty() {
+                    event_ids.insert(event_id, maturation)?;
+                }
+            }
+        }
+        Ok(SledStorage {
+            events: database.clone(),
+            event_ids,
+            signed_nonces: database.open_tree("signed_nonces")?,
+        })
+    }
+}
+
+impl EventStorage for SledStorage {
+    fn get(&self, maturation: &str) -> Result<Option<Vec<u8>>> {
+        Ok(self.events.get(maturation)?.map(|event| event.to_vec()))
+    }
+
+    fn event_maturation(&self, event_id: &str) -> Result<Option<String>> {
+        Ok(self
+            .event_ids
+            .get(event_id)?
+            .map(|maturation| String::from_utf8_lossy(&maturation).to_string()))
+    }
+
+    fn insert(&self, maturation: &str, event_id: &str, event: &[u8]) -> Result<bool> {
+        if self
+            .events
+            .compare_and_swap(maturation, None as Option<&[u8]>, Some(event))?
+            .is_err()
+        {
+            return Ok(false);
+        }
+        if !event_id.is_empty() {
+            self.event_ids.insert(event_id, maturation)?;
+        }
+        Ok(true)
+    }
+
+    fn update(&self, maturation: &str, current: &[u8], new: &[u8]) -> Result<bool> {
+        Ok(self
+            .events
+            .compare_and_swap(maturation, Some(current), Some(new))?
+            .is_ok())
+    }
+
+    fn remove(&self, maturation: &str, event_id: &str, current: &[u8]) -> Result<bool> {
+        if self
+            .events
+            .compare_and_swap(maturation, Some(current), None as Option<&[u8]>)?
+            .is_err()
+        {
+            return Ok(false);
+        }
+        if !event_id.is_empty() {
+            self.event_ids.remove(event_id)?;
+        }
+        Ok(true)
+    }
+
+    fn range(&self, range: (Bound<String>, Bound<String>)) -> Result<Events<'_>> {
+        Ok(Box::new(self.events.range(range).map(|entry| {
+            let (maturation, event) = entry?;
+            Ok((
+                String::from_utf8_lossy(&maturation).to_string(),
+                event.to_vec(),
+            ))
+        })))
+    }
+
+    fn record_signed_nonces(&self, nonces: &[[u8; 32]], outcomes: &[String]) -> Result<bool> {
+        let recorded = self.signed_nonces.transaction(|signed_nonces| {
+            for (nonce, outcome) in nonces.iter().zip(outcomes) {
+                match signed_nonces.insert(nonce.as_slice(), outcome.as_bytes())? {
+                    Some(signed) if signed != outcome.as_bytes() => {
+                        return sled::transaction::abort(())
+                    }
+                    _ => {}
+                }
+            }
+            Ok(())
+        });
+        match recorded {
+            Ok(()) => Ok(true),
+            Err(TransactionError::Abort(())) => Ok(false),
+            Err(TransactionError::Storage(err)) => Err(err.into()),
+        }
+    }
+
+    fn has_signed_nonces(&self) -> Result<bool> {
+        Ok(!self.signed_nonces.is_empty())
+    }
+
+    fn size_on_disk(&self) -> Result<u64> {
+        Ok(self.events.size_on_disk()?)
+    }
+
+    fn flush(&self) -> Result<()> {
+        self.events.flush()?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn events_are_only_replaced_if_unchanged() {
+        let database = sled::Config::new().temporary(true).open().unwrap();
+        let storage = SledStorage::new(&database).unwrap();
+        let first = "2022-06-07T08:00:00Z";
+        let second = "2022-06-08T08:00:00Z";
+        assert!(storage.is_empty().unwrap());
+        assert!(storage.insert(second, "second", b"announced").unwrap());
+        assert!(storage.insert(first, "", b"announced").unwrap());
+        assert!(!storage.insert(first, "", b"again").unwrap());
+        assert_eq!(Some(b"announced".to_vec()), storage.get(first).unwrap());
+        assert_eq!(
+            Some(second.to_string()),
+            storage.event_maturation("second").unwrap()
+        );
+
+        assert!(!storage.update(first, b"again", b"attested").unwrap());
+        assert!(storage.update(first, b"announced", b"attested").unwrap());
+        assert_eq!(
+            vec![first.to_string(), second.to_string()],
+            storage
+                .iter()
+                .unwrap()
+                .map(|entry| entry.unwrap().0)
+                .collect::<Vec<_>>()
+        );
+        assert_eq!(
+            vec![(first.to_string(), b"attested".to_vec())],
+            storage
+                .range((Bound::Unbounded, Bound::Excluded(second.to_string())))
+                .unwrap()
+                .map(Result::unwrap)
+                .collect::<Vec<_>>()
+        );
+
+        assert!(!storage.remove(second, "second", b"attested").unwrap());
+        assert!(storage.remove(second, "second", b"announced").unwrap());
+        assert_eq!(None, storage.get(second).unwrap());
+        assert_eq!(None, storage.event_maturation("second").unwrap());
+    }
+
+    #[test]
+    fn nonces_never_sign_another_outcome() {
+        let database = sled::Config::new().temporary(true).open().unwrap();
+        let storage = SledStorage::new(&database).unwrap();
+        let outcomes = ["1".to_string(), "2".to_string()];
+        assert!(!storage.has_signed_nonces().unwrap());
+        assert!(storage
+            .record_signed_nonces(&[[1; 32], [2; 32]], &outcomes)
+            .unwrap());
+        assert!(storage
+            .record_signed_nonces(&[[1; 32], [2; 32]], &outcomes)
+            .unwrap());
+        assert!(!storage
+            .record_signed_nonces(&[[3; 32], [2; 32]], &["3".to_string(), "3".to_string()])
+            .unwrap());
+        // nothing is recorded if any nonce is refused
+        assert!(storage
+            .record_signed_nonces(&[[3; 32]], &["4".to_string()])
+            .unwrap());
+        assert!(storage.has_signed_nonces().unwrap());
+    }
+}
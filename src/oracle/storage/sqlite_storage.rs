@@ -0,0 +1,237 @@
+use super::{EventStorage, Events, Result, StorageError};
+use log::info;
+use rusqlite::{params, params_from_iter, Connection, OptionalExtension, TransactionBehavior};
+use std::{ops::Bound, path::Path, sync::Mutex, time::Duration};
+
+/// Table of the oracle events of all asset pairs and schedules, told apart by `namespace`. The
+/// primary key indexes maturations, which are compared bytewise like RFC3339 maturations order.
+/// The outcomes signed by nonces are shared by all namespaces, as no nonce may sign two outcomes
+const SCHEMA: &str = r#"
+CREATE TABLE IF NOT EXISTS oracle_events (
+    namespace TEXT NOT NULL,
+    maturation TEXT NOT NULL,
+    event_id TEXT NOT NULL,
+    event BLOB NOT NULL,
+    PRIMARY KEY (namespace, maturation)
+) WITHOUT ROWID;
+CREATE INDEX IF NOT EXISTS oracle_events_event_id
+    ON oracle_events (namespace, event_id) WHERE event_id <> '';
+CREATE TABLE IF NOT EXISTS signed_nonces (
+    nonce BLOB PRIMARY KEY,
+    namespace TEXT NOT NULL,
+    outcome TEXT NOT NULL
+) WITHOUT ROWID;
+"#;
+
+/// How long a write waits for the oracle of another asset pair writing to the same file
+const BUSY_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Oracle events stored in a single SQLite database file, which can be inspected and backed up
+/// with standard tooling
+#[derive(Debug)]
+pub struct SqliteStorage {
+    namespace: String,
+    connection: Mutex<Connection>,
+}
+
+impl SqliteStorage {
+    /// Opens the database file at `path`, creating it and the table of oracle events if missing.
+    /// The oracle events are stored as `namespace`
+    pub fn open(path: &Path, namespace: String) -> Result<SqliteStorage> {
+        info!("opening SQLite database at {}", path.display());
+        let connection = Connection::open(path)?;
+        connection.busy_timeout(BUSY_TIMEOUT)?;
+        // lets readers of the file, e.g. the API of another instance, proceed while writing
+        connection.pragma_update_and_check(None, "journal_mode", "WAL", |_| Ok(()))?;
+        connection.execute_batch(SCHEMA)?;
+        Ok(SqliteStorage {
+            namespace,
+            connection: Mutex::new(connection),
+        })
+    }
+
+    fn connection(&self) -> std::sync::MutexGuard<'_, Connection> {
+        self.connection.lock().unwrap()
+    }
+}
+
+impl EventStorage for SqliteStorage {
+    fn get(&self, maturation: &str) -> Result<Option<Vec<u8>>> {
+        Ok(self
+            .connection()
+            .query_row(
+                "SELECT event FROM oracle_events WHERE namespace = ?1 AND maturation = ?2",
+                params![self.namespace, maturation],
+                |row| row.get(0),
+            )
+            .optional()?)
+    }
+
+    fn event_maturation(&self, event_id: &str) -> Result<Option<String>> {
+        Ok(self
+            .connection()
+            .query_row(
+                "SELECT maturation FROM oracle_events WHERE namespace = ?1 AND event_id = ?2",
+                params![self.namespace, event_id],
+                |row| row.get(0),
+            )
+            .optional()?)
+    }
+
+    fn insert(&self, maturation: &str, event_id: &str, event: &[u8]) -> Result<bool> {
+        Ok(self.connection().execute(
+            "INSERT OR IGNORE INTO oracle_events (namespace, maturation, event_id, event)
+                VALUES (?1, ?2, ?3, ?4)",
+            params![self.namespace, maturation, event_id, event],
+        )? == 1)
+    }
+
+    fn update(&self, maturation: &str, current: &[u8], new: &[u8]) -> Result<bool> {
+        Ok(self.connection().execute(
+            "UPDATE oracle_events SET event = ?4
+                WHERE namespace = ?1 AND maturation = ?2 AND event = ?3",
+            params![self.namespace, maturation, current, new],
+        )? == 1)
+    }
+
+    fn remove(&self, maturation: &str, _event_id: &str, current: &[u8]) -> Result<bool> {
+        Ok(self.connection().execute(
+            "DELETE FROM oracle_events
+                WHERE namespace = ?1 AND maturation = ?2 AND event = ?3",
+            params![self.namespace, maturation, current],
+        )? == 1)
+    }
+
+    fn range(&self, range: (Bound<String>, Bound<String>)) -> Result<Events<'_>> {
+        let mut query =
+            "SELECT maturation, event FROM oracle_events WHERE namespace = ?1".to_string();
+        let mut params = vec![&self.namespace];
+        for (bound, inclusive, exclusive) in [(&range.0, ">=", ">"), (&range.1, "<=", "<")] {
+            let (operator, maturation) = match bound {
+                Bound::Included(maturation) => (inclusive, maturation),
+                Bound::Excluded(maturation) => (exclusive, maturation),
+                Bound::Unbounded => continue,
+            };
+            params.push(maturation);
+            query += &format!(" AND maturation {operator} ?{}", params.len());
+        }
+        query += " ORDER BY maturation";
+        let connection = self.connection();
+        let mut statement = connection.prepare(&query)?;
+        let events = statement
+            .query_map(params_from_iter(params), |row| {
+                Ok((row.get(0)?, row.get(1)?))
+            })?
+            .map(|event| event.map_err(StorageError::from))
+            .collect::<Vec<Result<(String, Vec<u8>)>>>();
+        Ok(Box::new(events.into_iter()))
+    }
+
+    fn record_signed_nonces(&self, nonces: &[[u8; 32]], outcomes: &[String]) -> Result<bool> {
+        let mut connection = self.connection();
+        // takes the write lock up front, so other instances record their nonces after these
+        let transaction = connection.transaction_with_behavior(TransactionBehavior::Immediate)?;
+        for (nonce, outcome) in nonces.iter().zip(outcomes) {
+            transaction.execute(
+                "INSERT OR IGNORE INTO signed_nonces (nonce, namespace, outcome)
+                    VALUES (?1, ?2, ?3)",
+                params![nonce.as_slice(), self.namespace, outcome],
+            )?;
+            let signed: String = transaction.query_row(
+                "SELECT outcome FROM signed_nonces WHERE nonce = ?1",
+                params![nonce.as_slice()],
+                |row| row.get(0),
+            )?;
+            if signed != *outcome {
+                // rolled back once dropped
+                return Ok(false);
+            }
+        }
+        transaction.commit()?;
+        Ok(true)
+    }
+
+    fn has_signed_nonces(&self) -> Result<bool> {
+        Ok(self.connection().query_row(
+            "SELECT EXISTS (SELECT 1 FROM signed_nonces WHERE namespace = ?1)",
+            params![self.namespace],
+            |row| row.get(0),
+        )?)
+    }
+
+    fn size_on_disk(&self) -> Result<u64> {
+        let size: i64 = self.connection().query_row(
+            "SELECT COALESCE(SUM(LENGTH(maturation) + LENGTH(event_id) + LENGTH(event)), 0)
+                FROM oracle_events WHERE namespace = ?1",
+            params![self.namespace],
+            |row| row.get(0),
+        )?;
+        Ok(size as u64)
+    }
+
+    fn flush(&self) -> Result<()> {
+        // every statement is committed once it returns
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn events_are_kept_apart_by_namespace() {
+        let path = std::env::temp_dir().join(format!("sibyls-{}.sqlite", std::process::id()));
+        let btcusd = SqliteStorage::open(&path, "BTCUSD".to_string()).unwrap();
+        let daily = SqliteStorage::open(&path, "BTCUSD-daily".to_string()).unwrap();
+        let first = "2022-06-07T08:00:00Z";
+        let second = "2022-06-08T08:00:00Z";
+        assert!(btcusd.insert(first, "first", b"announced").unwrap());
+        assert!(btcusd.insert(second, "", b"announced").unwrap());
+        assert!(!btcusd.insert(first, "first", b"again").unwrap());
+        assert!(daily.insert(first, "daily", b"announced").unwrap());
+        assert_eq!(
+            Some(first.to_string()),
+            btcusd.event_maturation("first").unwrap()
+        );
+        assert_eq!(None, daily.event_maturation("first").unwrap());
+
+        assert!(!btcusd.update(first, b"again", b"attested").unwrap());
+        assert!(btcusd.update(first, b"announced", b"attested").unwrap());
+        assert_eq!(Some(b"announced".to_vec()), daily.get(first).unwrap());
+        assert_eq!(
+            vec![(second.to_string(), b"announced".to_vec())],
+            btcusd
+                .range((Bound::Excluded(first.to_string()), Bound::Unbounded))
+                .unwrap()
+                .map(Result::unwrap)
+                .collect::<Vec<_>>()
+        );
+        assert_eq!(
+            vec![second.to_string(), first.to_string()],
+            btcusd
+                .iter()
+                .unwrap()
+                .rev()
+                .map(|entry| entry.unwrap().0)
+                .collect::<Vec<_>>()
+        );
+
+        assert!(!daily.remove(first, "daily", b"attested").unwrap());
+        assert!(daily.remove(first, "daily", b"announced").unwrap());
+        assert!(daily.is_empty().unwrap());
+        assert!(!btcusd.is_empty().unwrap());
+
+        // nonces are shared by all namespaces
+        assert!(btcusd
+            .record_signed_nonces(&[[1; 32]], &["1".to_string()])
+            .unwrap());
+        assert!(!daily
+            .record_signed_nonces(&[[1; 32]], &["2".to_string()])
+            .unwrap());
+        assert!(btcusd.has_signed_nonces().unwrap());
+        assert!(!daily.has_signed_nonces().unwrap());
+        drop((btcusd, daily));
+        std::fs::remove_file(&path).unwrap();
+    }
+}
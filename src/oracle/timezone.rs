@@ -0,0 +1,107 @@
+//! Conversion between instants and wall-clock times in the timezone of a schedule, so events keep
+//! their local time across daylight saving time transitions
+
+use chrono::{LocalResult, NaiveDate, NaiveDateTime, Offset, TimeZone};
+use chrono_tz::Tz;
+use time::{Duration, OffsetDateTime, PrimitiveDateTime, UtcOffset};
+
+fn naive(date_time: PrimitiveDateTime) -> NaiveDateTime {
+    NaiveDate::from_ymd_opt(
+        date_time.year(),
+        date_time.month() as u32,
+        date_time.day().into(),
+    )
+    .and_then(|date| {
+        date.and_hms_nano_opt(
+            date_time.hour().into(),
+            date_time.minute().into(),
+            date_time.second().into(),
+            date_time.nanosecond(),
+        )
+    })
+    .expect("date time to be in the range of chrono")
+}
+
+fn offset_seconds(offset: impl Offset) -> Duration {
+    Duration::seconds(offset.fix().local_minus_utc().into())
+}
+
+/// Returns the wall-clock time in `timezone` at `instant`
+pub fn to_local(timezone: Tz, instant: OffsetDateTime) -> PrimitiveDateTime {
+    let instant = instant.to_offset(UtcOffset::UTC);
+    let utc = PrimitiveDateTime::new(instant.date(), instant.time());
+    utc + offset_seconds(timezone.offset_from_utc_datetime(&naive(utc)))
+}
+
+/// Returns the instants at which `timezone` shows wall-clock time `local`, in chronological
+/// order: twice if it is repeated as clocks are turned back, and once moved forward by the
+/// transition if it is skipped as clocks are turned forward
+pub fn from_local(timezone: Tz, local: PrimitiveDateTime) -> Vec<OffsetDateTime> {
+    let at_offset = |offset| (local - offset_seconds(offset)).assume_utc();
+    match timezone.offset_from_local_datetime(&naive(local)) {
+        LocalResult::Single(offset) => vec![at_offset(offset)],
+        LocalResult::Ambiguous(earliest, latest) => {
+            let mut instants = vec![at_offset(earliest), at_offset(latest)];
+            instants.sort();
+            instants
+        }
+        // skipped, so read with the offset before the transition, which lies less than a day
+        // earlier
+        LocalResult::None => {
+            let before = local.assume_utc() - Duration::DAY;
+            let offset = timezone.offset_from_utc_datetime(&naive(PrimitiveDateTime::new(
+                before.date(),
+                before.time(),
+            )));
+            vec![at_offset(offset)]
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use time::macros::datetime;
+
+    #[test]
+    fn local_times_across_dst_transitions() {
+        let new_york = Tz::America__New_York;
+        assert_eq!(
+            datetime!(2022-06-07 17:00),
+            to_local(new_york, datetime!(2022-06-07 21:00 UTC))
+        );
+        assert_eq!(
+            datetime!(2022-12-07 17:00),
+            to_local(new_york, datetime!(2022-12-07 22:00 UTC))
+        );
+        assert_eq!(
+            vec![datetime!(2022-06-07 21:00 UTC)],
+            from_local(new_york, datetime!(2022-06-07 17:00))
+        );
+        assert_eq!(
+            vec![datetime!(2022-12-07 22:00 UTC)],
+            from_local(new_york, datetime!(2022-12-07 17:00))
+        );
+        // clocks are turned forward from 02:00 to 03:00 on 2022-03-13
+        assert_eq!(
+            vec![datetime!(2022-03-13 07:30 UTC)],
+            from_local(new_york, datetime!(2022-03-13 02:30))
+        );
+        // and back from 02:00 to 01:00 on 2022-11-06
+        assert_eq!(
+            vec![
+                datetime!(2022-11-06 05:30 UTC),
+                datetime!(2022-11-06 06:30 UTC)
+            ],
+            from_local(new_york, datetime!(2022-11-06 01:30))
+        );
+        assert_eq!(
+            vec![datetime!(2022-11-06 08:00 UTC)],
+            from_local(new_york, datetime!(2022-11-06 03:00))
+        );
+        assert_eq!(
+            datetime!(2022-06-07 08:00),
+            to_local(Tz::UTC, datetime!(2022-06-07 08:00 UTC))
+        );
+    }
+}
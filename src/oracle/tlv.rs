@@ -0,0 +1,137 @@
+//! TLV encoding of oracle announcements and attestations as specified by the DLC specification
+//! v0, with the messages of rust-dlc that downstream wallets parse them with
+
+use displaydoc::Display;
+use dlc_messages::ser_impls::{read_as_tlv, write_as_tlv};
+use lightning::{
+    ln::{msgs::DecodeError, wire::Type},
+    util::ser::{Readable, Writeable},
+};
+use std::io::Cursor;
+use thiserror::Error;
+
+#[derive(Debug, Display, Error)]
+pub enum TlvError {
+    /// invalid TLV: {0:?}
+    DecodeError(DecodeError),
+
+    /// {0} bytes left after the TLV
+    TrailingBytesError(usize),
+
+    /// TLV not canonically encoded
+    NonCanonicalError,
+}
+
+/// Encodes `message` as TLV
+pub fn encode<T: Type + Writeable>(message: &T) -> Vec<u8> {
+    let mut bytes = Vec::new();
+    write_as_tlv(message, &mut bytes).expect("writing to a vector to succeed");
+    bytes
+}
+
+/// Decodes a TLV spanning all of `bytes`. Encodings that would not be encoded back to the same
+/// bytes, like another type or a non-minimal length, are refused, since signatures and hashes
+/// of the TLV would not match those computed by other implementations.
+pub fn decode<T: Type + Readable + Writeable>(bytes: &[u8]) -> Result<T, TlvError> {
+    let mut cursor = Cursor::new(bytes);
+    let message = read_as_tlv(&mut cursor).map_err(TlvError::DecodeError)?;
+    let trailing = bytes.len() - cursor.position() as usize;
+    if trailing > 0 {
+        return Err(TlvError::TrailingBytesError(trailing));
+    }
+    if encode(&message) != bytes {
+        return Err(TlvError::NonCanonicalError);
+    }
+    Ok(message)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        oracle::oracle_scheduler::{build_attestation, build_event_announcement},
+        AssetPair, SigningVersion,
+    };
+    use dlc_messages::oracle_msgs::{
+        DigitDecompositionEventDescriptor, EnumEventDescriptor, EventDescriptor,
+        OracleAnnouncement, OracleAttestation,
+    };
+    use secp256k1_zkp::{KeyPair, Secp256k1, SecretKey};
+
+    fn event_descriptors() -> Vec<EventDescriptor> {
+        let numeric = |base, is_signed, nb_digits| {
+            EventDescriptor::DigitDecompositionEvent(DigitDecompositionEventDescriptor {
+                base,
+                is_signed,
+                unit: "BTCUSD".to_string(),
+                precision: 0,
+                nb_digits,
+            })
+        };
+        vec![
+            numeric(2, false, 18),
+            numeric(10, true, 5),
+            EventDescriptor::EnumEvent(EnumEventDescriptor {
+                outcomes: vec!["yes".to_string(), "no".to_string()],
+            }),
+        ]
+    }
+
+    #[test]
+    fn tlvs_round_trip() {
+        let secp = Secp256k1::new();
+        let keypair = KeyPair::from_secret_key(&secp, &SecretKey::from_slice(&[1; 32]).unwrap());
+        let maturation = time::macros::datetime!(2022-06-07 08:00 UTC);
+        for event_descriptor in event_descriptors() {
+            for signing_version in [SigningVersion::Basic, SigningVersion::DLCv0] {
+                let (announcement, sk_nonces) = build_event_announcement(
+                    event_descriptor.clone(),
+                    "btcusd1654588800".to_string(),
+                    AssetPair::BTCUSD,
+                    &keypair,
+                    &secp,
+                    maturation,
+                    signing_version,
+                )
+                .unwrap();
+                let bytes = encode(&announcement);
+                assert_eq!(announcement, decode::<OracleAnnouncement>(&bytes).unwrap());
+
+                let outcomes = vec!["1".to_string(); sk_nonces.len()];
+                let attestation =
+                    build_attestation(&sk_nonces, &keypair, &secp, outcomes, signing_version);
+                let bytes = encode(&attestation);
+                assert_eq!(attestation, decode::<OracleAttestation>(&bytes).unwrap());
+            }
+        }
+    }
+
+    #[test]
+    fn invalid_tlvs_are_refused() {
+        let secp = Secp256k1::new();
+        let keypair = KeyPair::from_secret_key(&secp, &SecretKey::from_slice(&[1; 32]).unwrap());
+        let (announcement, _) = build_event_announcement(
+            event_descriptors().remove(0),
+            String::new(),
+            AssetPair::BTCUSD,
+            &keypair,
+            &secp,
+            time::macros::datetime!(2022-06-07 08:00 UTC),
+            SigningVersion::Basic,
+        )
+        .unwrap();
+        let bytes = encode(&announcement);
+
+        let mut trailing = bytes.clone();
+        trailing.push(0);
+        assert!(matches!(
+            decode::<OracleAnnouncement>(&trailing),
+            Err(TlvError::TrailingBytesError(1))
+        ));
+        assert!(matches!(
+            decode::<OracleAnnouncement>(&bytes[..bytes.len() - 1]),
+            Err(TlvError::DecodeError(_))
+        ));
+        assert!(decode::<OracleAttestation>(&bytes).is_err());
+    }
+}
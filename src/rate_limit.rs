@@ -0,0 +1,60 @@
+use actix_web::{
+    body::{EitherBody, MessageBody},
+    dev::{ServiceRequest, ServiceResponse},
+    http::header,
+    middleware::Next,
+    web, HttpResponse,
+};
+use governor::{
+    clock::{Clock, DefaultClock},
+    DefaultKeyedRateLimiter, Quota,
+};
+use std::{net::IpAddr, num::NonZeroU32, time::Duration};
+
+pub type RateLimiter = DefaultKeyedRateLimiter<IpAddr>;
+
+/// Time between two cleanups of the state the [`RateLimiter`] keeps per client IP
+const CLEANUP_INTERVAL: Duration = Duration::from_secs(60);
+
+pub fn rate_limiter(requests_per_minute: NonZeroU32) -> RateLimiter {
+    RateLimiter::keyed(Quota::per_minute(requests_per_minute))
+}
+
+/// Periodically forgets the client IPs of `limiter` whose quota is replenished, which it would
+/// otherwise keep for every client ever seen
+pub async fn clean_up(limiter: web::Data<RateLimiter>) {
+    let mut interval = tokio::time::interval(CLEANUP_INTERVAL);
+    loop {
+        interval.tick().await;
+        limiter.retain_recent();
+        limiter.shrink_to_fit();
+    }
+}
+
+/// Rejects requests with `429 Too Many Requests` once a client IP exceeds the quota of the
+/// [`RateLimiter`] registered as app data; requests pass through if none is registered. Clients
+/// are told apart by the peer address of the connection, so those behind a reverse proxy or
+/// Tor share the quota of the proxy, and requests over a unix socket are never limited
+pub async fn rate_limit(
+    req: ServiceRequest,
+    next: Next<impl MessageBody>,
+) -> Result<ServiceResponse<EitherBody<impl MessageBody>>, actix_web::Error> {
+    let limiter = req.app_data::<web::Data<RateLimiter>>().cloned();
+    if let (Some(limiter), Some(peer_addr)) = (limiter, req.peer_addr()) {
+        if let Err(not_until) = limiter.check_key(&peer_addr.ip()) {
+            let retry_after = not_until.wait_time_from(DefaultClock::default().now());
+            info!(
+                "rate limiting {} for {}s",
+                peer_addr.ip(),
+                retry_after.as_secs()
+            );
+            let response = HttpResponse::TooManyRequests()
+                .insert_header((header::RETRY_AFTER, retry_after.as_secs() + 1))
+                .finish();
+            return Ok(req.into_response(response).map_into_right_body());
+        }
+    }
+    next.call(req)
+        .await
+        .map(ServiceResponse::map_into_left_body)
+}
@@ -0,0 +1,170 @@
+use actix_web::{post, web, HttpResponse};
+use hex::ToHex;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sibyls::{oracle::Oracle, AssetPair};
+use std::collections::HashMap;
+
+use crate::{error::SibylsError, get_announcement, list_announcements, Filters, Format};
+
+const JSON_RPC_VERSION: &str = "2.0";
+
+const PARSE_ERROR: i64 = -32700;
+const INVALID_REQUEST: i64 = -32600;
+const METHOD_NOT_FOUND: i64 = -32601;
+const INVALID_PARAMS: i64 = -32602;
+const SERVER_ERROR: i64 = -32000;
+
+#[derive(Debug, Deserialize)]
+struct RpcRequest {
+    jsonrpc: String,
+    method: String,
+    #[serde(default)]
+    params: Value,
+    id: Option<Value>,
+}
+
+#[derive(Debug, Serialize)]
+struct RpcResponse {
+    jsonrpc: &'static str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    result: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<RpcError>,
+    id: Value,
+}
+
+#[derive(Debug, Serialize)]
+struct RpcError {
+    code: i64,
+    message: String,
+}
+
+impl RpcError {
+    fn new(code: i64, message: impl ToString) -> Self {
+        RpcError {
+            code,
+            message: message.to_string(),
+        }
+    }
+}
+
+impl From<SibylsError> for RpcError {
+    fn from(err: SibylsError) -> Self {
+        RpcError::new(SERVER_ERROR, err)
+    }
+}
+
+/// `getannouncement` params, either `[maturation, asset_pair?]` or
+/// `{"maturation": ..., "assetPair": ...}`
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+enum GetAnnouncementParams {
+    ByPosition(String, AssetPair),
+    MaturationByPosition((String,)),
+    ByName {
+        maturation: String,
+        #[serde(default, rename = "assetPair", alias = "asset_pair")]
+        asset_pair: Option<AssetPair>,
+    },
+}
+
+fn params<T: for<'de> Deserialize<'de>>(params: Value) -> Result<T, RpcError> {
+    serde_json::from_value(params).map_err(|err| RpcError::new(INVALID_PARAMS, err))
+}
+
+fn dispatch(
+    oracles: &HashMap<AssetPair, Oracle>,
+    method: &str,
+    params_value: Value,
+) -> Result<Value, RpcError> {
+    match method {
+        "getannouncement" => {
+            let (maturation, asset_pair) = match params(params_value)? {
+                GetAnnouncementParams::ByPosition(maturation, asset_pair) => {
+                    (maturation, asset_pair)
+                }
+                GetAnnouncementParams::MaturationByPosition((maturation,)) => {
+                    (maturation, AssetPair::BTCUSD)
+                }
+                GetAnnouncementParams::ByName {
+                    maturation,
+                    asset_pair,
+                } => (maturation, asset_pair.unwrap_or(AssetPair::BTCUSD)),
+            };
+            let event = get_announcement(oracles, asset_pair, &maturation, Format::Hex)?;
+            Ok(serde_json::to_value(event).unwrap())
+        }
+        "listannouncements" => {
+            let filters: Filters = match params_value {
+                Value::Null => Filters::default(),
+                params_value => params(params_value)?,
+            };
+            let events = list_announcements(oracles, &filters)?.events;
+            Ok(serde_json::to_value(events).unwrap())
+        }
+        "getpubkey" => {
+            let oracle = oracles
+                .values()
+                .next()
+                .ok_or(SibylsError::NoAssetPairsError)?;
+            Ok(Value::String(
+                oracle.public_key().serialize().encode_hex::<String>(),
+            ))
+        }
+        _ => Err(RpcError::new(
+            METHOD_NOT_FOUND,
+            format!("method {method} not found"),
+        )),
+    }
+}
+
+fn respond(id: Value, result: Result<Value, RpcError>) -> HttpResponse {
+    let (result, error) = match result {
+        Ok(result) => (Some(result), None),
+        Err(error) => (None, Some(error)),
+    };
+    HttpResponse::Ok().json(RpcResponse {
+        jsonrpc: JSON_RPC_VERSION,
+        result,
+        error,
+        id,
+    })
+}
+
+#[post("/rpc")]
+async fn rpc(
+    oracles: web::Data<HashMap<AssetPair, Oracle>>,
+    body: web::Bytes,
+) -> actix_web::Result<HttpResponse, actix_web::Error> {
+    let request: Value = match serde_json::from_slice(&body) {
+        Ok(request) => request,
+        Err(err) => return Ok(respond(Value::Null, Err(RpcError::new(PARSE_ERROR, err)))),
+    };
+    let request: RpcRequest = match serde_json::from_value(request) {
+        Ok(request) => request,
+        Err(err) => {
+            return Ok(respond(
+                Value::Null,
+                Err(RpcError::new(INVALID_REQUEST, err)),
+            ))
+        }
+    };
+    info!("POST /rpc: {:#?}", request);
+    if request.jsonrpc != JSON_RPC_VERSION {
+        return Ok(respond(
+            request.id.unwrap_or(Value::Null),
+            Err(RpcError::new(
+                INVALID_REQUEST,
+                format!("unsupported jsonrpc version {}", request.jsonrpc),
+            )),
+        ));
+    }
+
+    let result = dispatch(&oracles, &request.method, request.params);
+    match request.id {
+        // notifications are not answered
+        None => Ok(HttpResponse::NoContent().finish()),
+        Some(id) => Ok(respond(id, result)),
+    }
+}
@@ -0,0 +1,111 @@
+use anyhow::{anyhow, bail, Context};
+use sibyls::TorConfig;
+use std::{fs, path::Path};
+use tokio::{
+    io::{AsyncBufReadExt, AsyncWriteExt, BufReader},
+    net::TcpStream,
+};
+
+/// Sends `command` to the Tor control port, returning the lines of a successful reply
+async fn command(control: &mut BufReader<TcpStream>, command: &str) -> anyhow::Result<Vec<String>> {
+    control
+        .get_mut()
+        .write_all(format!("{command}\r\n").as_bytes())
+        .await?;
+    let mut reply = Vec::new();
+    loop {
+        let mut line = String::new();
+        if control.read_line(&mut line).await? == 0 {
+            bail!("Tor control connection closed");
+        }
+        let line = line.trim_end().to_string();
+        // the last line of a reply has a space after the status code, the others a dash
+        let last = line.as_bytes().get(3) == Some(&b' ');
+        if !line.starts_with("250") {
+            bail!("Tor control command failed: {line}");
+        }
+        reply.push(line.get(4..).unwrap_or_default().to_string());
+        if last {
+            return Ok(reply);
+        }
+    }
+}
+
+async fn authenticate(
+    control: &mut BufReader<TcpStream>,
+    tor_config: &TorConfig,
+) -> anyhow::Result<()> {
+    let credentials = match (&tor_config.control_password, &tor_config.cookie_file) {
+        (Some(password), _) => format!("\"{}\"", password.0.replace('"', "\\\"")),
+        (None, Some(cookie_file)) => hex::encode(
+            fs::read(cookie_file)
+                .with_context(|| format!("cannot read {}", cookie_file.display()))?,
+        ),
+        (None, None) => String::new(),
+    };
+    command(control, &format!("AUTHENTICATE {credentials}")).await?;
+    Ok(())
+}
+
+/// Publishes a v3 onion service forwarding `tor_config.onion_port` to `target`, which is either
+/// `host:port` or `unix:/path`, and returns its onion address. The onion service is removed by
+/// Tor once the control connection closes, so it is kept open in the background.
+pub async fn publish_onion_service(tor_config: &TorConfig, target: &str) -> anyhow::Result<String> {
+    let stream = TcpStream::connect(tor_config.control_address)
+        .await
+        .with_context(|| {
+            format!(
+                "cannot connect to Tor control port at {}",
+                tor_config.control_address
+            )
+        })?;
+    let mut control = BufReader::new(stream);
+    authenticate(&mut control, tor_config).await?;
+
+    let key = match &tor_config.key_file {
+        Some(key_file) if key_file.exists() => fs::read_to_string(key_file)
+            .with_context(|| format!("cannot read {}", key_file.display()))?
+            .trim()
+            .to_string(),
+        _ => "NEW:ED25519-V3".to_string(),
+    };
+    let reply = command(
+        &mut control,
+        &format!("ADD_ONION {key} Port={},{target}", tor_config.onion_port),
+    )
+    .await?;
+
+    let service_id = reply
+        .iter()
+        .find_map(|line| line.strip_prefix("ServiceID="))
+        .ok_or_else(|| anyhow!("Tor did not return the onion service id"))?
+        .to_string();
+    if let (Some(key_file), Some(private_key)) = (
+        &tor_config.key_file,
+        reply
+            .iter()
+            .find_map(|line| line.strip_prefix("PrivateKey=")),
+    ) {
+        info!("storing onion service key at {}", key_file.display());
+        write_key(key_file, private_key)?;
+    }
+
+    tokio::spawn(async move {
+        // Tor never sends anything unsolicited, reading just waits for it to hang up
+        let mut line = String::new();
+        let _ = control.read_line(&mut line).await;
+        error!("Tor control connection closed, onion service is no longer published");
+    });
+    Ok(format!("{service_id}.onion"))
+}
+
+fn write_key(key_file: &Path, private_key: &str) -> anyhow::Result<()> {
+    fs::write(key_file, private_key)
+        .with_context(|| format!("cannot write {}", key_file.display()))?;
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        fs::set_permissions(key_file, fs::Permissions::from_mode(0o600))?;
+    }
+    Ok(())
+}
@@ -0,0 +1,178 @@
+//! Announcements and attestations as parsed and verified by rust-dlc, which most DLC wallets
+//! are built on. Any incompatibility would leave contracts unable to settle.
+
+use dlc::OracleInfo;
+use dlc_messages::{
+    oracle_msgs::{
+        DigitDecompositionEventDescriptor, EnumEventDescriptor, EventDescriptor,
+        OracleAnnouncement, OracleAttestation,
+    },
+    ser_impls::{read_as_tlv, write_as_tlv},
+};
+use secp256k1::Scalar;
+use secp256k1_zkp::{hashes::sha256, KeyPair, Message, PublicKey, Secp256k1, SecretKey};
+use sibyls::{
+    build_attestation,
+    oracle::{oracle_scheduler::build_event_announcement, tlv},
+    AssetPair, SigningVersion,
+};
+use std::io::Cursor;
+use time::macros::datetime;
+
+fn keypair(secp: &Secp256k1<secp256k1_zkp::All>) -> KeyPair {
+    KeyPair::from_secret_key(secp, &SecretKey::from_slice(&[7; 32]).unwrap())
+}
+
+/// Announces an event with `event_descriptor` and attests it with `outcomes`
+fn announce_and_attest(
+    event_descriptor: EventDescriptor,
+    outcomes: &[&str],
+) -> (OracleAnnouncement, OracleAttestation) {
+    let secp = Secp256k1::new();
+    let keypair = keypair(&secp);
+    let (announcement, sk_nonces) = build_event_announcement(
+        event_descriptor,
+        "btcusd1654588800".to_string(),
+        AssetPair::BTCUSD,
+        &keypair,
+        &secp,
+        datetime!(2022-06-07 08:00 UTC),
+        SigningVersion::Basic,
+    )
+    .unwrap();
+    let attestation = build_attestation(
+        &sk_nonces,
+        &keypair,
+        &secp,
+        outcomes.iter().map(ToString::to_string).collect(),
+        SigningVersion::Basic,
+    );
+    (announcement, attestation)
+}
+
+fn numeric_event() -> EventDescriptor {
+    EventDescriptor::DigitDecompositionEvent(DigitDecompositionEventDescriptor {
+        base: 2,
+        is_signed: false,
+        unit: "BTCUSD".to_string(),
+        precision: 0,
+        nb_digits: 5,
+    })
+}
+
+fn enum_event() -> EventDescriptor {
+    EventDescriptor::EnumEvent(EnumEventDescriptor {
+        outcomes: vec!["yes".to_string(), "no".to_string(), "void".to_string()],
+    })
+}
+
+/// Checks that rust-dlc reads the TLVs we encode and writes them back byte for byte
+fn assert_rust_dlc_round_trip(announcement: &OracleAnnouncement, attestation: &OracleAttestation) {
+    let announcement_bytes = tlv::encode(announcement);
+    let read: OracleAnnouncement = read_as_tlv(&mut Cursor::new(&announcement_bytes)).unwrap();
+    let mut written = Vec::new();
+    write_as_tlv(&read, &mut written).unwrap();
+    assert_eq!(announcement_bytes, written);
+
+    let attestation_bytes = tlv::encode(attestation);
+    let read: OracleAttestation = read_as_tlv(&mut Cursor::new(&attestation_bytes)).unwrap();
+    let mut written = Vec::new();
+    write_as_tlv(&read, &mut written).unwrap();
+    assert_eq!(attestation_bytes, written);
+}
+
+/// Checks that the attestation reveals the secret of the adaptor point rust-dlc locks the
+/// contract execution transaction of the attested outcomes with
+fn assert_unlocks_adaptor_point(
+    announcement: &OracleAnnouncement,
+    attestation: &OracleAttestation,
+) {
+    let secp = Secp256k1::new();
+    let adaptor_point = dlc::get_adaptor_point_from_oracle_info(
+        &secp,
+        &[OracleInfo {
+            public_key: announcement.oracle_public_key,
+            nonces: announcement.oracle_event.oracle_nonces.clone(),
+        }],
+        &[attestation
+            .outcomes
+            .iter()
+            .map(|outcome| Message::from_hashed_data::<sha256::Hash>(outcome.as_bytes()))
+            .collect()],
+    )
+    .unwrap();
+
+    let mut s_values = attestation.signatures.iter().map(|signature| {
+        Scalar::from_be_bytes(signature.as_ref()[32..].try_into().unwrap()).unwrap()
+    });
+    let first = SecretKey::from_slice(&s_values.next().unwrap().to_be_bytes()).unwrap();
+    let secret = s_values.fold(first, |secret, s| secret.add_tweak(&s).unwrap());
+    assert_eq!(adaptor_point, PublicKey::from_secret_key(&secp, &secret));
+}
+
+#[test]
+fn numeric_events_comply_with_rust_dlc() {
+    let (announcement, attestation) =
+        announce_and_attest(numeric_event(), &["0", "1", "1", "0", "1"]);
+    announcement.validate(&Secp256k1::new()).unwrap();
+    assert_eq!(
+        announcement.oracle_public_key,
+        attestation.oracle_public_key
+    );
+    // each digit is signed with the nonce announced for it
+    for (signature, nonce) in attestation
+        .signatures
+        .iter()
+        .zip(&announcement.oracle_event.oracle_nonces)
+    {
+        assert_eq!(&signature.as_ref()[..32], &nonce.serialize()[..]);
+    }
+    assert_rust_dlc_round_trip(&announcement, &attestation);
+    assert_unlocks_adaptor_point(&announcement, &attestation);
+}
+
+#[test]
+fn enum_events_comply_with_rust_dlc() {
+    let (announcement, attestation) = announce_and_attest(enum_event(), &["void"]);
+    announcement.validate(&Secp256k1::new()).unwrap();
+    assert_eq!(1, announcement.oracle_event.oracle_nonces.len());
+    assert_rust_dlc_round_trip(&announcement, &attestation);
+    assert_unlocks_adaptor_point(&announcement, &attestation);
+}
+
+#[test]
+fn tlvs_follow_dlc_spec_layout() {
+    let (announcement, attestation) =
+        announce_and_attest(numeric_event(), &["0", "1", "1", "0", "1"]);
+    let bytes = tlv::encode(&announcement);
+    // type 55332 and length as BigSize, then signature, public key and the oracle_event TLV
+    assert_eq!([0xfd, 0xd8, 0x24], bytes[..3]);
+    assert_eq!(
+        bytes.len() - 6,
+        u16::from_be_bytes([bytes[4], bytes[5]]).into()
+    );
+    assert_eq!(
+        announcement.announcement_signature.as_ref()[..],
+        bytes[6..70]
+    );
+    assert_eq!(announcement.oracle_public_key.serialize(), bytes[70..102]);
+    // type 55330 and one byte length of oracle_event, with the nonces and maturity epoch first
+    assert_eq!([0xfd, 0xd8, 0x22], bytes[102..105]);
+    assert_eq!([0, 5], bytes[106..108]);
+    let epoch_offset = 108 + 5 * 32;
+    assert_eq!(
+        1_654_588_800u32.to_be_bytes(),
+        bytes[epoch_offset..epoch_offset + 4]
+    );
+    // digit_decomposition_event_descriptor of type 55306
+    assert_eq!(
+        [0xfd, 0xd8, 0x0a],
+        bytes[epoch_offset + 4..epoch_offset + 7]
+    );
+
+    let bytes = tlv::encode(&attestation);
+    // type 55400, then public key, signatures and outcomes
+    assert_eq!([0xfd, 0xd8, 0x68], bytes[..3]);
+    assert_eq!(attestation.oracle_public_key.serialize(), bytes[6..38]);
+    assert_eq!([0, 5], bytes[38..40]);
+}